@@ -0,0 +1,395 @@
+//! Times the crate's `Vec` against `std::vec::Vec`.
+//!
+//! ```text
+//! cargo bench --bench vec -- [--quick] [--csv <path>] [<filter>]
+//! ```
+//!
+//! Every operation runs on both vectors for several element types and
+//! lengths, and the median time per run is printed as a table. `--csv`
+//! also writes the results to `<path>`, `--quick` takes fewer and shorter
+//! samples, and a filter only runs the operations whose name contains it.
+
+use std::fmt::Write as _;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use std::{env, fs, process};
+
+use rusty_collections::pod::Zeroable;
+use rusty_collections::vec::{self, Vec};
+
+type StdVec<T> = std::vec::Vec<T>;
+
+/// An element type to benchmark with.
+trait Elem: Copy + PartialEq + Zeroable {
+    const NAME: &'static str;
+
+    /// A value derived from `i`. Only `from_index(0)` is zero.
+    fn from_index(i: usize) -> Self;
+}
+
+impl Elem for u8 {
+    const NAME: &'static str = "u8";
+
+    fn from_index(i: usize) -> u8 {
+        i as u8 | (i != 0) as u8
+    }
+}
+
+impl Elem for u32 {
+    const NAME: &'static str = "u32";
+
+    fn from_index(i: usize) -> u32 {
+        i as u32
+    }
+}
+
+impl Elem for u64 {
+    const NAME: &'static str = "u64";
+
+    fn from_index(i: usize) -> u64 {
+        i as u64
+    }
+}
+
+impl Elem for [u64; 4] {
+    const NAME: &'static str = "[u64; 4]";
+
+    fn from_index(i: usize) -> [u64; 4] {
+        [i as u64; 4]
+    }
+}
+
+struct Config {
+    samples: usize,
+    min_time: Duration,
+    filter: Option<String>,
+}
+
+struct Row {
+    op: &'static str,
+    elem: &'static str,
+    elem_size: usize,
+    len: usize,
+    ours: f64,
+    std: f64,
+}
+
+struct Bench<'a> {
+    config: &'a Config,
+    rows: StdVec<Row>,
+}
+
+impl Bench<'_> {
+    // Times `ours` on inputs made by `setup_ours` and `theirs` on inputs
+    // made by `setup_theirs`, and records the median time of one call of
+    // each. Making the inputs is not timed, but dropping what the calls
+    // return is, so each call should consume all of its input.
+    fn run<T: Elem, I1, I2, R1, R2>(
+        &mut self,
+        op: &'static str,
+        len: usize,
+        mut setup_ours: impl FnMut() -> I1,
+        mut ours: impl FnMut(I1) -> R1,
+        mut setup_theirs: impl FnMut() -> I2,
+        mut theirs: impl FnMut(I2) -> R2,
+    ) {
+        if let Some(filter) = &self.config.filter {
+            if !op.contains(filter.as_str()) {
+                return;
+            }
+        }
+        let ours = time(self.config, &mut setup_ours, &mut ours);
+        let std = time(self.config, &mut setup_theirs, &mut theirs);
+        let row = Row { op, elem: T::NAME, elem_size: std::mem::size_of::<T>(), len, ours, std };
+        println!("{}", format_row(&row));
+        self.rows.push(row);
+    }
+}
+
+// The median time of one call of `f`, in nanoseconds. Each sample calls `f`
+// often enough to take at least `min_time`.
+fn time<I, R>(
+    config: &Config,
+    setup: &mut impl FnMut() -> I,
+    f: &mut impl FnMut(I) -> R,
+) -> f64 {
+    let mut sample = |iters: usize| {
+        let inputs: StdVec<I> = (0..iters).map(|_| setup()).collect();
+        let start = Instant::now();
+        for input in inputs {
+            black_box(f(black_box(input)));
+        }
+        start.elapsed()
+    };
+
+    let mut iters = 1;
+    while sample(iters) < config.min_time && iters < 1 << 20 {
+        iters *= 2;
+    }
+    let mut times: StdVec<f64> = (0..config.samples)
+        .map(|_| sample(iters).as_nanos() as f64 / iters as f64)
+        .collect();
+    times.sort_by(|a, b| a.total_cmp(b));
+    times[times.len() / 2]
+}
+
+fn bench_elem<T: Elem>(bench: &mut Bench<'_>, len: usize) {
+    let data: StdVec<T> = (0..len).map(T::from_index).collect();
+    let ours_data = Vec::from(&data[..]);
+    let zero = T::from_index(0);
+    let one = T::from_index(1);
+
+    bench.run::<T, _, _, _, _>(
+        "push",
+        len,
+        || (),
+        |()| {
+            let mut v = Vec::new();
+            for i in 0..len {
+                v.push(T::from_index(i));
+            }
+            v
+        },
+        || (),
+        |()| {
+            let mut v = StdVec::new();
+            for i in 0..len {
+                v.push(T::from_index(i));
+            }
+            v
+        },
+    );
+
+    bench.run::<T, _, _, _, _>(
+        "extend_from_slice",
+        len,
+        || (),
+        |()| {
+            let mut v = Vec::new();
+            v.extend_from_slice(&data);
+            v
+        },
+        || (),
+        |()| {
+            let mut v = StdVec::new();
+            v.extend_from_slice(&data);
+            v
+        },
+    );
+
+    bench.run::<T, _, _, _, _>(
+        "vec! (zero)",
+        len,
+        || (),
+        |()| rusty_collections::vec![zero; len],
+        || (),
+        |()| std::vec![zero; len],
+    );
+
+    bench.run::<T, _, _, _, _>(
+        "from_zeroable_elem (zero)",
+        len,
+        || (),
+        |()| vec::from_zeroable_elem(zero, len),
+        || (),
+        |()| std::vec![zero; len],
+    );
+
+    bench.run::<T, _, _, _, _>(
+        "vec! (non-zero)",
+        len,
+        || (),
+        |()| rusty_collections::vec![one; len],
+        || (),
+        |()| std::vec![one; len],
+    );
+
+    // Removes every seventh element.
+    let retained = T::from_index(3);
+    let sevenths: StdVec<T> = (0..len).map(|i| T::from_index(i % 7)).collect();
+    let ours_sevenths = Vec::from(&sevenths[..]);
+    bench.run::<T, _, _, _, _>(
+        "retain",
+        len,
+        || ours_sevenths.clone(),
+        |mut v| {
+            v.retain(|x| *x != retained);
+            v
+        },
+        || sevenths.clone(),
+        |mut v| {
+            v.retain(|x| *x != retained);
+            v
+        },
+    );
+
+    // Runs of four equal elements.
+    let runs: StdVec<T> = (0..len).map(|i| T::from_index(i / 4)).collect();
+    let ours_runs = Vec::from(&runs[..]);
+    bench.run::<T, _, _, _, _>(
+        "dedup",
+        len,
+        || ours_runs.clone(),
+        |mut v| {
+            v.dedup();
+            v
+        },
+        || runs.clone(),
+        |mut v| {
+            v.dedup();
+            v
+        },
+    );
+
+    // Drains the second quarter.
+    bench.run::<T, _, _, _, _>(
+        "drain",
+        len,
+        || ours_data.clone(),
+        |mut v| {
+            black_box(v.drain(len / 4..len / 2).count());
+            v
+        },
+        || data.clone(),
+        |mut v| {
+            black_box(v.drain(len / 4..len / 2).count());
+            v
+        },
+    );
+
+    // Replaces the second quarter with half as many elements.
+    let replacement = &data[..len / 8];
+    bench.run::<T, _, _, _, _>(
+        "splice",
+        len,
+        || ours_data.clone(),
+        |mut v| {
+            black_box(v.splice(len / 4..len / 2, replacement.iter().copied()).count());
+            v
+        },
+        || data.clone(),
+        |mut v| {
+            black_box(v.splice(len / 4..len / 2, replacement.iter().copied()).count());
+            v
+        },
+    );
+
+    // Collects an `IntoIter` that has been advanced by one, which moves the
+    // elements to the front of the buffer it reuses.
+    bench.run::<T, _, _, _, _>(
+        "collect IntoIter",
+        len,
+        || ours_data.clone(),
+        |v| {
+            let mut iter = v.into_iter();
+            iter.next();
+            iter.collect::<Vec<T>>()
+        },
+        || data.clone(),
+        |v| {
+            let mut iter = v.into_iter();
+            iter.next();
+            iter.collect::<StdVec<T>>()
+        },
+    );
+
+    bench.run::<T, _, _, _, _>(
+        "shrink_to_fit",
+        len,
+        || {
+            let mut v = Vec::with_capacity(2 * len);
+            v.extend_from_slice(&data);
+            v
+        },
+        |mut v| {
+            v.shrink_to_fit();
+            v
+        },
+        || {
+            let mut v = StdVec::with_capacity(2 * len);
+            v.extend_from_slice(&data);
+            v
+        },
+        |mut v| {
+            v.shrink_to_fit();
+            v
+        },
+    );
+}
+
+fn format_row(row: &Row) -> String {
+    format!(
+        "{:<26} {:<9} {:>5} {:>8} {:>14.1} {:>14.1} {:>7.2}",
+        row.op,
+        row.elem,
+        row.elem_size,
+        row.len,
+        row.ours,
+        row.std,
+        row.ours / row.std
+    )
+}
+
+fn to_csv(rows: &[Row]) -> String {
+    let mut csv = String::from("op,elem,elem_size,len,ours_ns,std_ns,ratio\n");
+    for row in rows {
+        writeln!(
+            csv,
+            "{},\"{}\",{},{},{:.1},{:.1},{:.3}",
+            row.op,
+            row.elem,
+            row.elem_size,
+            row.len,
+            row.ours,
+            row.std,
+            row.ours / row.std
+        )
+        .unwrap();
+    }
+    csv
+}
+
+fn usage() -> ! {
+    eprintln!("usage: vec [--quick] [--csv <path>] [<filter>]");
+    process::exit(2);
+}
+
+fn main() {
+    let mut config =
+        Config { samples: 11, min_time: Duration::from_millis(20), filter: None };
+    let mut csv = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            // Passed by `cargo bench`.
+            "--bench" => {}
+            "--quick" => {
+                config.samples = 3;
+                config.min_time = Duration::from_millis(2);
+            }
+            "--csv" => csv = Some(args.next().unwrap_or_else(|| usage())),
+            _ if arg.starts_with('-') => usage(),
+            _ => config.filter = Some(arg),
+        }
+    }
+    let lens: &[usize] = if config.samples < 11 { &[1_000] } else { &[1_000, 100_000] };
+
+    println!(
+        "{:<26} {:<9} {:>5} {:>8} {:>14} {:>14} {:>7}",
+        "op", "elem", "size", "len", "ours (ns)", "std (ns)", "ratio"
+    );
+    let mut bench = Bench { config: &config, rows: StdVec::new() };
+    for &len in lens {
+        bench_elem::<u8>(&mut bench, len);
+        bench_elem::<u32>(&mut bench, len);
+        bench_elem::<u64>(&mut bench, len);
+        bench_elem::<[u64; 4]>(&mut bench, len);
+    }
+
+    if let Some(path) = csv {
+        if let Err(err) = fs::write(&path, to_csv(&bench.rows)) {
+            eprintln!("cannot write {}: {}", path, err);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,1010 @@
+//! Sorting and searching algorithms for slices.
+//!
+//! Every function here works on a plain `&mut [T]` (or `&[T]`), so it also
+//! works on a [`Vec`] through deref:
+//!
+//! ```
+//! use rusty_collections::algorithms;
+//!
+//! let mut v = vec![5, 3, 1, 4, 2];
+//! algorithms::quicksort(&mut v);
+//! assert_eq!(v, [1, 2, 3, 4, 5]);
+//! assert_eq!(algorithms::binary_search(&v, &4), Ok(3));
+//! ```
+//!
+//! Each sort comes in three flavours, following `slice::sort`:
+//!
+//! * `xxx(v)` sorts by [`Ord`],
+//! * `xxx_by(v, compare)` sorts with a comparator returning an [`Ordering`],
+//! * `xxx_by_key(v, f)` sorts by the key `f` extracts. The key function may
+//!   be called more than once per element.
+//!
+//! | Algorithm          | Stable | Time (worst)   | Extra memory  |
+//! |--------------------|--------|----------------|---------------|
+//! | [`insertion_sort`] | yes    | *O*(*n*²)      | none          |
+//! | [`merge_sort`]     | yes    | *O*(*n* log *n*) | *n*/2       |
+//! | [`timsort`]        | yes    | *O*(*n* log *n*) | *n*/2       |
+//! | [`quicksort`]      | no     | *O*(*n* log *n*) | none        |
+//! | [`heapsort`]       | no     | *O*(*n* log *n*) | none        |
+//! | [`radix_sort`]     | yes    | *O*(*n* · *w*) | *n*           |
+//!
+//! [`timsort`] finds the runs that are already in order, so it is *O*(*n*)
+//! on sorted or reversed input. [`quicksort`] is an introsort: it falls back
+//! to [`heapsort`] when partitioning goes badly.
+//!
+//! # Panic safety
+//!
+//! If a comparator or key function panics, the slice is left holding every
+//! element exactly once, in an unspecified order. Nothing is dropped twice
+//! and nothing is leaked.
+
+use core::cmp::Ordering;
+use core::mem::{self, ManuallyDrop};
+use core::ptr;
+
+use crate::vec::Vec;
+
+#[cfg(test)]
+mod tests;
+
+/// Slices of up to this length are insertion sorted by the other sorts.
+const MAX_INSERTION: usize = 20;
+
+////////////////////////////////////////////////////////////////////////////////
+// Insertion sort
+////////////////////////////////////////////////////////////////////////////////
+
+/// Sorts the slice with insertion sort.
+///
+/// This sort is stable and *O*(*n*²) in the worst case, but *O*(*n*) on
+/// input that is already nearly sorted, and the fastest choice for very short
+/// slices.
+pub fn insertion_sort<T: Ord>(v: &mut [T]) {
+    insertion_sort_impl(v, &mut |a: &T, b: &T| a.lt(b));
+}
+
+/// Sorts the slice with insertion sort and a comparator function.
+///
+/// See [`insertion_sort`].
+pub fn insertion_sort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    insertion_sort_impl(v, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less);
+}
+
+/// Sorts the slice with insertion sort and a key extraction function.
+///
+/// See [`insertion_sort`].
+pub fn insertion_sort_by_key<T, K, F>(v: &mut [T], mut f: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    insertion_sort_impl(v, &mut |a: &T, b: &T| f(a).lt(&f(b)));
+}
+
+fn insertion_sort_impl<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    for i in 1..v.len() {
+        insert_tail(&mut v[..=i], is_less);
+    }
+}
+
+/// Inserts `v[v.len() - 1]` into pre-sorted sequence `v[..v.len() - 1]` so that whole `v[..]`
+/// becomes sorted.
+fn insert_tail<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let len = v.len();
+    if len >= 2 && is_less(&v[len - 1], &v[len - 2]) {
+        unsafe {
+            // Read the last element into a stack-allocated variable. If a following comparison
+            // panics, `hole` will get dropped and automatically write the element back into the
+            // slice.
+            let tmp = ManuallyDrop::new(ptr::read(&v[len - 1]));
+            let mut hole = InsertionHole { src: &*tmp, dest: &mut v[len - 2] };
+            ptr::copy_nonoverlapping(&v[len - 2], &mut v[len - 1], 1);
+
+            for i in (0..len - 2).rev() {
+                if !is_less(&*tmp, &v[i]) {
+                    break;
+                }
+
+                // Move `i`-th element one place to the right, thus shifting the hole to the left.
+                ptr::copy_nonoverlapping(&v[i], &mut v[i + 1], 1);
+                hole.dest = &mut v[i];
+            }
+            // `hole` gets dropped and thus copies `tmp` into the remaining hole in `v`.
+        }
+    }
+}
+
+/// Inserts `v[0]` into pre-sorted sequence `v[1..]` so that whole `v[..]` becomes sorted.
+fn insert_head<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    if v.len() >= 2 && is_less(&v[1], &v[0]) {
+        unsafe {
+            // See `insert_tail` for how `hole` keeps this panic safe.
+            let tmp = ManuallyDrop::new(ptr::read(&v[0]));
+            let mut hole = InsertionHole { src: &*tmp, dest: &mut v[1] };
+            ptr::copy_nonoverlapping(&v[1], &mut v[0], 1);
+
+            for i in 2..v.len() {
+                if !is_less(&v[i], &*tmp) {
+                    break;
+                }
+
+                // Move `i`-th element one place to the left, thus shifting the hole to the right.
+                ptr::copy_nonoverlapping(&v[i], &mut v[i - 1], 1);
+                hole.dest = &mut v[i];
+            }
+            // `hole` gets dropped and thus copies `tmp` into the remaining hole in `v`.
+        }
+    }
+}
+
+// When dropped, copies from `src` into `dest`.
+struct InsertionHole<T> {
+    src: *const T,
+    dest: *mut T,
+}
+
+impl<T> Drop for InsertionHole<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::copy_nonoverlapping(self.src, self.dest, 1);
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Merge sort and timsort
+////////////////////////////////////////////////////////////////////////////////
+
+/// Sorts the slice with a top-down merge sort.
+///
+/// This sort is stable and *O*(*n* log *n*) in the worst case. It allocates
+/// a buffer of half the length of `v`.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_collections::algorithms::merge_sort_by_key;
+///
+/// let mut v = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+/// merge_sort_by_key(&mut v, |&(k, _)| k);
+/// assert_eq!(v, [(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+/// ```
+pub fn merge_sort<T: Ord>(v: &mut [T]) {
+    merge_sort_impl(v, &mut |a: &T, b: &T| a.lt(b));
+}
+
+/// Sorts the slice with a top-down merge sort and a comparator function.
+///
+/// See [`merge_sort`].
+pub fn merge_sort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    merge_sort_impl(v, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less);
+}
+
+/// Sorts the slice with a top-down merge sort and a key extraction function.
+///
+/// See [`merge_sort`].
+pub fn merge_sort_by_key<T, K, F>(v: &mut [T], mut f: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    merge_sort_impl(v, &mut |a: &T, b: &T| f(a).lt(&f(b)));
+}
+
+fn merge_sort_impl<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    // Sorting has no meaningful behavior on zero-sized types.
+    if mem::size_of::<T>() == 0 {
+        return;
+    }
+
+    if v.len() <= MAX_INSERTION {
+        insertion_sort_impl(v, is_less);
+        return;
+    }
+
+    // Every merge copies the shorter half into `buf`, which is never longer
+    // than half of `v`.
+    let mut buf = Vec::<T>::with_capacity(v.len() / 2);
+    merge_sort_rec(v, buf.as_mut_ptr(), is_less);
+}
+
+fn merge_sort_rec<T, F>(v: &mut [T], buf: *mut T, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let len = v.len();
+    if len <= MAX_INSERTION {
+        insertion_sort_impl(v, is_less);
+        return;
+    }
+
+    let mid = len / 2;
+    merge_sort_rec(&mut v[..mid], buf, is_less);
+    merge_sort_rec(&mut v[mid..], buf, is_less);
+    // The halves are already in order if their boundary is.
+    if is_less(&v[mid], &v[mid - 1]) {
+        unsafe {
+            merge(v, mid, buf, is_less);
+        }
+    }
+}
+
+/// Sorts the slice with an adaptive, natural merge sort in the style of
+/// TimSort.
+///
+/// This sort is stable and *O*(*n* log *n*) in the worst case. It splits `v`
+/// into runs that are already in order (reversing strictly descending ones),
+/// extends short runs with insertion sort, and merges them while keeping the
+/// run lengths balanced. On input made of a few long runs it is much faster
+/// than [`merge_sort`], and on sorted input it is *O*(*n*).
+///
+/// It allocates a buffer of half the length of `v`.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_collections::algorithms::{is_sorted, timsort};
+///
+/// let mut v: Vec<_> = (0..100).chain(0..100).collect();
+/// timsort(&mut v);
+/// assert!(is_sorted(&v));
+/// ```
+pub fn timsort<T: Ord>(v: &mut [T]) {
+    timsort_impl(v, &mut |a: &T, b: &T| a.lt(b));
+}
+
+/// Sorts the slice with [`timsort`] and a comparator function.
+pub fn timsort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    timsort_impl(v, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less);
+}
+
+/// Sorts the slice with [`timsort`] and a key extraction function.
+pub fn timsort_by_key<T, K, F>(v: &mut [T], mut f: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    timsort_impl(v, &mut |a: &T, b: &T| f(a).lt(&f(b)));
+}
+
+fn timsort_impl<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    // Very short runs are extended using insertion sort to span at least this many elements.
+    const MIN_RUN: usize = 10;
+
+    // Sorting has no meaningful behavior on zero-sized types.
+    if mem::size_of::<T>() == 0 {
+        return;
+    }
+
+    let len = v.len();
+    if len <= MAX_INSERTION {
+        insertion_sort_impl(v, is_less);
+        return;
+    }
+
+    // Very short runs are extended, and merges always copy the shorter run,
+    // so the buffer never needs more than half of `v`.
+    let mut buf = Vec::<T>::with_capacity(len / 2);
+
+    // In order to identify natural runs in `v`, we traverse it backwards. That might seem like a
+    // strange decision, but consider the fact that merges more often go in the opposite direction
+    // (forwards). According to benchmarks, merging forwards is slightly faster than merging
+    // backwards. To conclude, identifying runs by traversing backwards improves performance.
+    let mut runs: Vec<Run> = Vec::new();
+    let mut end = len;
+    while end > 0 {
+        // Find the next natural run, and reverse it if it's strictly descending.
+        let mut start = end - 1;
+        if start > 0 {
+            start -= 1;
+            if is_less(&v[start + 1], &v[start]) {
+                while start > 0 && is_less(&v[start], &v[start - 1]) {
+                    start -= 1;
+                }
+                v[start..end].reverse();
+            } else {
+                while start > 0 && !is_less(&v[start], &v[start - 1]) {
+                    start -= 1;
+                }
+            }
+        }
+
+        // Insert some more elements into the run if it's too short.
+        while start > 0 && end - start < MIN_RUN {
+            start -= 1;
+            insert_head(&mut v[start..end], is_less);
+        }
+
+        // Push this run onto the stack.
+        runs.push(Run { start, len: end - start });
+        end = start;
+
+        // Merge some pairs of adjacent runs to satisfy the invariants.
+        while let Some(r) = collapse(&runs) {
+            let left = runs[r + 1];
+            let right = runs[r];
+            unsafe {
+                merge(
+                    &mut v[left.start..right.start + right.len],
+                    left.len,
+                    buf.as_mut_ptr(),
+                    is_less,
+                );
+            }
+            runs[r] = Run { start: left.start, len: left.len + right.len };
+            runs.remove(r + 1);
+        }
+    }
+
+    // Finally, exactly one run must remain in the stack.
+    debug_assert!(runs.len() == 1 && runs[0].start == 0 && runs[0].len == len);
+
+    // Examines the stack of runs and identifies the next pair of runs to merge. More specifically,
+    // if `Some(r)` is returned, that means `runs[r]` and `runs[r + 1]` must be merged next. If the
+    // algorithm should continue building a new run instead, `None` is returned.
+    //
+    // TimSort is infamous for its buggy implementations, as described here:
+    // http://envisage-project.eu/timsort-specification-and-verification/
+    //
+    // The gist of the story is: we must enforce the invariants on the top four runs on the stack.
+    // Enforcing them on just top three is not sufficient to ensure that the invariants will still
+    // hold for *all* runs in the stack.
+    #[inline]
+    fn collapse(runs: &[Run]) -> Option<usize> {
+        let n = runs.len();
+        if n >= 2
+            && (runs[n - 1].start == 0
+                || runs[n - 2].len <= runs[n - 1].len
+                || (n >= 3 && runs[n - 3].len <= runs[n - 2].len + runs[n - 1].len)
+                || (n >= 4 && runs[n - 4].len <= runs[n - 3].len + runs[n - 2].len))
+        {
+            if n >= 3 && runs[n - 3].len < runs[n - 1].len { Some(n - 3) } else { Some(n - 2) }
+        } else {
+            None
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct Run {
+        start: usize,
+        len: usize,
+    }
+}
+
+/// Merges non-decreasing runs `v[..mid]` and `v[mid..]` using `buf` as temporary storage, and
+/// stores the result into `v[..]`.
+///
+/// # Safety
+///
+/// The two slices must be non-empty and `mid` must be in bounds. Buffer `buf` must be long enough
+/// to hold a copy of the shorter slice. Also, `T` must not be a zero-sized type.
+pub(crate) unsafe fn merge<T, F>(v: &mut [T], mid: usize, buf: *mut T, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let len = v.len();
+    let v = v.as_mut_ptr();
+    let (v_mid, v_end) = unsafe { (v.add(mid), v.add(len)) };
+
+    // The merging process first copies the shorter run into `buf`. Then it traces the newly copied
+    // run and the longer run forwards (or backwards), comparing their next unconsumed elements and
+    // copying the lesser (or greater) one into `v`.
+    //
+    // As soon as the shorter run is fully consumed, the process is done. If the longer run gets
+    // consumed first, then we must copy whatever is left of the shorter run into the remaining
+    // hole in `v`.
+    //
+    // Intermediate state of the process is always tracked by `hole`, which serves two purposes:
+    // 1. Protects integrity of `v` from panics in `is_less`.
+    // 2. Fills the remaining hole in `v` if the longer run gets consumed first.
+    //
+    // Panic safety:
+    //
+    // If `is_less` panics at any point during the process, `hole` will get dropped and fill the
+    // hole in `v` with the unconsumed range in `buf`, thus ensuring that `v` still holds every
+    // object it initially held exactly once.
+    let mut hole;
+
+    if mid <= len - mid {
+        // The left run is shorter.
+        unsafe {
+            ptr::copy_nonoverlapping(v, buf, mid);
+            hole = MergeHole { start: buf, end: buf.add(mid), dest: v };
+        }
+
+        // Initially, these pointers point to the beginnings of their arrays.
+        let left = &mut hole.start;
+        let mut right = v_mid;
+        let out = &mut hole.dest;
+
+        while *left < hole.end && right < v_end {
+            // Consume the lesser side.
+            // If equal, prefer the left run to maintain stability.
+            unsafe {
+                let to_copy = if is_less(&*right, &**left) {
+                    get_and_increment(&mut right)
+                } else {
+                    get_and_increment(left)
+                };
+                ptr::copy_nonoverlapping(to_copy, get_and_increment(out), 1);
+            }
+        }
+    } else {
+        // The right run is shorter.
+        unsafe {
+            ptr::copy_nonoverlapping(v_mid, buf, len - mid);
+            hole = MergeHole { start: buf, end: buf.add(len - mid), dest: v_mid };
+        }
+
+        // Initially, these pointers point past the ends of their arrays.
+        let left = &mut hole.dest;
+        let right = &mut hole.end;
+        let mut out = v_end;
+
+        while v < *left && buf < *right {
+            // Consume the greater side.
+            // If equal, prefer the right run to maintain stability.
+            unsafe {
+                let to_copy = if is_less(&*right.offset(-1), &*left.offset(-1)) {
+                    decrement_and_get(left)
+                } else {
+                    decrement_and_get(right)
+                };
+                ptr::copy_nonoverlapping(to_copy, decrement_and_get(&mut out), 1);
+            }
+        }
+    }
+    // Finally, `hole` gets dropped. If the shorter run was not fully consumed, whatever remains of
+    // it will now be copied into the hole in `v`.
+
+    unsafe fn get_and_increment<T>(ptr: &mut *mut T) -> *mut T {
+        let old = *ptr;
+        *ptr = unsafe { ptr.offset(1) };
+        old
+    }
+
+    unsafe fn decrement_and_get<T>(ptr: &mut *mut T) -> *mut T {
+        *ptr = unsafe { ptr.offset(-1) };
+        *ptr
+    }
+
+    // When dropped, copies the range `start..end` into `dest..`.
+    struct MergeHole<T> {
+        start: *mut T,
+        end: *mut T,
+        dest: *mut T,
+    }
+
+    impl<T> Drop for MergeHole<T> {
+        fn drop(&mut self) {
+            // `T` is not a zero-sized type, so it's okay to divide by its size.
+            let len = (self.end as usize - self.start as usize) / mem::size_of::<T>();
+            unsafe {
+                ptr::copy_nonoverlapping(self.start, self.dest, len);
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Quicksort and heapsort
+////////////////////////////////////////////////////////////////////////////////
+
+/// Sorts the slice with an introsort: quicksort with a median-of-three
+/// pivot, switching to [`heapsort`] when the recursion gets too deep.
+///
+/// This sort is unstable, in-place and *O*(*n* log *n*) in the worst case.
+pub fn quicksort<T: Ord>(v: &mut [T]) {
+    quicksort_impl(v, &mut |a: &T, b: &T| a.lt(b));
+}
+
+/// Sorts the slice with [`quicksort`] and a comparator function.
+pub fn quicksort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quicksort_impl(v, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less);
+}
+
+/// Sorts the slice with [`quicksort`] and a key extraction function.
+pub fn quicksort_by_key<T, K, F>(v: &mut [T], mut f: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    quicksort_impl(v, &mut |a: &T, b: &T| f(a).lt(&f(b)));
+}
+
+fn quicksort_impl<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    // Allow twice the depth a perfectly balanced quicksort would need.
+    let limit = usize::BITS - v.len().leading_zeros();
+    introsort(v, is_less, 2 * limit);
+}
+
+fn introsort<T, F>(mut v: &mut [T], is_less: &mut F, mut limit: u32)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    loop {
+        if v.len() <= MAX_INSERTION {
+            insertion_sort_impl(v, is_less);
+            return;
+        }
+
+        // Too many bad pivot choices, fall back to heapsort to guarantee `O(n log n)`.
+        if limit == 0 {
+            heapsort_impl(v, is_less);
+            return;
+        }
+        limit -= 1;
+
+        let pivot = choose_pivot(v, is_less);
+        v.swap(0, pivot);
+        let mid = partition(v, is_less);
+
+        // Recurse into the shorter side only in order to minimize the total number of recursive
+        // calls and consume less stack space. Then just continue with the longer side (this is
+        // akin to tail recursion).
+        let (left, right) = mem::take(&mut v).split_at_mut(mid);
+        let right = &mut right[1..];
+        if left.len() < right.len() {
+            introsort(left, is_less, limit);
+            v = right;
+        } else {
+            introsort(right, is_less, limit);
+            v = left;
+        }
+    }
+}
+
+// Returns the index of the median of the first, middle and last element.
+fn choose_pivot<T, F>(v: &[T], is_less: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let (mut a, mut b, mut c) = (0, v.len() / 2, v.len() - 1);
+    if is_less(&v[b], &v[a]) {
+        mem::swap(&mut a, &mut b);
+    }
+    if is_less(&v[c], &v[b]) {
+        mem::swap(&mut b, &mut c);
+    }
+    if is_less(&v[b], &v[a]) {
+        mem::swap(&mut a, &mut b);
+    }
+    b
+}
+
+// Partitions `v[1..]` around the pivot `v[0]` and moves the pivot between
+// the two parts. Returns the final position of the pivot: everything before
+// it is less than it, nothing after it is.
+//
+// Only swaps elements, so a panicking `is_less` leaves `v` intact.
+fn partition<T, F>(v: &mut [T], is_less: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let (pivot, rest) = v.split_at_mut(1);
+    let pivot = &pivot[0];
+
+    // Invariant: `rest[..l]` is less than the pivot, `rest[r..]` is not.
+    let mut l = 0;
+    let mut r = rest.len();
+    loop {
+        while l < r && is_less(&rest[l], pivot) {
+            l += 1;
+        }
+        while l < r && !is_less(&rest[r - 1], pivot) {
+            r -= 1;
+        }
+        if l >= r {
+            break;
+        }
+        r -= 1;
+        rest.swap(l, r);
+        l += 1;
+    }
+
+    // `rest[l - 1]` is `v[l]`, the last element less than the pivot.
+    v.swap(0, l);
+    l
+}
+
+/// Sorts the slice with heapsort.
+///
+/// This sort is unstable, in-place and *O*(*n* log *n*) in the worst case.
+pub fn heapsort<T: Ord>(v: &mut [T]) {
+    heapsort_impl(v, &mut |a: &T, b: &T| a.lt(b));
+}
+
+/// Sorts the slice with [`heapsort`] and a comparator function.
+pub fn heapsort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    heapsort_impl(v, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less);
+}
+
+/// Sorts the slice with [`heapsort`] and a key extraction function.
+pub fn heapsort_by_key<T, K, F>(v: &mut [T], mut f: F)
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    heapsort_impl(v, &mut |a: &T, b: &T| f(a).lt(&f(b)));
+}
+
+fn heapsort_impl<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    // This binary heap respects the invariant `parent >= child`.
+    let mut sift_down = |v: &mut [T], mut node| {
+        loop {
+            // Children of `node`:
+            let left = 2 * node + 1;
+            let right = 2 * node + 2;
+
+            // Choose the greater child.
+            let greater =
+                if right < v.len() && is_less(&v[left], &v[right]) { right } else { left };
+
+            // Stop if the invariant holds at `node`.
+            if greater >= v.len() || !is_less(&v[node], &v[greater]) {
+                break;
+            }
+
+            // Swap `node` with the greater child, move one step down, and continue sifting.
+            v.swap(node, greater);
+            node = greater;
+        }
+    };
+
+    // Build the heap in linear time.
+    for i in (0..v.len() / 2).rev() {
+        sift_down(v, i);
+    }
+
+    // Pop maximal elements from the heap.
+    for i in (1..v.len()).rev() {
+        v.swap(0, i);
+        sift_down(&mut v[..i], 0);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Radix sort
+////////////////////////////////////////////////////////////////////////////////
+
+/// Integer keys for [`radix_sort`].
+///
+/// A key is split into `BYTES` digits of one byte each. Comparing the
+/// digits from the most to the least significant as unsigned bytes must give
+/// the same result as comparing the keys.
+pub trait RadixKey: Copy {
+    /// The number of one-byte digits in a key.
+    const BYTES: usize;
+
+    /// Returns digit `i` of the key, where digit 0 is the least significant.
+    fn radix_digit(self, i: usize) -> u8;
+}
+
+macro_rules! radix_key_unsigned {
+    ($($t:ty)*) => {$(
+        impl RadixKey for $t {
+            const BYTES: usize = mem::size_of::<$t>();
+
+            #[inline]
+            fn radix_digit(self, i: usize) -> u8 {
+                (self >> (i * 8)) as u8
+            }
+        }
+    )*}
+}
+
+macro_rules! radix_key_signed {
+    ($($t:ty => $u:ty)*) => {$(
+        impl RadixKey for $t {
+            const BYTES: usize = mem::size_of::<$t>();
+
+            #[inline]
+            fn radix_digit(self, i: usize) -> u8 {
+                // Flipping the sign bit maps `MIN..=MAX` onto `0..=<$u>::MAX`
+                // in order.
+                ((self as $u ^ (1 << (<$u>::BITS - 1))) >> (i * 8)) as u8
+            }
+        }
+    )*}
+}
+
+radix_key_unsigned! { u8 u16 u32 u64 u128 usize }
+radix_key_signed! { i8 => u8 i16 => u16 i32 => u32 i64 => u64 i128 => u128 isize => usize }
+
+/// Sorts a slice of integers with an LSD radix sort.
+///
+/// This sort is stable and takes *O*(*n* · *w*) time, where *w* is the size
+/// of the key in bytes. Passes over digits that are the same in every key
+/// are skipped. It allocates a buffer as long as `v`.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_collections::algorithms::radix_sort;
+///
+/// let mut v = [-5i32, 300, 0, -70000, 12];
+/// radix_sort(&mut v);
+/// assert_eq!(v, [-70000, -5, 0, 12, 300]);
+/// ```
+pub fn radix_sort<K: RadixKey>(v: &mut [K]) {
+    let len = v.len();
+    if len < 2 {
+        return;
+    }
+
+    let mut buf = Vec::with_capacity(len);
+    buf.extend_from_slice(v);
+    for digit in 0..K::BYTES {
+        let mut offsets = [0usize; 256];
+        for key in v.iter() {
+            offsets[key.radix_digit(digit) as usize] += 1;
+        }
+        if offsets.contains(&len) {
+            continue;
+        }
+
+        // Turn the counts into the first output position of each digit.
+        let mut sum = 0;
+        for offset in offsets.iter_mut() {
+            let count = *offset;
+            *offset = sum;
+            sum += count;
+        }
+
+        for &key in v.iter() {
+            let slot = &mut offsets[key.radix_digit(digit) as usize];
+            buf[*slot] = key;
+            *slot += 1;
+        }
+        v.copy_from_slice(&buf);
+    }
+}
+
+/// Sorts the slice with an LSD radix sort on an integer key.
+///
+/// The key function is called exactly once per element, before anything is
+/// moved, so a panicking key function leaves `v` untouched. The elements are
+/// then moved into place with swaps. See [`radix_sort`].
+///
+/// Radix sort does not compare elements, so there is no comparator variant.
+pub fn radix_sort_by_key<T, K, F>(v: &mut [T], f: F)
+where
+    F: FnMut(&T) -> K,
+    K: RadixKey,
+{
+    let len = v.len();
+    if len < 2 {
+        return;
+    }
+
+    let keys: Vec<K> = v.iter().map(f).collect();
+    let mut order: Vec<usize> = (0..len).collect();
+    let mut buf = order.clone();
+    for digit in 0..K::BYTES {
+        let mut offsets = [0usize; 256];
+        for key in keys.iter() {
+            offsets[key.radix_digit(digit) as usize] += 1;
+        }
+        if offsets.contains(&len) {
+            continue;
+        }
+
+        let mut sum = 0;
+        for offset in offsets.iter_mut() {
+            let count = *offset;
+            *offset = sum;
+            sum += count;
+        }
+
+        for &index in order.iter() {
+            let slot = &mut offsets[keys[index].radix_digit(digit) as usize];
+            buf[*slot] = index;
+            *slot += 1;
+        }
+        mem::swap(&mut order, &mut buf);
+    }
+
+    // `order[i]` is the index of the element that belongs at `i`. Walk each
+    // cycle of the permutation, marking finished slots with `order[i] == i`.
+    for start in 0..len {
+        let mut current = start;
+        loop {
+            let next = order[current];
+            order[current] = current;
+            if next == start || next == current {
+                break;
+            }
+            v.swap(current, next);
+            current = next;
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Searching and helpers
+////////////////////////////////////////////////////////////////////////////////
+
+/// Returns the index of the first element for which `pred` returns `false`.
+///
+/// The slice must be partitioned by `pred`: all elements for which it
+/// returns `true` come before all elements for which it returns `false`.
+/// If it is not, the result is unspecified but in bounds.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_collections::algorithms::partition_point;
+///
+/// let v = [1, 2, 3, 3, 5, 6, 7];
+/// assert_eq!(partition_point(&v, |&x| x < 5), 4);
+/// ```
+pub fn partition_point<T, P>(v: &[T], mut pred: P) -> usize
+where
+    P: FnMut(&T) -> bool,
+{
+    let mut lo = 0;
+    let mut hi = v.len();
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(&v[mid]) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// Binary searches a sorted slice for `x`.
+///
+/// Returns `Ok` with the index of the *first* matching element, or `Err`
+/// with the index where `x` could be inserted to keep the slice sorted.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_collections::algorithms::binary_search;
+///
+/// let v = [0, 1, 1, 1, 2, 3, 5];
+/// assert_eq!(binary_search(&v, &1), Ok(1));
+/// assert_eq!(binary_search(&v, &4), Err(6));
+/// ```
+pub fn binary_search<T: Ord>(v: &[T], x: &T) -> Result<usize, usize> {
+    binary_search_by(v, |probe| probe.cmp(x))
+}
+
+/// Binary searches a sorted slice with a comparator function.
+///
+/// `f` returns whether its argument is `Less`, `Equal` or `Greater` than the
+/// target. See [`binary_search`].
+pub fn binary_search_by<T, F>(v: &[T], mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let index = partition_point(v, |probe| f(probe) == Ordering::Less);
+    if index < v.len() && f(&v[index]) == Ordering::Equal { Ok(index) } else { Err(index) }
+}
+
+/// Binary searches a slice sorted by a key extraction function.
+///
+/// See [`binary_search`].
+pub fn binary_search_by_key<T, K, F>(v: &[T], key: &K, mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    binary_search_by(v, |probe| f(probe).cmp(key))
+}
+
+/// Merges two sorted slices into a new sorted vector.
+///
+/// The merge is stable: of two equal elements, the one from `a` comes first.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_collections::algorithms::merge_sorted;
+///
+/// assert_eq!(merge_sorted(&[1, 4, 6], &[2, 3, 7]), [1, 2, 3, 4, 6, 7]);
+/// ```
+pub fn merge_sorted<T: Ord + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    merge_sorted_by(a, b, T::cmp)
+}
+
+/// Merges two slices sorted by a comparator function into a new vector.
+///
+/// See [`merge_sorted`].
+pub fn merge_sorted_by<T, F>(a: &[T], b: &[T], mut compare: F) -> Vec<T>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut out = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if compare(&b[j], &a[i]) == Ordering::Less {
+            out.push(b[j].clone());
+            j += 1;
+        } else {
+            out.push(a[i].clone());
+            i += 1;
+        }
+    }
+    out.extend_from_slice(&a[i..]);
+    out.extend_from_slice(&b[j..]);
+    out
+}
+
+/// Merges two slices sorted by a key extraction function into a new vector.
+///
+/// See [`merge_sorted`].
+pub fn merge_sorted_by_key<T, K, F>(a: &[T], b: &[T], mut f: F) -> Vec<T>
+where
+    T: Clone,
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    merge_sorted_by(a, b, |x, y| f(x).cmp(&f(y)))
+}
+
+/// Checks if the elements of the slice are sorted.
+///
+/// Empty slices and slices of one element are sorted.
+pub fn is_sorted<T: Ord>(v: &[T]) -> bool {
+    is_sorted_by(v, T::cmp)
+}
+
+/// Checks if the elements of the slice are sorted according to a
+/// comparator function.
+pub fn is_sorted_by<T, F>(v: &[T], mut compare: F) -> bool
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    v.windows(2).all(|w| compare(&w[0], &w[1]) != Ordering::Greater)
+}
+
+/// Checks if the elements of the slice are sorted by a key extraction
+/// function.
+pub fn is_sorted_by_key<T, K, F>(v: &[T], mut f: F) -> bool
+where
+    F: FnMut(&T) -> K,
+    K: Ord,
+{
+    is_sorted_by(v, |a, b| f(a).cmp(&f(b)))
+}
@@ -0,0 +1,254 @@
+use super::*;
+
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::test_util::Rng;
+
+const LENS: [usize; 12] = [0, 1, 2, 3, 7, 20, 21, 31, 64, 100, 1000, 5000];
+
+// Random, sorted, reversed and few-distinct-values inputs.
+fn inputs(len: usize) -> Vec<Vec<u32>> {
+    let mut rng = Rng::new(len as u64);
+    let random: Vec<u32> = (0..len).map(|_| rng.next() as u32).collect();
+    let few: Vec<u32> = (0..len).map(|_| (rng.next() % 4) as u32).collect();
+    let sorted: Vec<u32> = (0..len as u32).collect();
+    let reversed: Vec<u32> = (0..len as u32).rev().collect();
+    let sawtooth: Vec<u32> = (0..len as u32).map(|i| i % 17).collect();
+    vec![random, few, sorted, reversed, sawtooth]
+}
+
+fn check_sorts_like_std(sort: impl Fn(&mut [u32])) {
+    for &len in LENS.iter() {
+        for input in inputs(len) {
+            let mut expected = input.clone();
+            expected.sort();
+            let mut v = input;
+            sort(&mut v);
+            assert_eq!(v, expected);
+        }
+    }
+}
+
+#[test]
+fn insertion_sort_sorts() {
+    check_sorts_like_std(insertion_sort);
+    check_sorts_like_std(|v| insertion_sort_by(v, |a, b| a.cmp(b)));
+    check_sorts_like_std(|v| insertion_sort_by_key(v, |&x| x));
+}
+
+#[test]
+fn merge_sort_sorts() {
+    check_sorts_like_std(merge_sort);
+    check_sorts_like_std(|v| merge_sort_by(v, |a, b| a.cmp(b)));
+    check_sorts_like_std(|v| merge_sort_by_key(v, |&x| x));
+}
+
+#[test]
+fn timsort_sorts() {
+    check_sorts_like_std(timsort);
+    check_sorts_like_std(|v| timsort_by(v, |a, b| a.cmp(b)));
+    check_sorts_like_std(|v| timsort_by_key(v, |&x| x));
+}
+
+#[test]
+fn quicksort_sorts() {
+    check_sorts_like_std(quicksort);
+    check_sorts_like_std(|v| quicksort_by(v, |a, b| a.cmp(b)));
+    check_sorts_like_std(|v| quicksort_by_key(v, |&x| x));
+}
+
+#[test]
+fn heapsort_sorts() {
+    check_sorts_like_std(heapsort);
+    check_sorts_like_std(|v| heapsort_by(v, |a, b| a.cmp(b)));
+    check_sorts_like_std(|v| heapsort_by_key(v, |&x| x));
+}
+
+#[test]
+fn radix_sort_sorts() {
+    check_sorts_like_std(radix_sort);
+    check_sorts_like_std(|v| radix_sort_by_key(v, |&x| x));
+}
+
+#[test]
+fn radix_sort_signed() {
+    let mut rng = Rng::new(7);
+    let mut v: Vec<i64> = (0..1000).map(|_| rng.next() as i64).collect();
+    v.extend_from_slice(&[i64::MIN, i64::MAX, 0, -1, 1]);
+    let mut expected = v.clone();
+    expected.sort();
+    radix_sort(&mut v);
+    assert_eq!(v, expected);
+
+    let mut v: Vec<i8> = (i8::MIN..=i8::MAX).rev().collect();
+    radix_sort(&mut v);
+    assert_eq!(v, (i8::MIN..=i8::MAX).collect::<Vec<_>>());
+}
+
+#[test]
+fn sorts_descending_with_comparator() {
+    let mut v: Vec<u32> = (0..100).collect();
+    timsort_by(&mut v, |a, b| b.cmp(a));
+    assert_eq!(v, (0..100).rev().collect::<Vec<_>>());
+    quicksort_by(&mut v, |a, b| a.cmp(b));
+    assert_eq!(v, (0..100).collect::<Vec<_>>());
+}
+
+// Sorts `(key, original index)` pairs by key and checks that equal keys kept
+// their original order.
+fn check_stable(sort: impl Fn(&mut [(u32, usize)])) {
+    for &len in LENS.iter() {
+        let mut rng = Rng::new(len as u64 + 1);
+        let mut v: Vec<(u32, usize)> = (0..len).map(|i| ((rng.next() % 5) as u32, i)).collect();
+        sort(&mut v);
+        for w in v.windows(2) {
+            assert!(w[0].0 < w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1), "{:?}", w);
+        }
+    }
+}
+
+#[test]
+fn stable_sorts_are_stable() {
+    check_stable(|v| insertion_sort_by_key(v, |&(k, _)| k));
+    check_stable(|v| merge_sort_by_key(v, |&(k, _)| k));
+    check_stable(|v| merge_sort_by(v, |a, b| a.0.cmp(&b.0)));
+    check_stable(|v| timsort_by_key(v, |&(k, _)| k));
+    check_stable(|v| timsort_by(v, |a, b| a.0.cmp(&b.0)));
+    check_stable(|v| radix_sort_by_key(v, |&(k, _)| k));
+}
+
+#[test]
+fn timsort_is_stable_on_descending_runs() {
+    // Equal keys inside a descending run must not be reversed.
+    let mut v: Vec<(u32, usize)> = (0..100).map(|i| ((100 - i) as u32 / 3, i)).collect();
+    timsort_by_key(&mut v, |&(k, _)| k);
+    for w in v.windows(2) {
+        assert!(w[0].0 < w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1), "{:?}", w);
+    }
+}
+
+thread_local! {
+    static DROPS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+struct Tracked {
+    id: usize,
+    key: u32,
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPS.with(|drops| drops.borrow_mut()[self.id] += 1);
+    }
+}
+
+// Runs `sort` with a comparator that panics after `panic_after` calls and
+// checks that every element is still in the slice, and is dropped exactly
+// once afterwards.
+fn check_panic_safety<S>(sort: S)
+where
+    S: Fn(&mut [Tracked], &mut dyn FnMut(&Tracked, &Tracked) -> Ordering),
+{
+    for &len in &[2, 10, 21, 50, 200] {
+        for &panic_after in &[0, 1, 5, 20, 100, 1000] {
+            DROPS.with(|drops| *drops.borrow_mut() = vec![0; len]);
+            let mut rng = Rng::new(len as u64);
+            let mut v: Vec<Tracked> =
+                (0..len).map(|id| Tracked { id, key: (rng.next() % 8) as u32 }).collect();
+
+            let mut calls = 0;
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+                sort(&mut v, &mut |a, b| {
+                    calls += 1;
+                    if calls > panic_after {
+                        panic!("comparator panicked");
+                    }
+                    a.key.cmp(&b.key)
+                })
+            }));
+
+            let mut ids: Vec<usize> = v.iter().map(|t| t.id).collect();
+            ids.sort();
+            assert_eq!(ids, (0..len).collect::<Vec<_>>());
+
+            drop(v);
+            DROPS.with(|drops| assert!(drops.borrow().iter().all(|&n| n == 1)));
+        }
+    }
+}
+
+#[test]
+fn panicking_comparator_does_not_double_drop() {
+    check_panic_safety(|v, cmp| insertion_sort_by(v, cmp));
+    check_panic_safety(|v, cmp| merge_sort_by(v, cmp));
+    check_panic_safety(|v, cmp| timsort_by(v, cmp));
+    check_panic_safety(|v, cmp| quicksort_by(v, cmp));
+    check_panic_safety(|v, cmp| heapsort_by(v, cmp));
+}
+
+#[test]
+fn panicking_radix_key_leaves_slice_untouched() {
+    let mut v: Vec<u32> = (0..100).rev().collect();
+    let mut calls = 0;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        radix_sort_by_key(&mut v, |&x| {
+            calls += 1;
+            if calls == 50 {
+                panic!("key panicked");
+            }
+            x
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(v, (0..100).rev().collect::<Vec<_>>());
+}
+
+#[test]
+fn zero_sized_types() {
+    let mut v = vec![(); 100];
+    merge_sort(&mut v);
+    timsort(&mut v);
+    quicksort(&mut v);
+    heapsort(&mut v);
+    insertion_sort(&mut v);
+    assert_eq!(v.len(), 100);
+}
+
+#[test]
+fn binary_search_finds_first_match() {
+    let v = [1, 2, 2, 2, 3, 5, 8];
+    assert_eq!(binary_search(&v, &2), Ok(1));
+    assert_eq!(binary_search(&v, &0), Err(0));
+    assert_eq!(binary_search(&v, &4), Err(5));
+    assert_eq!(binary_search(&v, &9), Err(7));
+    assert_eq!(binary_search::<i32>(&[], &1), Err(0));
+    assert_eq!(binary_search_by_key(&[(1, 'a'), (3, 'b')], &3, |&(k, _)| k), Ok(1));
+}
+
+#[test]
+fn partition_point_splits() {
+    let v = [1, 2, 3, 4, 5];
+    assert_eq!(partition_point(&v, |&x| x < 1), 0);
+    assert_eq!(partition_point(&v, |&x| x < 3), 2);
+    assert_eq!(partition_point(&v, |&x| x < 10), 5);
+}
+
+#[test]
+fn merge_sorted_is_stable() {
+    let a = [(1, 'a'), (2, 'a'), (2, 'b')];
+    let b = [(0, 'c'), (2, 'c'), (3, 'c')];
+    let merged = merge_sorted_by_key(&a, &b, |&(k, _)| k);
+    assert_eq!(merged, [(0, 'c'), (1, 'a'), (2, 'a'), (2, 'b'), (2, 'c'), (3, 'c')]);
+    assert_eq!(merge_sorted(&[1, 3], &[]), [1, 3]);
+}
+
+#[test]
+fn is_sorted_checks() {
+    assert!(is_sorted::<i32>(&[]));
+    assert!(is_sorted(&[1]));
+    assert!(is_sorted(&[1, 1, 2]));
+    assert!(!is_sorted(&[2, 1]));
+    assert!(is_sorted_by(&[3, 2, 1], |a, b| b.cmp(a)));
+    assert!(is_sorted_by_key(&[-1i32, 2, -3], |x| x.abs()));
+}
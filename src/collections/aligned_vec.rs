@@ -0,0 +1,176 @@
+//! Vectors whose buffer starts at a caller-chosen alignment.
+//!
+//! `RawVec` asks its allocator for `Layout::array::<T>`, so a plain [`Vec`]
+//! is only ever aligned to `T`. [`Aligned`] is an [`Allocator`] adapter that
+//! raises the alignment of every request it forwards to `ALIGN`, and
+//! [`AlignedVec`] is a [`Vec`] using it.
+//!
+//! Since the alignment lives in the allocator, it is kept by everything that
+//! goes through it: `reserve`, `shrink_to_fit` and `from_raw_parts_in`.
+//! `into_boxed_slice` is not available, since `Box` only uses the global
+//! allocator.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_collections::aligned_vec::{Aligned, AlignedVec};
+//!
+//! let mut v: AlignedVec<f32, 64> = AlignedVec::new_in(Aligned::new());
+//! v.extend_from_slice(&[1.0; 17]);
+//! assert_eq!(v.as_ptr() as usize % 64, 0);
+//!
+//! v.shrink_to_fit();
+//! assert_eq!(v.as_ptr() as usize % 64, 0);
+//! ```
+//!
+//! Buffers for `O_DIRECT` also need their length rounded to whole pages,
+//! which is what the [`PageRounded`] growth policy does:
+//!
+//! ```
+//! use rusty_collections::aligned_vec::{Aligned, AlignedVec};
+//! use rusty_collections::vec::PageRounded;
+//!
+//! let mut buf = AlignedVec::<u8, 4096>::new_in(Aligned::new())
+//!     .with_growth(PageRounded::<4096>);
+//! buf.reserve_exact(100);
+//! assert_eq!(buf.capacity(), 4096);
+//! assert_eq!(buf.as_ptr() as usize % 4096, 0);
+//! ```
+//!
+//! [`PageRounded`]: crate::vec::PageRounded
+
+use core::cmp;
+use core::ptr::NonNull;
+
+use crate::alloc::{AllocError, Allocator, Global, Layout};
+use crate::vec::Vec;
+
+#[cfg(test)]
+mod tests;
+
+/// A [`Vec`] whose buffer is aligned to at least `ALIGN` bytes.
+///
+/// The alignment holds whenever the vector has allocated. A vector that has
+/// not allocated yet, or one of zero-sized elements, points to a dangling
+/// address that is only aligned to `T`, like any other empty `Vec`.
+///
+/// See the [module documentation](self) for details.
+pub type AlignedVec<T, const ALIGN: usize> = Vec<T, Aligned<ALIGN>>;
+
+/// An allocator adapter that aligns every allocation to at least `ALIGN`
+/// bytes.
+///
+/// Requests that already ask for a larger alignment are passed on unchanged.
+/// Memory allocated through an `Aligned<ALIGN, A>` must be freed through an
+/// `Aligned<ALIGN, A>` as well, since the inner allocator sees the raised
+/// layout.
+#[derive(Clone, Copy, Debug)]
+pub struct Aligned<const ALIGN: usize, A: Allocator = Global> {
+    alloc: A,
+}
+
+impl<const ALIGN: usize> Aligned<ALIGN> {
+    /// Creates an adapter over the global allocator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ALIGN` is not a power of two.
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<const ALIGN: usize, A: Allocator> Aligned<ALIGN, A> {
+    /// Creates an adapter over `alloc`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ALIGN` is not a power of two.
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
+        assert!(ALIGN.is_power_of_two(), "alignment must be a power of two");
+        Aligned { alloc }
+    }
+
+    /// Returns a shared reference to the wrapped allocator.
+    #[inline]
+    pub fn inner(&self) -> &A {
+        &self.alloc
+    }
+
+    // Used for new requests, which may not fit once the alignment is raised.
+    #[inline]
+    fn align(layout: Layout) -> Result<Layout, AllocError> {
+        layout.align_to(ALIGN).map_err(|_| AllocError)
+    }
+
+    // Used for memory that was handed out by `self`, whose raised layout was
+    // already checked when it was allocated.
+    #[inline]
+    unsafe fn align_unchecked(layout: Layout) -> Layout {
+        unsafe { Layout::from_size_align_unchecked(layout.size(), cmp::max(layout.align(), ALIGN)) }
+    }
+}
+
+impl<const ALIGN: usize> Default for Aligned<ALIGN> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<const ALIGN: usize, A: Allocator> Allocator for Aligned<ALIGN, A> {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.alloc.allocate(Self::align(layout)?)
+    }
+
+    #[inline]
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.alloc.allocate_zeroed(Self::align(layout)?)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        unsafe { self.alloc.deallocate(ptr, Self::align_unchecked(layout)) }
+    }
+
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe { self.alloc.grow(ptr, Self::align_unchecked(old_layout), Self::align(new_layout)?) }
+    }
+
+    #[inline]
+    unsafe fn grow_zeroed(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe {
+            self.alloc.grow_zeroed(ptr, Self::align_unchecked(old_layout), Self::align(new_layout)?)
+        }
+    }
+
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        unsafe {
+            self.alloc.shrink(
+                ptr,
+                Self::align_unchecked(old_layout),
+                Self::align_unchecked(new_layout),
+            )
+        }
+    }
+}
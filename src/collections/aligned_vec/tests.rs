@@ -0,0 +1,79 @@
+use super::*;
+
+use std::cell::RefCell;
+use std::vec::Vec as StdVec;
+
+use crate::raw_vec::debug_memory;
+
+fn is_aligned<T>(ptr: *const T, align: usize) -> bool {
+    ptr as usize % align == 0
+}
+
+#[test]
+fn reserve_and_shrink_keep_the_alignment() {
+    let mut v: AlignedVec<u8, 256> = AlignedVec::new_in(Aligned::new());
+    for i in 0..1000 {
+        v.push(i as u8);
+        assert!(is_aligned(v.as_ptr(), 256));
+    }
+    v.reserve_exact(5000);
+    assert!(is_aligned(v.as_ptr(), 256));
+
+    v.truncate(3);
+    v.shrink_to_fit();
+    assert_eq!(v.capacity(), 3);
+    assert!(is_aligned(v.as_ptr(), 256));
+    assert_eq!(v, [0, 1, 2]);
+
+    v.shrink_to(0);
+    v.push(7);
+    assert!(is_aligned(v.as_ptr(), 256));
+}
+
+#[test]
+fn a_larger_alignment_than_requested_is_kept() {
+    #[repr(align(128))]
+    struct Wide(#[allow(dead_code)] u8);
+
+    let mut v: AlignedVec<Wide, 16> = AlignedVec::new_in(Aligned::new());
+    v.push(Wide(1));
+    assert!(is_aligned(v.as_ptr(), 128));
+}
+
+// Records the layouts the adapter forwards.
+#[derive(Default)]
+struct Recording {
+    layouts: RefCell<StdVec<(&'static str, usize, usize)>>,
+}
+
+unsafe impl Allocator for &Recording {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.layouts.borrow_mut().push(("allocate", layout.size(), layout.align()));
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.layouts.borrow_mut().push(("deallocate", layout.size(), layout.align()));
+        unsafe { Global.deallocate(ptr, layout) }
+    }
+}
+
+#[test]
+fn the_inner_allocator_sees_the_raised_layout() {
+    let recording = Recording::default();
+    {
+        let mut v: Vec<u32, Aligned<64, &Recording>> = Vec::new_in(Aligned::new_in(&recording));
+        v.reserve_exact(4);
+        v.extend_from_slice(&[1, 2, 3, 4]);
+    }
+    let layouts = recording.layouts.borrow();
+    // `debug-memory` puts a canary after the four elements.
+    let size = if debug_memory::ENABLED { 16 + debug_memory::CANARY_LEN } else { 16 };
+    assert_eq!(layouts[..], [("allocate", size, 64), ("deallocate", size, 64)]);
+}
+
+#[test]
+#[should_panic(expected = "alignment must be a power of two")]
+fn alignment_must_be_a_power_of_two() {
+    let _ = Aligned::<48>::new();
+}
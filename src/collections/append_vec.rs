@@ -0,0 +1,366 @@
+//! A lock-free, append-only vector that many threads can push to at once,
+//! written `AppendVec<T>`.
+//!
+//! Elements live in segments allocated by `RawVec`, each twice the size of
+//! the one before, so an element never moves once it has been pushed and
+//! references to it stay valid for as long as the vector lives. Pushing
+//! claims an index with a single atomic increment; the thread that claims
+//! the first index of a segment nobody has allocated yet allocates it.
+//!
+//! Readers never block either. [`get`] returns `None` for an index whose
+//! push has not finished, and [`iter`] walks a snapshot of the elements
+//! pushed so far.
+//!
+//! Elements can only be removed through `&mut self`, so the vector works
+//! well as a shared log: producers append, consumers read by index.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_collections::append_vec::AppendVec;
+//! use std::sync::Arc;
+//! use std::thread;
+//!
+//! let log = Arc::new(AppendVec::new());
+//!
+//! let producers: Vec<_> = (0..4)
+//!     .map(|t| {
+//!         let log = Arc::clone(&log);
+//!         thread::spawn(move || {
+//!             for i in 0..100 {
+//!                 log.push(t * 100 + i);
+//!             }
+//!         })
+//!     })
+//!     .collect();
+//! for producer in producers {
+//!     producer.join().unwrap();
+//! }
+//!
+//! assert_eq!(log.len(), 400);
+//! let mut all: Vec<i32> = log.iter().copied().collect();
+//! all.sort();
+//! assert_eq!(all, (0..400).collect::<Vec<_>>());
+//! ```
+//!
+//! [`get`]: AppendVec::get
+//! [`iter`]: AppendVec::iter
+
+use core::cell::UnsafeCell;
+use core::cmp;
+use core::fmt;
+use core::iter::{FromIterator, FusedIterator};
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::ptr;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+use crate::raw_vec::RawVec;
+use crate::vec::Vec;
+
+#[cfg(test)]
+mod tests;
+
+/// log2 of the size of the first segment.
+const FIRST_SHIFT: u32 = 5;
+
+/// Number of segments needed to cover every index up to `usize::MAX`.
+const SEGMENTS: usize = (usize::BITS - FIRST_SHIFT) as usize;
+
+// `AtomicPtr` is not `Copy`, so the segment table is built from a constant.
+#[allow(clippy::declare_interior_mutable_const)]
+const NULL: AtomicPtr<u8> = AtomicPtr::new(ptr::null_mut());
+
+struct Slot<T> {
+    // Set once `value` has been written. All zeroes is `false`, so a zeroed
+    // segment starts out with no element ready.
+    ready: AtomicBool,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// An append-only vector that supports concurrent `push` and lock-free reads.
+///
+/// See the [module documentation](self) for an overview.
+pub struct AppendVec<T> {
+    // Segment `k` holds `32 << k` slots, and is null until first needed.
+    segments: [AtomicPtr<u8>; SEGMENTS],
+    // The number of indices handed out by `push`. Some of them may still be
+    // being written.
+    reserved: AtomicUsize,
+    marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for AppendVec<T> {}
+unsafe impl<T: Send + Sync> Sync for AppendVec<T> {}
+
+// Returns the segment holding `index` and the offset into it.
+#[inline]
+fn locate(index: usize) -> (usize, usize) {
+    let biased = index.checked_add(1 << FIRST_SHIFT).expect("capacity overflow");
+    let top = usize::BITS - 1 - biased.leading_zeros();
+    let segment = (top - FIRST_SHIFT) as usize;
+    (segment, biased - (1 << top))
+}
+
+#[inline]
+fn segment_capacity(segment: usize) -> usize {
+    1 << (segment as u32 + FIRST_SHIFT)
+}
+
+impl<T> AppendVec<T> {
+    /// Constructs a new, empty `AppendVec<T>`.
+    ///
+    /// Nothing is allocated until the first push.
+    pub const fn new() -> Self {
+        AppendVec { segments: [NULL; SEGMENTS], reserved: AtomicUsize::new(0), marker: PhantomData }
+    }
+
+    /// Returns the number of elements pushed so far, including pushes that
+    /// are still in progress on other threads.
+    ///
+    /// Every index below the length has been handed out, but [`get`] may
+    /// still return `None` for the last few of them for a short while.
+    ///
+    /// [`get`]: AppendVec::get
+    pub fn len(&self) -> usize {
+        self.reserved.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if nothing has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends an element and returns its index.
+    ///
+    /// This can be called from many threads at once. It takes no locks: the
+    /// only contended operation is an atomic increment, plus a
+    /// compare-and-swap when a new segment is needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes. The index a
+    /// panicking push claimed stays empty: [`get`] returns `None` for it, and
+    /// [`iter`] stops in front of it.
+    ///
+    /// [`get`]: AppendVec::get
+    /// [`iter`]: AppendVec::iter
+    pub fn push(&self, value: T) -> usize {
+        let index = self.reserved.fetch_add(1, Ordering::AcqRel);
+        let (segment, offset) = locate(index);
+        let slots = self.segment_or_alloc(segment);
+        unsafe {
+            let slot = &*slots.add(offset);
+            (*slot.value.get()).as_mut_ptr().write(value);
+            slot.ready.store(true, Ordering::Release);
+        }
+        index
+    }
+
+    // Returns segment `segment`, allocating it if no other thread has.
+    fn segment_or_alloc(&self, segment: usize) -> *mut Slot<T> {
+        let current = self.segments[segment].load(Ordering::Acquire);
+        if !current.is_null() {
+            return current as *mut Slot<T>;
+        }
+
+        let buf = RawVec::<Slot<T>>::with_capacity_zeroed(segment_capacity(segment));
+        let buf = ManuallyDrop::new(buf);
+        let fresh = buf.ptr() as *mut u8;
+        match self.segments[segment].compare_exchange(
+            ptr::null_mut(),
+            fresh,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => fresh as *mut Slot<T>,
+            Err(winner) => {
+                // Another thread got there first; use its segment.
+                drop(ManuallyDrop::into_inner(buf));
+                winner as *mut Slot<T>
+            }
+        }
+    }
+
+    fn slot(&self, index: usize) -> Option<&Slot<T>> {
+        if index >= self.len() {
+            return None;
+        }
+        let (segment, offset) = locate(index);
+        let slots = self.segments[segment].load(Ordering::Acquire) as *const Slot<T>;
+        if slots.is_null() {
+            // The push that claimed `index` has not allocated the segment yet.
+            None
+        } else {
+            Some(unsafe { &*slots.add(offset) })
+        }
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if it is out
+    /// of bounds or its push has not finished yet.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let slot = self.slot(index)?;
+        if slot.ready.load(Ordering::Acquire) {
+            Some(unsafe { &*(*slot.value.get()).as_ptr() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if it
+    /// is out of bounds or its push panicked.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let slot = self.slot(index)?;
+        if slot.ready.load(Ordering::Relaxed) {
+            Some(unsafe { &mut *(*slot.value.get()).as_mut_ptr() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the elements pushed before the call.
+    ///
+    /// The iterator stops early at the first element whose push is still in
+    /// progress, so it always yields a prefix of the vector, in index order.
+    /// Elements pushed after the call are not included.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { vec: self, index: 0, end: self.len() }
+    }
+
+    /// Removes all elements, keeping the allocated segments for reuse.
+    pub fn clear(&mut self) {
+        let len = mem::replace(self.reserved.get_mut(), 0);
+        // The first index of the current segment.
+        let mut start = 0;
+        for (segment, slots) in self.segments.iter_mut().enumerate() {
+            if start >= len {
+                break;
+            }
+            // A push that panicked leaves its index unwritten, and possibly
+            // its whole segment unallocated, so only ready slots are dropped.
+            let slots = *slots.get_mut() as *mut Slot<T>;
+            if !slots.is_null() {
+                for offset in 0..cmp::min(segment_capacity(segment), len - start) {
+                    unsafe {
+                        let slot = &mut *slots.add(offset);
+                        // Unmark the slot before dropping, so a panic in
+                        // `drop` leaks the rest instead of dropping it twice.
+                        if mem::replace(slot.ready.get_mut(), false) {
+                            ptr::drop_in_place((*slot.value.get()).as_mut_ptr());
+                        }
+                    }
+                }
+            }
+            start += segment_capacity(segment);
+        }
+    }
+}
+
+impl<T> Drop for AppendVec<T> {
+    fn drop(&mut self) {
+        struct FreeSegments<'a, T>(&'a mut AppendVec<T>);
+
+        impl<T> Drop for FreeSegments<'_, T> {
+            fn drop(&mut self) {
+                for (segment, slots) in self.0.segments.iter_mut().enumerate() {
+                    let slots = *slots.get_mut() as *mut Slot<T>;
+                    if !slots.is_null() {
+                        unsafe { drop(RawVec::from_raw_parts(slots, segment_capacity(segment))) };
+                    }
+                }
+            }
+        }
+
+        // Free the segments even if dropping an element panics.
+        let guard = FreeSegments(self);
+        guard.0.clear();
+    }
+}
+
+impl<T> Default for AppendVec<T> {
+    fn default() -> Self {
+        AppendVec::new()
+    }
+}
+
+impl<T> FromIterator<T> for AppendVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let vec = AppendVec::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T> AppendVec<T> {
+    /// Pushes every element of `iter`, in order.
+    ///
+    /// Pushes from other threads may land between them.
+    pub fn extend<I: IntoIterator<Item = T>>(&self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AppendVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a AppendVec<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// An iterator over a snapshot of an [`AppendVec`].
+///
+/// This `struct` is created by [`AppendVec::iter`].
+pub struct Iter<'a, T> {
+    vec: &'a AppendVec<T>,
+    index: usize,
+    end: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.index == self.end {
+            return None;
+        }
+        match self.vec.get(self.index) {
+            Some(value) => {
+                self.index += 1;
+                Some(value)
+            }
+            None => {
+                // Stop at the first unfinished push, for good.
+                self.end = self.index;
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.index))
+    }
+}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter { vec: self.vec, index: self.index, end: self.end }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Iter").field(&self.clone().collect::<Vec<_>>()).finish()
+    }
+}
@@ -0,0 +1,172 @@
+use super::*;
+
+use std::panic;
+use std::thread;
+use std::vec::Vec as StdVec;
+
+use crate::test_util::{drops, Counted};
+
+const THREADS: usize = 8;
+const PER_THREAD: usize = 2000;
+
+#[test]
+fn segments_double_in_size() {
+    assert_eq!(locate(0), (0, 0));
+    assert_eq!(locate(31), (0, 31));
+    assert_eq!(locate(32), (1, 0));
+    assert_eq!(locate(95), (1, 63));
+    assert_eq!(locate(96), (2, 0));
+    assert_eq!(locate(usize::MAX - 32), (SEGMENTS - 1, segment_capacity(SEGMENTS - 1) - 1));
+    for segment in 0..10 {
+        let start = (1 << FIRST_SHIFT) * ((1 << segment) - 1);
+        let last = segment_capacity(segment) - 1;
+        assert_eq!(locate(start), (segment, 0));
+        assert_eq!(locate(start + last), (segment, last));
+    }
+}
+
+#[test]
+fn concurrent_push_and_get() {
+    let vec = AppendVec::new();
+    thread::scope(|s| {
+        for t in 0..THREADS {
+            let vec = &vec;
+            s.spawn(move || {
+                for i in 0..PER_THREAD {
+                    let value = t * PER_THREAD + i;
+                    let index = vec.push(value);
+                    // Our own push has finished, so it is visible to us.
+                    assert_eq!(vec.get(index), Some(&value));
+                }
+            });
+        }
+        // Readers racing with the pushes only ever see finished elements.
+        for _ in 0..2 {
+            let vec = &vec;
+            s.spawn(move || {
+                while vec.len() < THREADS * PER_THREAD {
+                    let seen = vec.iter().count();
+                    let len = vec.len();
+                    assert!(seen <= len);
+                    for index in 0..len {
+                        if let Some(&value) = vec.get(index) {
+                            assert!(value < THREADS * PER_THREAD);
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    assert_eq!(vec.len(), THREADS * PER_THREAD);
+    let mut all: StdVec<usize> = vec.iter().copied().collect();
+    all.sort_unstable();
+    assert_eq!(all, (0..THREADS * PER_THREAD).collect::<StdVec<_>>());
+
+    // Each thread's elements keep the order it pushed them in.
+    for t in 0..THREADS {
+        let mine: StdVec<usize> =
+            vec.iter().copied().filter(|v| v / PER_THREAD == t).collect();
+        assert!(mine.windows(2).all(|w| w[0] < w[1]));
+    }
+}
+
+#[test]
+fn references_stay_valid_while_others_push() {
+    let vec = AppendVec::new();
+    vec.push(String::from("first"));
+    let first = vec.get(0).unwrap();
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| vec.extend((0..1000).map(|i| i.to_string())));
+        }
+        s.spawn(|| {
+            for _ in 0..1000 {
+                assert_eq!(first, "first");
+            }
+        });
+    });
+    assert_eq!(first as *const String, vec.get(0).unwrap() as *const String);
+    assert_eq!(vec.len(), 4001);
+}
+
+#[test]
+fn racing_allocations_keep_one_segment() {
+    // Every thread pushes the first element of a fresh segment at about the
+    // same time; the losers must free their segment and use the winner's.
+    for _ in 0..50 {
+        let vec = AppendVec::new();
+        thread::scope(|s| {
+            for t in 0..THREADS {
+                let vec = &vec;
+                s.spawn(move || vec.push(t));
+            }
+        });
+        let mut all: StdVec<usize> = vec.iter().copied().collect();
+        all.sort_unstable();
+        assert_eq!(all, (0..THREADS).collect::<StdVec<_>>());
+    }
+}
+
+#[test]
+fn get_mut_and_clear() {
+    let mut vec: AppendVec<u32> = (0..100).collect();
+    *vec.get_mut(40).unwrap() = 7;
+    assert_eq!(vec.get(40), Some(&7));
+    assert_eq!(vec.get_mut(100), None);
+
+    let slot = vec.get(0).unwrap() as *const u32;
+    vec.clear();
+    assert!(vec.is_empty());
+    assert_eq!(vec.get(0), None);
+    assert_eq!(vec.iter().count(), 0);
+
+    // The segments are kept for reuse.
+    vec.push(1);
+    assert_eq!(vec.get(0).unwrap() as *const u32, slot);
+    assert_eq!(format!("{:?}", vec), "[1]");
+}
+
+#[test]
+fn each_element_is_dropped_once() {
+    let mut vec = AppendVec::new();
+    thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| vec.extend((0..100).map(|_| Counted(0))));
+        }
+    });
+    assert_eq!(drops(|| vec.clear()).1, 400);
+    vec.extend((0..50).map(|_| Counted(0)));
+    assert_eq!(drops(|| drop(vec)).1, 50);
+}
+
+#[test]
+fn a_push_that_panics_leaves_a_hole() {
+    let mut vec = AppendVec::new();
+    vec.extend((0..3).map(|_| Counted(0)));
+    // Pretend that enough pushes happened for the next one to land in the
+    // last segment, whose `2^(BITS - 1)` slots take more than `isize::MAX`
+    // bytes, so the push panics after claiming its index.
+    let far = 1 << (usize::BITS - 1);
+    *vec.reserved.get_mut() = far;
+    let (result, n) =
+        drops(|| panic::catch_unwind(panic::AssertUnwindSafe(|| vec.push(Counted(0)))));
+    assert!(result.is_err());
+    assert_eq!(n, 1, "the pushed value is dropped");
+    assert_eq!(vec.len(), far + 1);
+    assert!(vec.get(far).is_none());
+    assert!(vec.get_mut(far).is_none());
+    assert!(vec.get(3).is_none());
+    assert_eq!(vec.iter().count(), 3);
+
+    // Only the three elements that were written are dropped, and the
+    // segment that was never allocated is skipped.
+    assert_eq!(drops(|| vec.clear()).1, 3);
+    assert!(vec.is_empty());
+
+    vec.extend((0..40).map(|_| Counted(0)));
+    *vec.reserved.get_mut() = far;
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| vec.push(Counted(0))));
+    assert!(result.is_err());
+    assert_eq!(drops(|| drop(vec)).1, 40);
+}
@@ -0,0 +1,512 @@
+//! A growable vector of booleans packed one bit each, written `BitVec`.
+//!
+//! A `Vec<bool>` spends a whole byte on every flag. [`BitVec`] stores them
+//! in `usize` words instead, so it takes an eighth of the memory, and
+//! whole-vector operations such as [`count_ones`], [`and`] or [`or`] work a
+//! word at a time.
+//!
+//! The words live in a [`RawVec`]. Like `vec![0; n]`, [`BitVec::from_elem`]
+//! with `false` asks the allocator for zeroed memory instead of writing the
+//! zeroes itself, so a large, empty bitmap is cheap to create.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_collections::bit_vec::BitVec;
+//!
+//! // Which of 1000 rows match a predicate.
+//! let mut matches = BitVec::from_elem(false, 1000);
+//! for row in (0..1000).filter(|row| row % 7 == 0) {
+//!     matches.set(row, true);
+//! }
+//!
+//! let mut other = BitVec::from_elem(false, 1000);
+//! other.fill(0..500, true);
+//!
+//! matches.and(&other);
+//! assert_eq!(matches.count_ones(), 72);
+//! assert_eq!(matches.iter_ones().take(3).collect::<Vec<_>>(), [0, 7, 14]);
+//! ```
+//!
+//! [`count_ones`]: BitVec::count_ones
+//! [`and`]: BitVec::and
+//! [`or`]: BitVec::or
+
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::ops::{BitAndAssign, BitOrAssign, BitXorAssign, Not, Range, RangeBounds};
+use core::ptr;
+use core::slice;
+
+use crate::raw_vec::RawVec;
+use crate::vec::slice_range;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of bits in a storage word.
+const BITS: usize = usize::BITS as usize;
+
+// Number of words needed to hold `bits` bits.
+#[inline]
+fn words_for(bits: usize) -> usize {
+    bits / BITS + (bits & (BITS - 1) != 0) as usize
+}
+
+// A word with the low `bits` bits set, for `bits < BITS`.
+#[inline]
+fn low_mask(bits: usize) -> usize {
+    (1 << bits) - 1
+}
+
+/// A growable vector of bits.
+///
+/// See the [module documentation](self) for an overview.
+pub struct BitVec {
+    // The first `words_for(len)` words are initialized. Bits at or past
+    // `len` in the last of them are always zero.
+    buf: RawVec<usize>,
+    len: usize,
+}
+
+impl BitVec {
+    /// Constructs a new, empty `BitVec`.
+    ///
+    /// Nothing is allocated until the first bit is pushed.
+    pub const fn new() -> Self {
+        BitVec { buf: RawVec::NEW, len: 0 }
+    }
+
+    /// Constructs a new, empty `BitVec` with room for at least `capacity`
+    /// bits.
+    pub fn with_capacity(capacity: usize) -> Self {
+        BitVec { buf: RawVec::with_capacity(words_for(capacity)), len: 0 }
+    }
+
+    /// Creates a `BitVec` of `n` copies of `bit`.
+    ///
+    /// For `false`, the memory is requested already zeroed from the
+    /// allocator, as `vec![0; n]` does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::bit_vec::BitVec;
+    ///
+    /// let bits = BitVec::from_elem(true, 70);
+    /// assert_eq!(bits.len(), 70);
+    /// assert_eq!(bits.count_ones(), 70);
+    /// ```
+    pub fn from_elem(bit: bool, n: usize) -> Self {
+        let words = words_for(n);
+        if !bit {
+            return BitVec { buf: RawVec::with_capacity_zeroed(words), len: n };
+        }
+        let mut v = BitVec { buf: RawVec::with_capacity(words), len: n };
+        unsafe {
+            ptr::write_bytes(v.buf.ptr(), 0xFF, words);
+        }
+        v.clear_unused_bits();
+        v
+    }
+
+    /// Returns the number of bits.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no bits.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of bits the vector can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity().saturating_mul(BITS)
+    }
+
+    /// Reserves capacity for at least `additional` more bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        let bits = self.len.checked_add(additional).expect("capacity overflow");
+        let used = self.words().len();
+        self.buf.reserve(used, words_for(bits) - used);
+    }
+
+    /// Returns the storage words.
+    ///
+    /// Bit `i` is bit `i % usize::BITS` of word `i / usize::BITS`, counting
+    /// from the least significant bit. Bits past the length are zero.
+    #[inline]
+    pub fn words(&self) -> &[usize] {
+        unsafe { slice::from_raw_parts(self.buf.ptr(), words_for(self.len)) }
+    }
+
+    // The storage words, mutably. Callers must keep the bits past the length
+    // zero, usually through `clear_unused_bits`.
+    #[inline]
+    fn words_mut(&mut self) -> &mut [usize] {
+        unsafe { slice::from_raw_parts_mut(self.buf.ptr(), words_for(self.len)) }
+    }
+
+    fn clear_unused_bits(&mut self) {
+        let tail = self.len % BITS;
+        if tail != 0 {
+            if let Some(last) = self.words_mut().last_mut() {
+                *last &= low_mask(tail);
+            }
+        }
+    }
+
+    /// Returns the bit at `index`, or `None` if it is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index < self.len {
+            Some((self.words()[index / BITS] >> (index % BITS)) & 1 == 1)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the bit at `index` to `bit`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn set(&mut self, index: usize, bit: bool) {
+        assert!(index < self.len, "index {} out of range for BitVec of length {}", index, self.len);
+        let word = &mut self.words_mut()[index / BITS];
+        let mask = 1 << (index % BITS);
+        if bit {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /// Appends a bit to the back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn push(&mut self, bit: bool) {
+        if self.len & (BITS - 1) == 0 {
+            let used = self.len / BITS;
+            if used == self.buf.capacity() {
+                self.buf.reserve(used, 1);
+            }
+            unsafe {
+                ptr::write(self.buf.ptr().add(used), 0);
+            }
+        }
+        self.len += 1;
+        if bit {
+            self.set(self.len - 1, true);
+        }
+    }
+
+    /// Removes the last bit and returns it, or `None` if the vector is
+    /// empty.
+    pub fn pop(&mut self) -> Option<bool> {
+        let bit = self.get(self.len.checked_sub(1)?)?;
+        self.truncate(self.len - 1);
+        Some(bit)
+    }
+
+    /// Shortens the vector to `len` bits. Has no effect if `len` is greater
+    /// than the current length.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            self.len = len;
+            self.clear_unused_bits();
+        }
+    }
+
+    /// Removes all bits, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Sets every bit in `range` to `bit`.
+    ///
+    /// Whole words inside the range are written at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    pub fn fill<R: RangeBounds<usize>>(&mut self, range: R, bit: bool) {
+        let Range { start, end } = slice_range(range, self.len);
+        if start == end {
+            return;
+        }
+        let fill = if bit { !0 } else { 0 };
+        let (first, last) = (start / BITS, (end - 1) / BITS);
+        let head = !low_mask(start % BITS);
+        let tail = if end % BITS == 0 { !0 } else { low_mask(end % BITS) };
+        let words = self.words_mut();
+        if first == last {
+            let mask = head & tail;
+            words[first] = (words[first] & !mask) | (fill & mask);
+            return;
+        }
+        words[first] = (words[first] & !head) | (fill & head);
+        for word in &mut words[first + 1..last] {
+            *word = fill;
+        }
+        words[last] = (words[last] & !tail) | (fill & tail);
+    }
+
+    /// Returns the number of bits that are set.
+    pub fn count_ones(&self) -> usize {
+        self.words().iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns the number of bits that are clear.
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    /// Returns `true` if any bit is set.
+    pub fn any(&self) -> bool {
+        self.words().iter().any(|&w| w != 0)
+    }
+
+    /// Returns `true` if every bit is set, or the vector is empty.
+    pub fn all(&self) -> bool {
+        self.count_ones() == self.len
+    }
+
+    // Combines `other` into `self` word by word.
+    fn zip_words(&mut self, other: &BitVec, op: impl Fn(usize, usize) -> usize) {
+        assert_eq!(self.len, other.len, "BitVec lengths differ");
+        for (a, &b) in self.words_mut().iter_mut().zip(other.words()) {
+            *a = op(*a, b);
+        }
+    }
+
+    /// Sets each bit to the logical AND of itself and the same bit of
+    /// `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths differ.
+    pub fn and(&mut self, other: &BitVec) {
+        self.zip_words(other, |a, b| a & b);
+    }
+
+    /// Sets each bit to the logical OR of itself and the same bit of
+    /// `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths differ.
+    pub fn or(&mut self, other: &BitVec) {
+        self.zip_words(other, |a, b| a | b);
+    }
+
+    /// Sets each bit to the logical XOR of itself and the same bit of
+    /// `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths differ.
+    pub fn xor(&mut self, other: &BitVec) {
+        self.zip_words(other, |a, b| a ^ b);
+    }
+
+    /// Flips every bit.
+    pub fn negate(&mut self) {
+        for word in self.words_mut() {
+            *word = !*word;
+        }
+        self.clear_unused_bits();
+    }
+
+    /// Returns an iterator over the bits, front to back.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { bits: self, range: 0..self.len }
+    }
+
+    /// Returns an iterator over the indices of the bits that are set, in
+    /// increasing order.
+    ///
+    /// Runs of clear bits are skipped a word at a time.
+    pub fn iter_ones(&self) -> IterOnes<'_> {
+        let mut words = self.words().iter();
+        let current = words.next().copied().unwrap_or(0);
+        IterOnes { words, current, base: 0 }
+    }
+}
+
+impl Clone for BitVec {
+    fn clone(&self) -> Self {
+        let words = self.words();
+        let buf = RawVec::with_capacity(words.len());
+        unsafe {
+            ptr::copy_nonoverlapping(words.as_ptr(), buf.ptr(), words.len());
+        }
+        BitVec { buf, len: self.len }
+    }
+}
+
+impl Default for BitVec {
+    fn default() -> Self {
+        BitVec::new()
+    }
+}
+
+impl PartialEq for BitVec {
+    fn eq(&self, other: &Self) -> bool {
+        // Unused bits are always zero, so the words can be compared directly.
+        self.len == other.len && self.words() == other.words()
+    }
+}
+
+impl Eq for BitVec {}
+
+impl Hash for BitVec {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        self.words().hash(state);
+    }
+}
+
+impl fmt::Debug for BitVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BitVec[")?;
+        for bit in self {
+            f.write_str(if bit { "1" } else { "0" })?;
+        }
+        f.write_str("]")
+    }
+}
+
+impl BitAndAssign<&BitVec> for BitVec {
+    fn bitand_assign(&mut self, other: &BitVec) {
+        self.and(other);
+    }
+}
+
+impl BitOrAssign<&BitVec> for BitVec {
+    fn bitor_assign(&mut self, other: &BitVec) {
+        self.or(other);
+    }
+}
+
+impl BitXorAssign<&BitVec> for BitVec {
+    fn bitxor_assign(&mut self, other: &BitVec) {
+        self.xor(other);
+    }
+}
+
+impl Not for BitVec {
+    type Output = BitVec;
+
+    fn not(mut self) -> BitVec {
+        self.negate();
+        self
+    }
+}
+
+impl FromIterator<bool> for BitVec {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bits = BitVec::new();
+        bits.extend(iter);
+        bits
+    }
+}
+
+impl Extend<bool> for BitVec {
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for bit in iter {
+            self.push(bit);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a BitVec {
+    type Item = bool;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the bits of a [`BitVec`].
+///
+/// This `struct` is created by [`BitVec::iter`].
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    bits: &'a BitVec,
+    range: Range<usize>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = bool;
+
+    #[inline]
+    fn next(&mut self) -> Option<bool> {
+        self.range.next().map(|i| (self.bits.words()[i / BITS] >> (i % BITS)) & 1 == 1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<bool> {
+        self.range.next_back().map(|i| (self.bits.words()[i / BITS] >> (i % BITS)) & 1 == 1)
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+/// An iterator over the indices of the set bits of a [`BitVec`].
+///
+/// This `struct` is created by [`BitVec::iter_ones`].
+#[derive(Clone, Debug)]
+pub struct IterOnes<'a> {
+    words: slice::Iter<'a, usize>,
+    // The bits of the current word that have not been yielded yet.
+    current: usize,
+    // Index of bit 0 of the current word.
+    base: usize,
+}
+
+impl Iterator for IterOnes<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.current = *self.words.next()?;
+            self.base += BITS;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        // Clear the lowest set bit.
+        self.current &= self.current - 1;
+        Some(self.base + bit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let upper = self.current.count_ones() as usize + self.words.len() * BITS;
+        (0, Some(upper))
+    }
+}
+
+impl FusedIterator for IterOnes<'_> {}
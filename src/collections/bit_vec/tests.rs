@@ -0,0 +1,156 @@
+use super::*;
+
+use std::vec::Vec as StdVec;
+
+use crate::test_util::Rng;
+
+fn random_bits(rng: &mut Rng, len: usize) -> StdVec<bool> {
+    (0..len).map(|_| rng.next() & 1 == 1).collect()
+}
+
+// Checks every query against the model, including that the bits past the
+// length stay zero.
+fn check(bits: &BitVec, m: &[bool]) {
+    assert_eq!(bits.len(), m.len());
+    assert!(bits.iter().eq(m.iter().copied()));
+    assert!(bits.iter().rev().eq(m.iter().rev().copied()));
+    let ones: StdVec<usize> = (0..m.len()).filter(|&i| m[i]).collect();
+    assert_eq!(bits.iter_ones().collect::<StdVec<_>>(), ones);
+    assert_eq!(bits.count_ones(), ones.len());
+    assert_eq!(bits.count_zeros(), m.len() - ones.len());
+    assert_eq!(bits.any(), !ones.is_empty());
+    assert_eq!(bits.all(), ones.len() == m.len());
+    assert_eq!(bits.words().len(), m.len().div_ceil(BITS));
+    if m.len() % BITS != 0 {
+        assert_eq!(bits.words().last().unwrap() >> (m.len() % BITS), 0, "unused bits are set");
+    }
+    assert!(bits.capacity() >= bits.len());
+}
+
+// Applies one random operation to both the bit vector and a `Vec<bool>`.
+fn step(rng: &mut Rng, bits: &mut BitVec, m: &mut StdVec<bool>) {
+    let bit = rng.next() & 1 == 1;
+    match rng.below(10) {
+        0..=2 => {
+            bits.push(bit);
+            m.push(bit);
+        }
+        3 => assert_eq!(bits.pop(), m.pop()),
+        4 if !m.is_empty() => {
+            let i = rng.below(m.len());
+            bits.set(i, bit);
+            m[i] = bit;
+            assert_eq!(bits.get(i), Some(bit));
+        }
+        5 => {
+            let len = rng.below(m.len() + 1);
+            bits.truncate(len);
+            m.truncate(len);
+        }
+        6 => {
+            let start = rng.below(m.len() + 1);
+            let end = start + rng.below(m.len() - start + 1);
+            bits.fill(start..end, bit);
+            m[start..end].iter_mut().for_each(|b| *b = bit);
+        }
+        7 => {
+            let len = rng.below(3 * BITS);
+            let extra = random_bits(rng, len);
+            bits.extend(extra.iter().copied());
+            m.extend(extra);
+        }
+        8 => {
+            let other = random_bits(rng, m.len());
+            let other_bits: BitVec = other.iter().copied().collect();
+            match rng.below(3) {
+                0 => {
+                    bits.and(&other_bits);
+                    m.iter_mut().zip(&other).for_each(|(a, &b)| *a &= b);
+                }
+                1 => {
+                    *bits |= &other_bits;
+                    m.iter_mut().zip(&other).for_each(|(a, &b)| *a |= b);
+                }
+                _ => {
+                    *bits ^= &other_bits;
+                    m.iter_mut().zip(&other).for_each(|(a, &b)| *a ^= b);
+                }
+            }
+        }
+        _ => {
+            bits.negate();
+            m.iter_mut().for_each(|b| *b = !*b);
+        }
+    }
+    assert_eq!(bits.get(m.len()), None);
+    check(bits, m);
+}
+
+#[test]
+fn random_operations_match_a_vec_of_bools() {
+    for seed in 0..200 {
+        let mut rng = Rng::new(seed);
+        let mut bits = BitVec::new();
+        let mut m = StdVec::new();
+        for _ in 0..100 {
+            step(&mut rng, &mut bits, &mut m);
+        }
+    }
+}
+
+#[test]
+fn from_elem_around_word_boundaries() {
+    for n in [0, 1, BITS - 1, BITS, BITS + 1, 3 * BITS, 3 * BITS + 5] {
+        let ones = BitVec::from_elem(true, n);
+        check(&ones, &std::vec![true; n]);
+        let zeros = BitVec::from_elem(false, n);
+        check(&zeros, &std::vec![false; n]);
+        assert_eq!(!zeros, ones);
+    }
+}
+
+#[test]
+fn fill_within_and_across_words() {
+    let n = 4 * BITS + 3;
+    let ranges = [(0, n), (3, 9), (BITS - 1, BITS + 1), (5, 3 * BITS), (BITS, 2 * BITS)];
+    for (start, end) in ranges {
+        let mut bits = BitVec::from_elem(false, n);
+        bits.fill(start..end, true);
+        assert!(bits.iter_ones().eq(start..end));
+        bits.fill(.., true);
+        bits.fill(start..end, false);
+        assert_eq!(bits.count_zeros(), end - start);
+    }
+}
+
+#[test]
+#[should_panic(expected = "BitVec lengths differ")]
+fn combining_different_lengths_panics() {
+    let mut a = BitVec::from_elem(true, 10);
+    a.and(&BitVec::from_elem(true, 11));
+}
+
+#[test]
+#[should_panic(expected = "index 10 out of range for BitVec of length 10")]
+fn set_out_of_bounds_panics() {
+    BitVec::from_elem(true, 10).set(10, false);
+}
+
+#[test]
+fn equality_ignores_leftover_storage() {
+    // Shrink one vector from a longer one: the truncated bits must not leak
+    // into comparisons or later pushes.
+    let mut a = BitVec::from_elem(true, 2 * BITS);
+    a.truncate(3);
+    let b = BitVec::from_elem(true, 3);
+    assert_eq!(a, b);
+    a.clear();
+    a.extend([false, false]);
+    assert_eq!(a, BitVec::from_elem(false, 2));
+
+    let c = b.clone();
+    assert_eq!(c, b);
+    let d: BitVec = [true, false, true].into_iter().collect();
+    assert_eq!(format!("{:?}", d), "BitVec[101]");
+    assert_eq!(BitVec::default(), BitVec::new());
+}
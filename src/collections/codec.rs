@@ -0,0 +1,528 @@
+//! A compact binary encoding for vectors.
+//!
+//! [`encode`] writes a slice as a small header followed by its elements, and
+//! [`decode`] reads it back into a [`Vec`]. The header records the byte
+//! order the elements were written in, so a buffer written on a big-endian
+//! machine decodes correctly on a little-endian one.
+//!
+//! Vectors of [`Pod`] elements written in the native byte order can also be
+//! read *without copying*: [`decode_pod`] checks the header and hands out a
+//! `&[T]` that points straight into the byte buffer.
+//!
+//! # Format
+//!
+//! | Offset | Size | Contents                                                 |
+//! |--------|------|----------------------------------------------------------|
+//! | 0      | 1    | byte order tag, `b'L'` (little) or `b'B'` (big endian)   |
+//! | 1      | 3    | zero                                                     |
+//! | 4      | 4    | size of one encoded element, `0` if it varies (`u32`)    |
+//! | 8      | 8    | number of elements (`u64`)                               |
+//! | 16     |      | the elements                                             |
+//!
+//! All integers, in the header and in the elements, use the tagged byte
+//! order. The header is 16 bytes long, so elements in a 16-byte aligned
+//! buffer are aligned too.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_collections::codec::{self, ByteOrder};
+//! use rusty_collections::vec::Vec;
+//!
+//! let mut bytes = Vec::new();
+//! codec::encode_with_order(&[1u32, 2, 3], &mut bytes, ByteOrder::Big)?;
+//!
+//! let v: Vec<u32> = codec::decode(&mut &bytes[..])?;
+//! assert_eq!(v, [1, 2, 3]);
+//! # Ok::<(), codec::DecodeError>(())
+//! ```
+//!
+//! [`Pod`]: crate::pod::Pod
+
+use core::cmp;
+use core::convert::TryFrom;
+use core::fmt;
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::ptr;
+use core::slice;
+
+use std::error::Error;
+use std::io::{self, Read, Write};
+
+use crate::pod::Pod;
+use crate::vec::Vec;
+
+#[cfg(test)]
+mod tests;
+
+/// Size of the header written in front of the elements.
+pub const HEADER_SIZE: usize = 16;
+
+/// Decoding never reserves room for more elements than this up front, so a
+/// corrupt length cannot make it allocate gigabytes before failing.
+const MAX_PREALLOC: usize = 4096;
+
+/// The byte order of encoded integers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    /// Least significant byte first.
+    Little,
+    /// Most significant byte first.
+    Big,
+}
+
+impl ByteOrder {
+    /// The byte order of the machine this code runs on.
+    #[cfg(target_endian = "little")]
+    pub const NATIVE: ByteOrder = ByteOrder::Little;
+    /// The byte order of the machine this code runs on.
+    #[cfg(target_endian = "big")]
+    pub const NATIVE: ByteOrder = ByteOrder::Big;
+
+    fn tag(self) -> u8 {
+        match self {
+            ByteOrder::Little => b'L',
+            ByteOrder::Big => b'B',
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<ByteOrder, DecodeError> {
+        match tag {
+            b'L' => Ok(ByteOrder::Little),
+            b'B' => Ok(ByteOrder::Big),
+            _ => Err(DecodeError::BadTag(tag)),
+        }
+    }
+}
+
+/// The error type for decoding.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// Reading from the source failed.
+    Io(io::Error),
+    /// The input ended before the header or all elements were read.
+    Truncated,
+    /// The byte order tag is neither `b'L'` nor `b'B'`.
+    BadTag(u8),
+    /// The header was written for elements of a different size.
+    ElementSize {
+        /// The size of one element of the requested type.
+        expected: usize,
+        /// The size recorded in the header.
+        found: usize,
+    },
+    /// A zero-copy read was asked for data in a foreign byte order.
+    ForeignByteOrder,
+    /// A zero-copy read was asked for data that is not aligned for the
+    /// element type.
+    Misaligned,
+    /// The element count does not fit in memory.
+    LengthOverflow,
+    /// The bytes of an element do not form a valid value, like a `bool`
+    /// other than 0 or 1.
+    InvalidValue,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Io(err) => write!(f, "failed to read encoded vector: {}", err),
+            DecodeError::Truncated => f.write_str("encoded vector is truncated"),
+            DecodeError::BadTag(tag) => write!(f, "invalid byte order tag {:#04x}", tag),
+            DecodeError::ElementSize { expected, found } => {
+                write!(f, "element size mismatch: expected {}, found {}", expected, found)
+            }
+            DecodeError::ForeignByteOrder => {
+                f.write_str("zero-copy decoding requires the native byte order")
+            }
+            DecodeError::Misaligned => {
+                f.write_str("encoded elements are not aligned for zero-copy decoding")
+            }
+            DecodeError::LengthOverflow => f.write_str("encoded vector is too long"),
+            DecodeError::InvalidValue => f.write_str("encoded element is not a valid value"),
+        }
+    }
+}
+
+impl Error for DecodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DecodeError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DecodeError {
+    fn from(err: io::Error) -> DecodeError {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            DecodeError::Truncated
+        } else {
+            DecodeError::Io(err)
+        }
+    }
+}
+
+/// Types that can be written by [`encode`].
+pub trait Encode {
+    /// The number of bytes every encoded value takes, or `0` if it depends on
+    /// the value.
+    const WIDTH: usize;
+
+    /// Writes `self` in the given byte order.
+    fn encode<W: Write + ?Sized>(&self, w: &mut W, order: ByteOrder) -> io::Result<()>;
+}
+
+/// Types that can be read by [`decode`].
+pub trait Decode: Encode + Sized {
+    /// Reads a value that was written in the given byte order.
+    fn decode<R: Read + ?Sized>(r: &mut R, order: ByteOrder) -> Result<Self, DecodeError>;
+}
+
+macro_rules! codec_num {
+    ($($t:ty)*) => {$(
+        impl Encode for $t {
+            const WIDTH: usize = mem::size_of::<$t>();
+
+            #[inline]
+            fn encode<W: Write + ?Sized>(&self, w: &mut W, order: ByteOrder) -> io::Result<()> {
+                match order {
+                    ByteOrder::Little => w.write_all(&self.to_le_bytes()),
+                    ByteOrder::Big => w.write_all(&self.to_be_bytes()),
+                }
+            }
+        }
+
+        impl Decode for $t {
+            #[inline]
+            fn decode<R: Read + ?Sized>(r: &mut R, order: ByteOrder) -> Result<Self, DecodeError> {
+                let mut bytes = [0; mem::size_of::<$t>()];
+                r.read_exact(&mut bytes)?;
+                Ok(match order {
+                    ByteOrder::Little => <$t>::from_le_bytes(bytes),
+                    ByteOrder::Big => <$t>::from_be_bytes(bytes),
+                })
+            }
+        }
+    )*}
+}
+
+codec_num! { u8 u16 u32 u64 u128 i8 i16 i32 i64 i128 f32 f64 }
+
+// `usize` and `isize` are written as 64 bits, so the format does not depend
+// on the pointer width of the machine.
+macro_rules! codec_size {
+    ($($t:ty => $wide:ty)*) => {$(
+        impl Encode for $t {
+            const WIDTH: usize = 8;
+
+            #[inline]
+            fn encode<W: Write + ?Sized>(&self, w: &mut W, order: ByteOrder) -> io::Result<()> {
+                (*self as $wide).encode(w, order)
+            }
+        }
+
+        impl Decode for $t {
+            #[inline]
+            fn decode<R: Read + ?Sized>(r: &mut R, order: ByteOrder) -> Result<Self, DecodeError> {
+                let wide = <$wide>::decode(r, order)?;
+                <$t>::try_from(wide)
+                    .map_err(|_| DecodeError::InvalidValue)
+            }
+        }
+    )*}
+}
+
+codec_size! { usize => u64 isize => i64 }
+
+impl Encode for bool {
+    const WIDTH: usize = 1;
+
+    #[inline]
+    fn encode<W: Write + ?Sized>(&self, w: &mut W, order: ByteOrder) -> io::Result<()> {
+        (*self as u8).encode(w, order)
+    }
+}
+
+impl Decode for bool {
+    #[inline]
+    fn decode<R: Read + ?Sized>(r: &mut R, order: ByteOrder) -> Result<Self, DecodeError> {
+        match u8::decode(r, order)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError::InvalidValue),
+        }
+    }
+}
+
+impl Encode for char {
+    const WIDTH: usize = 4;
+
+    #[inline]
+    fn encode<W: Write + ?Sized>(&self, w: &mut W, order: ByteOrder) -> io::Result<()> {
+        (*self as u32).encode(w, order)
+    }
+}
+
+impl Decode for char {
+    #[inline]
+    fn decode<R: Read + ?Sized>(r: &mut R, order: ByteOrder) -> Result<Self, DecodeError> {
+        core::char::from_u32(u32::decode(r, order)?).ok_or(DecodeError::InvalidValue)
+    }
+}
+
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    const WIDTH: usize = T::WIDTH * N;
+
+    fn encode<W: Write + ?Sized>(&self, w: &mut W, order: ByteOrder) -> io::Result<()> {
+        self.iter().try_for_each(|elem| elem.encode(w, order))
+    }
+}
+
+impl<T: Decode, const N: usize> Decode for [T; N] {
+    fn decode<R: Read + ?Sized>(r: &mut R, order: ByteOrder) -> Result<Self, DecodeError> {
+        /// Drops the elements decoded so far if a later one fails.
+        struct DropGuard<T, const N: usize> {
+            array: MaybeUninit<[T; N]>,
+            init: usize,
+        }
+
+        impl<T, const N: usize> Drop for DropGuard<T, N> {
+            fn drop(&mut self) {
+                let elems = self.array.as_mut_ptr() as *mut T;
+                unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(elems, self.init)) }
+            }
+        }
+
+        let mut guard = DropGuard::<T, N> { array: MaybeUninit::uninit(), init: 0 };
+        while guard.init < N {
+            let elem = T::decode(r, order)?;
+            unsafe { (guard.array.as_mut_ptr() as *mut T).add(guard.init).write(elem) };
+            guard.init += 1;
+        }
+        // All `N` elements are initialized, so the array is taken out of
+        // the guard without running it.
+        let guard = ManuallyDrop::new(guard);
+        Ok(unsafe { guard.array.assume_init_read() })
+    }
+}
+
+/// Nested vectors are written as a 64-bit length followed by the elements,
+/// without a header of their own.
+impl<T: Encode> Encode for Vec<T> {
+    const WIDTH: usize = 0;
+
+    fn encode<W: Write + ?Sized>(&self, w: &mut W, order: ByteOrder) -> io::Result<()> {
+        (self.len() as u64).encode(w, order)?;
+        self.iter().try_for_each(|elem| elem.encode(w, order))
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode<R: Read + ?Sized>(r: &mut R, order: ByteOrder) -> Result<Self, DecodeError> {
+        let len = u64::decode(r, order)?;
+        decode_elements(r, order, len)
+    }
+}
+
+fn decode_elements<T: Decode, R: Read + ?Sized>(
+    r: &mut R,
+    order: ByteOrder,
+    len: u64,
+) -> Result<Vec<T>, DecodeError> {
+    let len = usize::try_from(len).map_err(|_| DecodeError::LengthOverflow)?;
+    let mut vec = Vec::with_capacity(cmp::min(len, MAX_PREALLOC));
+    for _ in 0..len {
+        vec.push(T::decode(r, order)?);
+    }
+    Ok(vec)
+}
+
+// Fails without writing anything if `width` does not fit in the header,
+// since storing `0` instead would turn off the width check on decode.
+fn write_header<W: Write + ?Sized>(
+    w: &mut W,
+    order: ByteOrder,
+    width: usize,
+    len: usize,
+) -> io::Result<()> {
+    let width = u32::try_from(width).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidInput, "element size does not fit in the header")
+    })?;
+    w.write_all(&[order.tag(), 0, 0, 0])?;
+    width.encode(w, order)?;
+    (len as u64).encode(w, order)
+}
+
+struct Header {
+    order: ByteOrder,
+    width: usize,
+    len: u64,
+}
+
+fn read_header<R: Read + ?Sized>(r: &mut R) -> Result<Header, DecodeError> {
+    let mut tag = [0; 4];
+    r.read_exact(&mut tag)?;
+    let order = ByteOrder::from_tag(tag[0])?;
+    let width = u32::decode(r, order)? as usize;
+    let len = u64::decode(r, order)?;
+    Ok(Header { order, width, len })
+}
+
+/// Writes `v` in the native byte order.
+///
+/// # Errors
+///
+/// Returns any error reported by `w`, or an error of kind
+/// [`InvalidInput`](io::ErrorKind::InvalidInput) if an encoded element is
+/// larger than `u32::MAX` bytes.
+pub fn encode<T: Encode, W: Write + ?Sized>(v: &[T], w: &mut W) -> io::Result<()> {
+    encode_with_order(v, w, ByteOrder::NATIVE)
+}
+
+/// Writes `v` in the given byte order.
+///
+/// # Errors
+///
+/// See [`encode`].
+pub fn encode_with_order<T: Encode, W: Write + ?Sized>(
+    v: &[T],
+    w: &mut W,
+    order: ByteOrder,
+) -> io::Result<()> {
+    write_header(w, order, T::WIDTH, v.len())?;
+    v.iter().try_for_each(|elem| elem.encode(w, order))
+}
+
+/// Reads a vector written by [`encode`], in whatever byte order it was
+/// written.
+///
+/// # Errors
+///
+/// Returns an error if the input is truncated, the header is malformed or
+/// was written for a different element size, or an element is invalid.
+pub fn decode<T: Decode, R: Read + ?Sized>(r: &mut R) -> Result<Vec<T>, DecodeError> {
+    let header = read_header(r)?;
+    if T::WIDTH != 0 && header.width != T::WIDTH {
+        return Err(DecodeError::ElementSize { expected: T::WIDTH, found: header.width });
+    }
+    decode_elements(r, header.order, header.len)
+}
+
+/// Writes the raw bytes of `v` in the native byte order, in the same format
+/// as [`encode`].
+///
+/// This is a single `write_all` of the slice's memory, and the output can be
+/// read back without copying by [`decode_pod`]. For the primitive types it
+/// writes exactly what [`encode`] would.
+///
+/// # Errors
+///
+/// See [`encode`].
+pub fn encode_pod<T: Pod, W: Write + ?Sized>(v: &[T], w: &mut W) -> io::Result<()> {
+    write_header(w, ByteOrder::NATIVE, mem::size_of::<T>(), v.len())?;
+    // `Pod` types have no padding, so every byte is initialized.
+    let bytes = unsafe { slice::from_raw_parts(v.as_ptr() as *const u8, mem::size_of_val(v)) };
+    w.write_all(bytes)
+}
+
+/// Reads a vector of [`Pod`] elements straight out of `bytes`, without
+/// copying.
+///
+/// Returns the elements and the bytes following them.
+///
+/// # Errors
+///
+/// Besides the errors of [`decode`], this fails with
+/// [`DecodeError::ForeignByteOrder`] if the data was not written in the
+/// native byte order, and with [`DecodeError::Misaligned`] if the elements
+/// do not start at an address aligned for `T`. Use [`decode_pod_vec`] for a
+/// copying read that accepts misaligned data.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_collections::codec;
+///
+/// let mut bytes = Vec::new();
+/// codec::encode_pod(&[1.5f64, 2.5], &mut bytes)?;
+///
+/// // A `Vec<u8>` buffer is only guaranteed to be aligned to 1, so copy it
+/// // into `u64`s to get 8-byte alignment for the example.
+/// let mut aligned = vec![0u64; (bytes.len() + 7) / 8];
+/// let aligned_bytes = unsafe {
+///     std::slice::from_raw_parts_mut(aligned.as_mut_ptr() as *mut u8, bytes.len())
+/// };
+/// aligned_bytes.copy_from_slice(&bytes);
+///
+/// let (values, rest) = codec::decode_pod::<f64>(aligned_bytes)?;
+/// assert_eq!(values, [1.5, 2.5]);
+/// assert!(rest.is_empty());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// [`Pod`]: crate::pod::Pod
+pub fn decode_pod<T: Pod>(bytes: &[u8]) -> Result<(&[T], &[u8]), DecodeError> {
+    let (len, data) = pod_payload::<T>(bytes)?;
+    if header_order(bytes)? != ByteOrder::NATIVE {
+        return Err(DecodeError::ForeignByteOrder);
+    }
+    if data.as_ptr() as usize & (mem::align_of::<T>() - 1) != 0 {
+        return Err(DecodeError::Misaligned);
+    }
+    let (elements, rest) = data.split_at(len * mem::size_of::<T>());
+    // The length, alignment and element size are checked, and any bit
+    // pattern is a valid `T`.
+    let elements = unsafe { slice::from_raw_parts(elements.as_ptr() as *const T, len) };
+    Ok((elements, rest))
+}
+
+/// Reads a vector of [`Pod`] elements out of `bytes` by copying them into a
+/// new [`Vec`].
+///
+/// Unlike [`decode_pod`], this accepts data at any alignment. It still
+/// requires the native byte order, since a `Pod` type does not say how to
+/// swap its bytes; use [`decode`] for that.
+///
+/// # Errors
+///
+/// See [`decode_pod`].
+///
+/// [`Pod`]: crate::pod::Pod
+pub fn decode_pod_vec<T: Pod>(bytes: &[u8]) -> Result<Vec<T>, DecodeError> {
+    let (len, data) = pod_payload::<T>(bytes)?;
+    if header_order(bytes)? != ByteOrder::NATIVE {
+        return Err(DecodeError::ForeignByteOrder);
+    }
+    let mut vec = Vec::<T>::with_capacity(len);
+    unsafe {
+        core::ptr::copy_nonoverlapping(
+            data.as_ptr(),
+            vec.as_mut_ptr() as *mut u8,
+            len * mem::size_of::<T>(),
+        );
+        vec.set_len_unchecked(len);
+    }
+    Ok(vec)
+}
+
+fn header_order(bytes: &[u8]) -> Result<ByteOrder, DecodeError> {
+    ByteOrder::from_tag(*bytes.first().ok_or(DecodeError::Truncated)?)
+}
+
+// Validates the header for `T` and checks that `bytes` holds all of the
+// elements. Returns the element count and the bytes after the header.
+fn pod_payload<T: Pod>(bytes: &[u8]) -> Result<(usize, &[u8]), DecodeError> {
+    let header = read_header(&mut &bytes[..])?;
+    if header.width != mem::size_of::<T>() {
+        return Err(DecodeError::ElementSize { expected: mem::size_of::<T>(), found: header.width });
+    }
+    let len = usize::try_from(header.len).map_err(|_| DecodeError::LengthOverflow)?;
+    let size = len.checked_mul(mem::size_of::<T>()).ok_or(DecodeError::LengthOverflow)?;
+    let data = &bytes[HEADER_SIZE..];
+    if data.len() < size {
+        return Err(DecodeError::Truncated);
+    }
+    Ok((len, data))
+}
@@ -0,0 +1,247 @@
+use super::*;
+
+use std::vec::Vec as StdVec;
+
+use crate::test_util::{drops, Counted};
+
+// Encoded like its `u32`, so arrays of it can count their drops.
+impl Encode for Counted {
+    const WIDTH: usize = 4;
+
+    fn encode<W: Write + ?Sized>(&self, w: &mut W, order: ByteOrder) -> io::Result<()> {
+        self.0.encode(w, order)
+    }
+}
+
+impl Decode for Counted {
+    fn decode<R: Read + ?Sized>(r: &mut R, order: ByteOrder) -> Result<Self, DecodeError> {
+        u32::decode(r, order).map(Counted)
+    }
+}
+
+fn encoded<T: Encode>(v: &[T], order: ByteOrder) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    encode_with_order(v, &mut bytes, order).unwrap();
+    bytes
+}
+
+// Copies `bytes` into a buffer aligned to 16, like the ones `decode_pod`
+// expects, and passes it to `f`.
+fn with_aligned<R>(bytes: &[u8], f: impl FnOnce(&mut [u8]) -> R) -> R {
+    let mut buf: StdVec<u128> = std::vec![0; bytes.len() / 16 + 1];
+    let aligned = unsafe { slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut u8, bytes.len()) };
+    aligned.copy_from_slice(bytes);
+    f(aligned)
+}
+
+#[test]
+fn round_trip_in_both_byte_orders() {
+    for order in [ByteOrder::Little, ByteOrder::Big] {
+        let v = [0u32, 1, 0x0102_0304, u32::MAX];
+        let bytes = encoded(&v, order);
+        assert_eq!(bytes.len(), HEADER_SIZE + 16);
+        assert_eq!(decode::<u32, _>(&mut &bytes[..]).unwrap(), v);
+
+        let v = [-1.5f64, 0.0, f64::INFINITY];
+        assert_eq!(decode::<f64, _>(&mut &encoded(&v, order)[..]).unwrap(), v);
+
+        let v = [true, false, true];
+        assert_eq!(decode::<bool, _>(&mut &encoded(&v, order)[..]).unwrap(), v);
+
+        let v = ['a', 'é', '\u{1F980}'];
+        assert_eq!(decode::<char, _>(&mut &encoded(&v, order)[..]).unwrap(), v);
+
+        let v = [[1u16, 2], [3, 4]];
+        assert_eq!(decode::<[u16; 2], _>(&mut &encoded(&v, order)[..]).unwrap(), v);
+
+        let v = [usize::MAX, 0, 7];
+        let bytes = encoded(&v, order);
+        assert_eq!(bytes.len(), HEADER_SIZE + 3 * 8);
+        assert_eq!(decode::<usize, _>(&mut &bytes[..]).unwrap(), v);
+
+        let v: [Vec<u8>; 3] = [Vec::new(), Vec::from(&[1u8][..]), Vec::from(&[2u8, 3][..])];
+        let bytes = encoded(&v, order);
+        assert_eq!(bytes[4..8], [0; 4], "nested vectors have no fixed width");
+        assert_eq!(decode::<Vec<u8>, _>(&mut &bytes[..]).unwrap(), v);
+    }
+}
+
+#[test]
+fn the_header_is_as_documented() {
+    let bytes = encoded(&[1u16, 2], ByteOrder::Big);
+    assert_eq!(bytes[..HEADER_SIZE], [b'B', 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 2]);
+    assert_eq!(bytes[HEADER_SIZE..], [0, 1, 0, 2]);
+
+    let bytes = encoded(&[1u16, 2], ByteOrder::Little);
+    assert_eq!(bytes[..HEADER_SIZE], [b'L', 0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(bytes[HEADER_SIZE..], [1, 0, 2, 0]);
+}
+
+#[test]
+fn foreign_byte_order_is_swapped_by_decode_and_rejected_without_copying() {
+    let foreign = match ByteOrder::NATIVE {
+        ByteOrder::Little => ByteOrder::Big,
+        ByteOrder::Big => ByteOrder::Little,
+    };
+    let bytes = encoded(&[0x0102_0304u32, 5], foreign);
+    assert_eq!(decode::<u32, _>(&mut &bytes[..]).unwrap(), [0x0102_0304, 5]);
+    with_aligned(&bytes, |bytes| {
+        assert!(matches!(decode_pod::<u32>(bytes), Err(DecodeError::ForeignByteOrder)));
+        assert!(matches!(decode_pod_vec::<u32>(bytes), Err(DecodeError::ForeignByteOrder)));
+    });
+}
+
+#[test]
+fn truncated_input_is_rejected() {
+    let bytes = encoded(&[1u32, 2, 3], ByteOrder::NATIVE);
+    for len in 0..bytes.len() {
+        let prefix = &bytes[..len];
+        assert!(
+            matches!(decode::<u32, _>(&mut &prefix[..]), Err(DecodeError::Truncated)),
+            "decoded a {}-byte prefix",
+            len
+        );
+        assert!(matches!(decode_pod_vec::<u32>(prefix), Err(DecodeError::Truncated)));
+        with_aligned(prefix, |prefix| {
+            assert!(matches!(decode_pod::<u32>(prefix), Err(DecodeError::Truncated)));
+        });
+    }
+
+    // A nested vector that claims more elements than there are.
+    let mut bytes = encoded(&[Vec::from(&[1u8, 2][..])], ByteOrder::Little);
+    bytes[HEADER_SIZE] = 3;
+    assert!(matches!(decode::<Vec<u8>, _>(&mut &bytes[..]), Err(DecodeError::Truncated)));
+}
+
+#[test]
+fn a_huge_length_fails_without_allocating_it() {
+    let mut bytes = encoded(&[1u64], ByteOrder::Little);
+    bytes[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+    let err = decode::<u64, _>(&mut &bytes[..]).unwrap_err();
+    assert!(matches!(err, DecodeError::Truncated | DecodeError::LengthOverflow), "{:?}", err);
+    assert!(matches!(decode_pod_vec::<u64>(&bytes), Err(DecodeError::LengthOverflow)));
+}
+
+#[test]
+fn element_width_mismatch_is_rejected() {
+    let bytes = encoded(&[1u32, 2], ByteOrder::NATIVE);
+    match decode::<u16, _>(&mut &bytes[..]) {
+        Err(DecodeError::ElementSize { expected: 2, found: 4 }) => {}
+        other => panic!("unexpected result {:?}", other),
+    }
+    match decode::<u64, _>(&mut &bytes[..]) {
+        Err(DecodeError::ElementSize { expected: 8, found: 4 }) => {}
+        other => panic!("unexpected result {:?}", other),
+    }
+    with_aligned(&bytes, |bytes| {
+        assert!(matches!(
+            decode_pod::<[u32; 2]>(bytes),
+            Err(DecodeError::ElementSize { expected: 8, found: 4 })
+        ));
+        assert!(matches!(
+            decode_pod_vec::<u8>(bytes),
+            Err(DecodeError::ElementSize { expected: 1, found: 4 })
+        ));
+    });
+
+    // Nested vectors vary in width, so they never match a fixed width.
+    let bytes = encoded(&[Vec::from(&[1u8][..])], ByteOrder::NATIVE);
+    assert!(matches!(
+        decode::<u64, _>(&mut &bytes[..]),
+        Err(DecodeError::ElementSize { expected: 8, found: 0 })
+    ));
+}
+
+#[test]
+fn arrays_of_owned_elements_round_trip() {
+    let v = [
+        [Vec::from(&[1u8, 2][..]), Vec::new()],
+        [Vec::from(&[3u8][..]), Vec::from(&[4u8][..])],
+    ];
+    for order in [ByteOrder::Little, ByteOrder::Big] {
+        let bytes = encoded(&v, order);
+        assert_eq!(decode::<[Vec<u8>; 2], _>(&mut &bytes[..]).unwrap(), v);
+        let cut = &bytes[..bytes.len() - 1];
+        assert!(matches!(decode::<[Vec<u8>; 2], _>(&mut &cut[..]), Err(DecodeError::Truncated)));
+    }
+}
+
+#[test]
+fn a_failed_array_drops_the_elements_decoded_so_far() {
+    let bytes = encoded(&[[Counted(1), Counted(2), Counted(3)]], ByteOrder::NATIVE);
+    let cut = &bytes[..bytes.len() - 1];
+    let (result, n) = drops(|| decode::<[Counted; 3], _>(&mut &cut[..]));
+    assert!(matches!(result, Err(DecodeError::Truncated)));
+    assert_eq!(n, 2);
+
+    let (v, n) = drops(|| decode::<[Counted; 3], _>(&mut &bytes[..]).unwrap());
+    assert_eq!(n, 0);
+    assert_eq!(v, [[Counted(1), Counted(2), Counted(3)]]);
+    assert_eq!(drops(|| drop(v)).1, 3);
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn elements_too_wide_for_the_header_are_rejected() {
+    let mut bytes = Vec::new();
+    let err = encode::<[u8; 1 << 32], _>(&[], &mut bytes).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(bytes.is_empty(), "nothing is written");
+    assert!(encode::<[u8; u32::MAX as usize], _>(&[], &mut bytes).is_ok());
+}
+
+#[test]
+fn malformed_headers_and_elements_are_rejected() {
+    let mut bytes = encoded(&[1u8], ByteOrder::Little);
+    bytes[0] = b'X';
+    assert!(matches!(decode::<u8, _>(&mut &bytes[..]), Err(DecodeError::BadTag(b'X'))));
+    assert!(matches!(decode_pod_vec::<u8>(&bytes), Err(DecodeError::BadTag(b'X'))));
+
+    let mut bytes = encoded(&[true], ByteOrder::Little);
+    bytes[HEADER_SIZE] = 2;
+    assert!(matches!(decode::<bool, _>(&mut &bytes[..]), Err(DecodeError::InvalidValue)));
+
+    let mut bytes = encoded(&['a'], ByteOrder::Little);
+    bytes[HEADER_SIZE..].copy_from_slice(&0xD800u32.to_le_bytes());
+    assert!(matches!(decode::<char, _>(&mut &bytes[..]), Err(DecodeError::InvalidValue)));
+}
+
+#[test]
+fn pod_encoding_matches_encode_and_decodes_in_place() {
+    let v = [1u64, 2, u64::MAX];
+    let mut bytes = Vec::new();
+    encode_pod(&v, &mut bytes).unwrap();
+    assert_eq!(bytes, encoded(&v, ByteOrder::NATIVE));
+    bytes.extend_from_slice(b"rest");
+
+    with_aligned(&bytes, |bytes| {
+        let (values, rest) = decode_pod::<u64>(bytes).unwrap();
+        assert_eq!(values, v);
+        assert_eq!(values.as_ptr() as *const u8, bytes[HEADER_SIZE..].as_ptr());
+        assert_eq!(rest, b"rest");
+
+        // The same bytes one past an aligned address.
+        let mut shifted = StdVec::from(&[0][..]);
+        shifted.extend_from_slice(bytes);
+        with_aligned(&shifted, |shifted| {
+            assert!(matches!(decode_pod::<u64>(&shifted[1..]), Err(DecodeError::Misaligned)));
+            assert_eq!(decode_pod_vec::<u64>(&shifted[1..]).unwrap(), v);
+        });
+    });
+}
+
+#[test]
+fn io_errors_other_than_eof_are_kept() {
+    struct Failing;
+
+    impl Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk on fire"))
+        }
+    }
+
+    let err = decode::<u8, _>(&mut Failing).unwrap_err();
+    assert!(matches!(err, DecodeError::Io(_)));
+    assert!(err.source().is_some());
+    assert!(err.to_string().contains("disk on fire"));
+}
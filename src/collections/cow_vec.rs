@@ -0,0 +1,300 @@
+//! A copy-on-write vector whose clones share one buffer, written `CowVec<T>`.
+//!
+//! Cloning a [`CowVec`] only bumps a reference count. The first mutation
+//! through a clone that shares its buffer copies the elements, so that it
+//! has a buffer of its own; after that, mutations are as cheap as on a
+//! [`Vec`]. This makes a `CowVec` a cheap snapshot of data that is read far
+//! more often than it is written.
+//!
+//! Converting between `Vec<T>` and `CowVec<T>` never copies the elements,
+//! except when turning a `CowVec` that is still shared back into a `Vec`.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_collections::cow_vec::CowVec;
+//! use rusty_collections::{vec, vec::Vec};
+//!
+//! let config = CowVec::from(vec![1, 2, 3]);
+//!
+//! // Cheap: both point at the same buffer.
+//! let mut snapshot = config.clone();
+//! assert!(CowVec::ptr_eq(&config, &snapshot));
+//!
+//! // The first write copies, leaving `config` alone.
+//! snapshot.make_mut().push(4);
+//! assert_eq!(config, [1, 2, 3]);
+//! assert_eq!(snapshot, [1, 2, 3, 4]);
+//!
+//! // `snapshot` is the only owner of its buffer now, so this does not copy.
+//! let v: Vec<i32> = snapshot.into();
+//! assert_eq!(v, [1, 2, 3, 4]);
+//! ```
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::Deref;
+use core::slice;
+
+use std::sync::Arc;
+
+use crate::vec::Vec;
+
+#[cfg(test)]
+mod tests;
+
+/// A vector that shares its buffer with its clones until one of them
+/// mutates it.
+///
+/// `CowVec<T>` derefs to `[T]` for reading. Mutations go through
+/// [`make_mut`], which copies the elements first if the buffer is shared.
+///
+/// See the [module documentation](self) for an overview.
+///
+/// [`make_mut`]: CowVec::make_mut
+pub struct CowVec<T> {
+    inner: Arc<Vec<T>>,
+}
+
+impl<T> CowVec<T> {
+    /// Constructs a new, empty `CowVec<T>`.
+    pub fn new() -> Self {
+        CowVec { inner: Arc::new(Vec::new()) }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Extracts a slice containing the entire vector.
+    pub fn as_slice(&self) -> &[T] {
+        &self.inner
+    }
+
+    /// Returns `true` if the two vectors share a buffer.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.inner, &other.inner)
+    }
+
+    /// Returns `true` if no other `CowVec` shares this vector's buffer, so
+    /// that mutating it will not copy.
+    pub fn is_unique(&mut self) -> bool {
+        Arc::get_mut(&mut self.inner).is_some()
+    }
+
+    /// Returns a mutable reference to the underlying `Vec` if the buffer is
+    /// not shared, and `None` otherwise.
+    ///
+    /// Unlike [`make_mut`](CowVec::make_mut), this never copies and does
+    /// not need `T: Clone`.
+    pub fn get_mut(&mut self) -> Option<&mut Vec<T>> {
+        Arc::get_mut(&mut self.inner)
+    }
+
+    /// Removes all elements.
+    ///
+    /// A shared buffer is left to its other owners rather than copied.
+    pub fn clear(&mut self) {
+        match Arc::get_mut(&mut self.inner) {
+            Some(vec) => vec.clear(),
+            None => self.inner = Arc::new(Vec::new()),
+        }
+    }
+}
+
+impl<T: Clone> CowVec<T> {
+    /// Returns a mutable reference to the underlying `Vec`, copying the
+    /// elements into a buffer of its own first if the buffer is shared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::cow_vec::CowVec;
+    /// use rusty_collections::vec;
+    ///
+    /// let a = CowVec::from(vec![1, 2, 3]);
+    /// let mut b = a.clone();
+    ///
+    /// b.make_mut()[0] = 10; // copies
+    /// b.make_mut()[1] = 20; // does not copy again
+    /// assert_eq!(a, [1, 2, 3]);
+    /// assert_eq!(b, [10, 20, 3]);
+    /// ```
+    pub fn make_mut(&mut self) -> &mut Vec<T> {
+        Arc::make_mut(&mut self.inner)
+    }
+
+    /// Appends an element to the back of the vector.
+    ///
+    /// Copies the elements first if the buffer is shared.
+    pub fn push(&mut self, value: T) {
+        self.make_mut().push(value);
+    }
+
+    /// Removes the last element and returns it, or `None` if the vector is
+    /// empty.
+    ///
+    /// If the buffer is shared, the last element is cloned and only the
+    /// elements that are kept are copied.
+    pub fn pop(&mut self) -> Option<T> {
+        if let Some(vec) = Arc::get_mut(&mut self.inner) {
+            return vec.pop();
+        }
+        let last = self.last()?.clone();
+        self.truncate(self.len() - 1);
+        Some(last)
+    }
+
+    /// Shortens the vector to `len` elements.
+    ///
+    /// If the buffer is shared, only the elements that are kept are copied.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len() {
+            return;
+        }
+        match Arc::get_mut(&mut self.inner) {
+            Some(vec) => vec.truncate(len),
+            None => self.inner = Arc::new(Vec::from(&self.inner[..len])),
+        }
+    }
+
+    /// Converts the `CowVec` into a `Vec`, copying the elements only if the
+    /// buffer is shared.
+    pub fn into_vec(self) -> Vec<T> {
+        Arc::try_unwrap(self.inner).unwrap_or_else(|shared| (*shared).clone())
+    }
+}
+
+impl<T> Clone for CowVec<T> {
+    /// Makes a clone that shares the buffer, without copying any elements.
+    fn clone(&self) -> Self {
+        CowVec { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<T> Default for CowVec<T> {
+    fn default() -> Self {
+        CowVec::new()
+    }
+}
+
+impl<T> Deref for CowVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.inner
+    }
+}
+
+impl<T> AsRef<[T]> for CowVec<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.inner
+    }
+}
+
+impl<T> Borrow<[T]> for CowVec<T> {
+    fn borrow(&self) -> &[T] {
+        &self.inner
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CowVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: Hash> Hash for CowVec<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<CowVec<U>> for CowVec<T> {
+    fn eq(&self, other: &CowVec<U>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for CowVec<T> {
+    fn eq(&self, other: &[U]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for CowVec<T> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<Vec<U>> for CowVec<T> {
+    fn eq(&self, other: &Vec<U>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Eq> Eq for CowVec<T> {}
+
+impl<T: PartialOrd> PartialOrd for CowVec<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<T: Ord> Ord for CowVec<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<T> From<Vec<T>> for CowVec<T> {
+    /// Wraps the vector without copying its elements.
+    fn from(vec: Vec<T>) -> Self {
+        CowVec { inner: Arc::new(vec) }
+    }
+}
+
+impl<T: Clone> From<CowVec<T>> for Vec<T> {
+    /// Unwraps the vector, copying its elements only if the buffer is
+    /// shared.
+    fn from(vec: CowVec<T>) -> Self {
+        vec.into_vec()
+    }
+}
+
+impl<T: Clone> From<&[T]> for CowVec<T> {
+    fn from(s: &[T]) -> Self {
+        CowVec::from(Vec::from(s))
+    }
+}
+
+impl<T> FromIterator<T> for CowVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        CowVec::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
+impl<T: Clone> Extend<T> for CowVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.make_mut().extend(iter)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a CowVec<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
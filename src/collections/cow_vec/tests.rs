@@ -0,0 +1,109 @@
+use super::*;
+
+use crate::test_util::{clones, Counted};
+
+fn counted(len: u32) -> CowVec<Counted> {
+    (0..len).map(Counted).collect()
+}
+
+#[test]
+fn clones_share_the_buffer() {
+    let mut a = counted(5);
+    assert!(a.is_unique());
+    let (mut b, n) = clones(|| a.clone());
+    assert_eq!(n, 0);
+    assert!(CowVec::ptr_eq(&a, &b));
+    assert_eq!(a.as_ptr(), b.as_ptr());
+    assert!(!a.is_unique());
+    assert!(b.get_mut().is_none());
+
+    drop(b);
+    assert!(a.is_unique());
+    assert!(a.get_mut().is_some());
+}
+
+#[test]
+fn the_first_write_copies_and_later_ones_do_not() {
+    let a = counted(5);
+    let mut b = a.clone();
+    assert_eq!(clones(|| b.make_mut()[0] = Counted(10)).1, 5);
+    assert!(!CowVec::ptr_eq(&a, &b));
+    assert_ne!(a.as_ptr(), b.as_ptr());
+    assert_eq!(a[0], Counted(0));
+    assert_eq!(b[0], Counted(10));
+
+    let ptr = b.as_ptr();
+    assert_eq!(clones(|| b.make_mut()[1] = Counted(20)).1, 0);
+    assert_eq!(b.as_ptr(), ptr);
+    assert_eq!(clones(|| b.push(Counted(5))).1, 0);
+    assert_eq!(b.len(), 6);
+    assert_eq!(clones(|| b.extend([Counted(6)])).1, 0);
+    assert_eq!(b.len(), 7);
+    assert_eq!(a.len(), 5);
+}
+
+#[test]
+fn shrinking_a_shared_buffer_copies_only_what_is_kept() {
+    let a = counted(10);
+
+    let mut b = a.clone();
+    // The popped element is cloned, as are the nine that stay.
+    let (popped, n) = clones(|| b.pop());
+    assert_eq!(n, 10);
+    assert_eq!(popped, Some(Counted(9)));
+    assert_eq!(b.len(), 9);
+
+    let mut c = a.clone();
+    assert_eq!(clones(|| c.truncate(3)).1, 3);
+    assert_eq!(c, [Counted(0), Counted(1), Counted(2)]);
+    assert_eq!(clones(|| c.truncate(5)).1, 0);
+
+    let mut d = a.clone();
+    assert_eq!(clones(|| d.clear()).1, 0);
+    assert!(d.is_empty());
+    assert_eq!(a.len(), 10);
+
+    // Unshared, nothing is cloned.
+    let mut e = counted(10);
+    assert_eq!(clones(|| assert_eq!(e.pop(), Some(Counted(9)))).1, 0);
+    assert_eq!(clones(|| e.truncate(2)).1, 0);
+    assert_eq!(e.len(), 2);
+}
+
+#[test]
+fn into_vec_copies_only_when_shared() {
+    let a = counted(5);
+    let ptr = a.as_ptr();
+    let (vec, n) = clones(|| a.into_vec());
+    assert_eq!(n, 0);
+    assert_eq!(vec.as_ptr(), ptr);
+
+    // And back again, without a copy either.
+    let a = CowVec::from(vec);
+    assert_eq!(a.as_ptr(), ptr);
+
+    let b = a.clone();
+    let (vec, n) = clones(|| Vec::from(b));
+    assert_eq!(n, 5);
+    assert_ne!(vec.as_ptr(), ptr);
+
+    // `b` is gone, so `a` owns its buffer again.
+    let (vec, n) = clones(|| a.into_vec());
+    assert_eq!(n, 0);
+    assert_eq!(vec.as_ptr(), ptr);
+}
+
+#[test]
+fn comparisons_and_conversions() {
+    let a: CowVec<i32> = CowVec::from(&[1, 2, 3][..]);
+    let b = a.clone();
+    assert_eq!(a, b);
+    assert_eq!(a, [1, 2, 3]);
+    assert_eq!(a, *[1, 2, 3].as_slice());
+    assert_eq!(a, Vec::from(&[1, 2, 3][..]));
+    assert!(a < b.iter().map(|x| x + 1).collect());
+    assert_eq!(format!("{:?}", a), "[1, 2, 3]");
+    assert_eq!((&a).into_iter().sum::<i32>(), 6);
+    assert!(CowVec::<i32>::new().is_empty());
+    assert!(CowVec::<i32>::default().is_empty());
+}
@@ -0,0 +1,407 @@
+//! Differences between two slices, and patches that turn one into the other.
+//!
+//! [`diff`] compares an old and a new slice with Myers' algorithm and returns
+//! a shortest edit script: a list of [`DiffOp`]s that keep, delete or insert
+//! runs of elements. It takes *O*((*n* + *m*) · *d*) time for slices of
+//! lengths *n* and *m* that differ in *d* elements, and *O*(*n* + *m*) extra
+//! memory, so similar slices are compared quickly whatever their length.
+//!
+//! [`patch`] turns the script into a self-contained list of [`Change`]s,
+//! carrying copies of the inserted elements. Sending it instead of the new
+//! slice is cheaper when little has changed, and [`apply_patch`] applies it
+//! to a copy of the old slice in place.
+//!
+//! [`unified_diff`] renders the difference between two lists of lines the
+//! way `diff -u` does.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_collections::diff::{self, Change, DiffOp};
+//! use rusty_collections::vec;
+//!
+//! let old = [1, 2, 3, 4, 5];
+//! let new = [1, 3, 4, 9, 5];
+//! use DiffOp::{Delete, Insert, Keep};
+//! assert_eq!(diff::diff(&old, &new), [Keep(1), Delete(1), Keep(2), Insert(3..4), Keep(1)]);
+//!
+//! let patch = diff::patch(&old, &new);
+//! assert_eq!(patch[3], Change::Insert(vec![9]));
+//!
+//! let mut v = vec![1, 2, 3, 4, 5];
+//! diff::apply_patch(&mut v, patch);
+//! assert_eq!(v, new);
+//! ```
+
+use core::cmp;
+use core::fmt::Write as _;
+use core::ops::Range;
+
+use crate::vec::Vec;
+
+#[cfg(test)]
+mod tests;
+
+/// One step of an edit script returned by [`diff`].
+///
+/// The steps are applied in order, each starting where the previous one
+/// stopped in the old and in the new slice.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DiffOp {
+    /// The next `n` elements are the same in both slices.
+    Keep(usize),
+    /// The next `n` elements of the old slice are not in the new one.
+    Delete(usize),
+    /// The elements of the new slice in the range are not in the old one.
+    Insert(Range<usize>),
+}
+
+/// One step of a patch returned by [`patch`].
+///
+/// Unlike a [`DiffOp`], a `Change` carries the elements it inserts, so a
+/// patch can be applied without the new slice.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Change<T> {
+    /// Skips over the next `n` elements.
+    Keep(usize),
+    /// Removes the next `n` elements.
+    Delete(usize),
+    /// Inserts the elements.
+    Insert(Vec<T>),
+}
+
+/// Returns a shortest edit script that turns `old` into `new`.
+///
+/// Runs of the same operation are merged, and a deletion and an insertion
+/// between the same two kept runs always come in that order.
+pub fn diff<T: PartialEq>(old: &[T], new: &[T]) -> Vec<DiffOp> {
+    let mut script = Script::default();
+    // Room for the diagonals `-max - 1..=max + 1` that `middle_snake` uses.
+    let max = (old.len() + new.len()).div_ceil(2);
+    let mut forward = vec![0; 2 * max + 3];
+    let mut backward = vec![0; 2 * max + 3];
+    diff_range(old, new, 0, 0, &mut script, &mut forward, &mut backward);
+    script.finish()
+}
+
+/// Returns the patch that turns `old` into `new`, built from [`diff`].
+pub fn patch<T: PartialEq + Clone>(old: &[T], new: &[T]) -> Vec<Change<T>> {
+    diff(old, new)
+        .into_iter()
+        .map(|op| match op {
+            DiffOp::Keep(n) => Change::Keep(n),
+            DiffOp::Delete(n) => Change::Delete(n),
+            DiffOp::Insert(range) => Change::Insert(Vec::from(&new[range])),
+        })
+        .collect()
+}
+
+/// Applies `patch` to `vec` in place.
+///
+/// A deletion followed by an insertion becomes a single [`splice`], and a
+/// deletion alone a [`drain`]. Elements after the last step of the patch
+/// are kept.
+///
+/// [`splice`]: Vec::splice
+/// [`drain`]: Vec::drain
+///
+/// # Panics
+///
+/// Panics if the patch keeps or deletes more elements than `vec` has.
+pub fn apply_patch<T, I>(vec: &mut Vec<T>, patch: I)
+where
+    I: IntoIterator<Item = Change<T>>,
+{
+    let mut at = 0;
+    let mut patch = patch.into_iter().peekable();
+    while let Some(change) = patch.next() {
+        match change {
+            Change::Keep(n) => {
+                at += n;
+                assert!(at <= vec.len(), "patch keeps more elements than there are");
+            }
+            Change::Delete(n) => {
+                let end = at + n;
+                assert!(end <= vec.len(), "patch deletes more elements than there are");
+                if let Some(Change::Insert(_)) = patch.peek() {
+                    let Some(Change::Insert(values)) = patch.next() else { unreachable!() };
+                    let inserted = values.len();
+                    vec.splice(at..end, values);
+                    at += inserted;
+                } else {
+                    vec.drain(at..end);
+                }
+            }
+            Change::Insert(values) => {
+                assert!(at <= vec.len(), "patch inserts past the end");
+                let inserted = values.len();
+                vec.splice(at..at, values);
+                at += inserted;
+            }
+        }
+    }
+}
+
+/// Renders the difference between two lists of lines in the unified
+/// format of `diff -u`, with `context` unchanged lines around each change.
+///
+/// Only the hunks are rendered, each starting with an `@@` header; callers
+/// that want the `---` and `+++` file headers write them first. Every line
+/// ends in `\n`. Equal lists render as the empty string.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_collections::diff::unified_diff;
+///
+/// let old = ["a", "b", "c", "d"];
+/// let new = ["a", "c", "d", "e"];
+/// assert_eq!(unified_diff(&old, &new, 1), "@@ -1,4 +1,4 @@\n a\n-b\n c\n d\n+e\n");
+/// ```
+pub fn unified_diff<S: AsRef<str>>(old: &[S], new: &[S], context: usize) -> String {
+    let old: Vec<&str> = old.iter().map(AsRef::as_ref).collect();
+    let new: Vec<&str> = new.iter().map(AsRef::as_ref).collect();
+
+    // The changed blocks, as ranges of the old and the new lines.
+    let mut blocks: Vec<(Range<usize>, Range<usize>)> = Vec::new();
+    let (mut o, mut n) = (0, 0);
+    for op in diff(&old, &new) {
+        let (old_len, new_len) = match op {
+            DiffOp::Keep(len) => {
+                o += len;
+                n += len;
+                continue;
+            }
+            DiffOp::Delete(len) => (len, 0),
+            DiffOp::Insert(range) => (0, range.len()),
+        };
+        match blocks.last_mut() {
+            Some((old_range, new_range)) if old_range.end == o && new_range.end == n => {
+                old_range.end += old_len;
+                new_range.end += new_len;
+            }
+            _ => blocks.push((o..o + old_len, n..n + new_len)),
+        }
+        o += old_len;
+        n += new_len;
+    }
+
+    let mut out = String::new();
+    let mut blocks = blocks.iter().peekable();
+    while let Some(first) = blocks.next() {
+        // Blocks whose contexts would touch share a hunk.
+        let mut hunk = vec![first];
+        while let Some(&next) = blocks.peek() {
+            if next.0.start - hunk[hunk.len() - 1].0.end > 2 * context {
+                break;
+            }
+            hunk.push(next);
+            blocks.next();
+        }
+
+        let (first, last) = (hunk[0], hunk[hunk.len() - 1]);
+        let before = cmp::min(context, first.0.start);
+        let after = cmp::min(context, old.len() - last.0.end);
+        let old_hunk = first.0.start - before..last.0.end + after;
+        let new_hunk = first.1.start - before..last.1.end + after;
+        writeln!(out, "@@ -{} +{} @@", HunkRange(&old_hunk), HunkRange(&new_hunk)).unwrap();
+
+        let mut o = old_hunk.start;
+        for (old_range, new_range) in hunk {
+            for line in &old[o..old_range.start] {
+                writeln!(out, " {}", line).unwrap();
+            }
+            for line in &old[old_range.clone()] {
+                writeln!(out, "-{}", line).unwrap();
+            }
+            for line in &new[new_range.clone()] {
+                writeln!(out, "+{}", line).unwrap();
+            }
+            o = old_range.end;
+        }
+        for line in &old[o..old_hunk.end] {
+            writeln!(out, " {}", line).unwrap();
+        }
+    }
+    out
+}
+
+// A range of lines in a hunk header: `start,len` with `start` counting from
+// one, or just `start` for a single line. An empty range names the line
+// before it, as `diff -u` does.
+struct HunkRange<'a>(&'a Range<usize>);
+
+impl core::fmt::Display for HunkRange<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.0.len() {
+            0 => write!(f, "{},0", self.0.start),
+            1 => write!(f, "{}", self.0.start + 1),
+            len => write!(f, "{},{}", self.0.start + 1, len),
+        }
+    }
+}
+
+/// Collects the edit script, merging runs of the same operation and
+/// putting deletions before insertions.
+#[derive(Default)]
+struct Script {
+    ops: Vec<DiffOp>,
+    /// The pending run of kept elements.
+    keep: usize,
+    /// The pending deletions and insertions since the last kept run.
+    delete: usize,
+    insert: Option<Range<usize>>,
+}
+
+impl Script {
+    fn keep(&mut self, n: usize) {
+        if n == 0 {
+            return;
+        }
+        self.flush_changes();
+        self.keep += n;
+    }
+
+    fn delete(&mut self, n: usize) {
+        if n == 0 {
+            return;
+        }
+        self.flush_keep();
+        self.delete += n;
+    }
+
+    fn insert(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        self.flush_keep();
+        // The inserted ranges of the new slice are produced in order, so
+        // they always join up.
+        self.insert = match self.insert.take() {
+            Some(pending) => Some(pending.start..range.end),
+            None => Some(range),
+        };
+    }
+
+    fn flush_keep(&mut self) {
+        if self.keep > 0 {
+            self.ops.push(DiffOp::Keep(self.keep));
+            self.keep = 0;
+        }
+    }
+
+    fn flush_changes(&mut self) {
+        if self.delete > 0 {
+            self.ops.push(DiffOp::Delete(self.delete));
+            self.delete = 0;
+        }
+        if let Some(range) = self.insert.take() {
+            self.ops.push(DiffOp::Insert(range));
+        }
+    }
+
+    fn finish(mut self) -> Vec<DiffOp> {
+        self.flush_keep();
+        self.flush_changes();
+        self.ops
+    }
+}
+
+// Diffs `old` against `new`, which start at `old_start` and `new_start` in
+// the slices passed to `diff`, and appends the script to `script`.
+fn diff_range<T: PartialEq>(
+    old: &[T],
+    new: &[T],
+    old_start: usize,
+    new_start: usize,
+    script: &mut Script,
+    forward: &mut [usize],
+    backward: &mut [usize],
+) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let (old, new) = (&old[prefix..], &new[prefix..]);
+    let suffix = old.iter().rev().zip(new.iter().rev()).take_while(|(a, b)| a == b).count();
+    let (old, new) = (&old[..old.len() - suffix], &new[..new.len() - suffix]);
+    let (old_start, new_start) = (old_start + prefix, new_start + prefix);
+
+    script.keep(prefix);
+    if old.is_empty() || new.is_empty() {
+        script.delete(old.len());
+        script.insert(new_start..new_start + new.len());
+    } else {
+        let (x, y, len) = middle_snake(old, new, forward, backward);
+        diff_range(&old[..x], &new[..y], old_start, new_start, script, forward, backward);
+        script.keep(len);
+        let (x, y) = (x + len, y + len);
+        diff_range(&old[x..], &new[y..], old_start + x, new_start + y, script, forward, backward);
+    }
+    script.keep(suffix);
+}
+
+// Finds the middle snake of a shortest edit script between `old` and `new`:
+// a run of equal elements, possibly empty, that such a script keeps half way
+// through its edits. Returns where the run starts in `old` and `new`, and
+// its length.
+//
+// `old` and `new` must not be empty, and must differ in their first and in
+// their last elements, so that the script has at least two edits and both
+// halves around the snake are smaller problems.
+//
+// `forward[k]` and `backward[k]` hold the furthest `x` reached on diagonal
+// `k - offset` from the start and from the end, as in Myers' paper.
+fn middle_snake<T: PartialEq>(
+    old: &[T],
+    new: &[T],
+    forward: &mut [usize],
+    backward: &mut [usize],
+) -> (usize, usize, usize) {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let delta = n - m;
+    let odd = delta % 2 != 0;
+    let max = (n + m + 1) / 2;
+    let offset = max + 1;
+    let at = |k: isize| (k + offset) as usize;
+
+    forward[at(1)] = 0;
+    backward[at(1)] = 0;
+    for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && forward[at(k - 1)] < forward[at(k + 1)]) {
+                forward[at(k + 1)] as isize
+            } else {
+                forward[at(k - 1)] as isize + 1
+            };
+            let mut y = x - k;
+            let (x0, y0) = (x, y);
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            forward[at(k)] = x as usize;
+            let c = delta - k;
+            if odd && -d < c && c < d && x + backward[at(c)] as isize >= n {
+                return (x0 as usize, y0 as usize, (x - x0) as usize);
+            }
+        }
+
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && backward[at(k - 1)] < backward[at(k + 1)]) {
+                backward[at(k + 1)] as isize
+            } else {
+                backward[at(k - 1)] as isize + 1
+            };
+            let mut y = x - k;
+            let x0 = x;
+            while x < n && y < m && old[(n - 1 - x) as usize] == new[(m - 1 - y) as usize] {
+                x += 1;
+                y += 1;
+            }
+            backward[at(k)] = x as usize;
+            let c = delta - k;
+            if !odd && -d <= c && c <= d && x + forward[at(c)] as isize >= n {
+                return ((n - x) as usize, (m - y) as usize, (x - x0) as usize);
+            }
+        }
+    }
+    unreachable!("the forward and backward searches must meet")
+}
@@ -0,0 +1,122 @@
+use super::*;
+
+use std::vec::Vec as StdVec;
+
+use crate::test_util::Rng;
+
+// A slice over a small alphabet, so that there is something in common.
+fn slice(rng: &mut Rng, alphabet: u8) -> StdVec<u8> {
+    let len = rng.below(40);
+    (0..len).map(|_| (rng.next() % alphabet as u64) as u8).collect()
+}
+
+// The length of the longest common subsequence, the slow way.
+fn lcs_len(a: &[u8], b: &[u8]) -> usize {
+    let mut row = vec![0usize; b.len() + 1];
+    for x in a {
+        let mut diag = 0;
+        for (j, y) in b.iter().enumerate() {
+            let up = row[j + 1];
+            row[j + 1] = if x == y { diag + 1 } else { cmp::max(up, row[j]) };
+            diag = up;
+        }
+    }
+    row[b.len()]
+}
+
+// Replays the script, checking that it turns `old` into `new`, and returns
+// the number of deleted and inserted elements.
+fn replay(old: &[u8], new: &[u8], script: &[DiffOp]) -> usize {
+    let (mut o, mut n, mut edits) = (0, 0, 0);
+    for op in script {
+        match op {
+            DiffOp::Keep(len) => {
+                assert_eq!(old[o..o + len], new[n..n + len]);
+                o += len;
+                n += len;
+            }
+            DiffOp::Delete(len) => {
+                o += len;
+                edits += len;
+            }
+            DiffOp::Insert(range) => {
+                assert_eq!(range.start, n);
+                n = range.end;
+                edits += range.len();
+            }
+        }
+    }
+    assert_eq!((o, n), (old.len(), new.len()));
+    edits
+}
+
+#[test]
+fn diff_is_a_shortest_edit_script() {
+    let mut rng = Rng::new(1);
+    for _ in 0..2000 {
+        let alphabet = 1 + rng.below(6) as u8;
+        let old = slice(&mut rng, alphabet);
+        let new = if rng.below(2) == 0 {
+            slice(&mut rng, alphabet)
+        } else {
+            // A few edits away from `old`.
+            let mut new = old.clone();
+            for _ in 0..rng.below(5) {
+                if !new.is_empty() && rng.below(2) == 0 {
+                    new.remove(rng.below(new.len()));
+                } else {
+                    new.insert(rng.below(new.len() + 1), rng.next() as u8 % alphabet);
+                }
+            }
+            new
+        };
+
+        let script = diff(&old, &new);
+        let edits = replay(&old, &new, &script);
+        assert_eq!(edits, old.len() + new.len() - 2 * lcs_len(&old, &new), "{:?} {:?}", old, new);
+
+        // Merged runs, with deletions before insertions.
+        for pair in script.windows(2) {
+            match pair {
+                [DiffOp::Keep(_), DiffOp::Keep(_)]
+                | [DiffOp::Delete(_), DiffOp::Delete(_)]
+                | [DiffOp::Insert(_), DiffOp::Insert(_)]
+                | [DiffOp::Insert(_), DiffOp::Delete(_)] => panic!("{:?}", script),
+                _ => {}
+            }
+        }
+
+        let mut v = Vec::from(&old[..]);
+        apply_patch(&mut v, patch(&old, &new));
+        assert_eq!(v, new[..]);
+    }
+}
+
+#[test]
+fn unified_diff_splits_distant_changes_into_hunks() {
+    let old: StdVec<String> = (1..=12).map(|i| i.to_string()).collect();
+    let mut new = old.clone();
+    new[1] = "two".to_string();
+    new.remove(10);
+    new.push("13".to_string());
+
+    let expected = "\
+@@ -1,5 +1,5 @@
+ 1
+-2
++two
+ 3
+ 4
+ 5
+@@ -8,5 +8,5 @@
+ 8
+ 9
+ 10
+-11
+ 12
++13
+";
+    assert_eq!(unified_diff(&old, &new, 3), expected);
+    assert_eq!(unified_diff(&old, &old, 3), "");
+    assert_eq!(unified_diff(&[] as &[&str], &["a"], 3), "@@ -0,0 +1 @@\n+a\n");
+}
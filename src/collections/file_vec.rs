@@ -0,0 +1,388 @@
+//! A vector of plain-old-data elements stored in a memory-mapped file.
+//!
+//! [`FileVec<T>`] is a [`Vec<T, FileAlloc>`]: the buffer `RawVec` allocates,
+//! grows and shrinks is a shared mapping of a file, so every element written
+//! to the vector is written to the file. Growing the vector extends the file
+//! and remaps it, shrinking truncates it.
+//!
+//! The file starts with a small header holding the element size and the
+//! length of the vector. Opening the same file again restores the vector
+//! exactly as it was, without a serialize or deserialize step.
+//!
+//! The length in the header is only updated by [`FileVec::sync`] and when the
+//! `FileVec` is dropped. Elements pushed after the last sync are in the file,
+//! but a process that dies before syncing reopens with the old length.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use rusty_collections::file_vec::FileVec;
+//!
+//! #[derive(Clone, Copy)]
+//! #[repr(C)]
+//! struct Point {
+//!     x: f64,
+//!     y: f64,
+//! }
+//!
+//! unsafe impl rusty_collections::pod::Pod for Point {}
+//!
+//! let mut points = FileVec::<Point>::open("points.bin")?;
+//! points.push(Point { x: 1.0, y: 2.0 });
+//! drop(points);
+//!
+//! let points = FileVec::<Point>::open("points.bin")?;
+//! assert_eq!(points.last().unwrap().y, 2.0);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+#![cfg(target_os = "linux")]
+
+use core::cell::Cell;
+use core::fmt;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::raw::{c_int, c_long, c_void};
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+
+use crate::alloc::{AllocError, Allocator, Layout};
+use crate::pod::Pod;
+use crate::raw_vec::debug_memory::{self, CANARY_LEN};
+use crate::vec::Vec;
+
+#[cfg(test)]
+mod tests;
+
+extern "C" {
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: c_int,
+        flags: c_int,
+        fd: c_int,
+        offset: c_long,
+    ) -> *mut c_void;
+    fn mremap(old_address: *mut c_void, old_size: usize, new_size: usize, flags: c_int, ...)
+    -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    fn msync(addr: *mut c_void, len: usize, flags: c_int) -> c_int;
+}
+
+const PROT_READ: c_int = 1;
+const PROT_WRITE: c_int = 2;
+const MAP_SHARED: c_int = 1;
+const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+const MREMAP_MAYMOVE: c_int = 1;
+const MS_SYNC: c_int = 4;
+
+const MAGIC: [u8; 8] = *b"RUSTYVEC";
+
+/// Size of the header in front of the elements. Mappings are page aligned,
+/// so elements aligned to at most this much are aligned in the file too.
+const HEADER_SIZE: usize = 64;
+
+#[repr(C)]
+struct Header {
+    magic: [u8; 8],
+    elem_size: u64,
+    elem_align: u64,
+    len: u64,
+}
+
+/// An allocator whose only allocation is a shared mapping of a file.
+///
+/// `FileAlloc` backs [`FileVec`] and cannot be created on its own. It holds
+/// at most one buffer at a time: the mapping covers the header followed by
+/// the buffer, and allocating, growing or shrinking resizes the file and
+/// the mapping together. Deallocating unmaps the file but leaves its
+/// contents alone.
+pub struct FileAlloc {
+    file: File,
+    elem_size: usize,
+    elem_align: usize,
+    map: Cell<*mut u8>,
+    map_len: Cell<usize>,
+}
+
+impl FileAlloc {
+    fn header(&self) -> Option<*mut Header> {
+        let map = self.map.get();
+        if map.is_null() { None } else { Some(map as *mut Header) }
+    }
+
+    // Makes the mapping cover the header plus `size` bytes of buffer,
+    // resizing the file along with it, and returns the buffer.
+    fn resize(&self, size: usize) -> Result<NonNull<[u8]>, AllocError> {
+        let new_len = size.checked_add(HEADER_SIZE).ok_or(AllocError)?;
+        let old_len = self.map_len.get();
+        let old_map = self.map.get();
+
+        // Grow the file before mapping more of it, shrink it only after the
+        // mapping no longer covers the tail.
+        if old_map.is_null() || new_len > old_len {
+            self.file.set_len(new_len as u64).map_err(|_| AllocError)?;
+        }
+
+        let map = unsafe {
+            if old_map.is_null() {
+                mmap(
+                    ptr::null_mut(),
+                    new_len,
+                    PROT_READ | PROT_WRITE,
+                    MAP_SHARED,
+                    self.file.as_raw_fd(),
+                    0,
+                )
+            } else {
+                mremap(old_map as *mut c_void, old_len, new_len, MREMAP_MAYMOVE)
+            }
+        };
+        if map == MAP_FAILED {
+            return Err(AllocError);
+        }
+        self.map.set(map as *mut u8);
+        self.map_len.set(new_len);
+
+        if !old_map.is_null() && new_len < old_len {
+            // The mapping is already smaller, so a failure here only wastes
+            // some disk space.
+            let _ = self.file.set_len(new_len as u64);
+        }
+
+        let data = unsafe { NonNull::new_unchecked((map as *mut u8).add(HEADER_SIZE)) };
+        Ok(NonNull::slice_from_raw_parts(data, size))
+    }
+}
+
+unsafe impl Allocator for FileAlloc {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout.align() > HEADER_SIZE {
+            return Err(AllocError);
+        }
+        let fresh = self.map.get().is_null();
+        let memory = self.resize(layout.size())?;
+        if fresh {
+            // A new file, or one that was truncated to nothing: (re)write
+            // the header. The length is filled in by `FileVec::sync`.
+            unsafe {
+                ptr::write(
+                    self.map.get() as *mut Header,
+                    Header {
+                        magic: MAGIC,
+                        elem_size: self.elem_size as u64,
+                        elem_align: self.elem_align as u64,
+                        len: 0,
+                    },
+                );
+            }
+        }
+        Ok(memory)
+    }
+
+    // Freshly extended file space reads as zeroes.
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {
+        let map = self.map.replace(ptr::null_mut());
+        if !map.is_null() {
+            unsafe {
+                munmap(map as *mut c_void, self.map_len.replace(0));
+            }
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        _ptr: NonNull<u8>,
+        _old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.resize(new_layout.size())
+    }
+
+    unsafe fn grow_zeroed(
+        &self,
+        _ptr: NonNull<u8>,
+        _old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.resize(new_layout.size())
+    }
+
+    unsafe fn shrink(
+        &self,
+        _ptr: NonNull<u8>,
+        _old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        self.resize(new_layout.size())
+    }
+}
+
+impl Drop for FileAlloc {
+    fn drop(&mut self) {
+        let map = self.map.get();
+        if !map.is_null() {
+            unsafe {
+                munmap(map as *mut c_void, self.map_len.get());
+            }
+        }
+    }
+}
+
+impl fmt::Debug for FileAlloc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileAlloc").field("file", &self.file).field("len", &self.map_len).finish()
+    }
+}
+
+/// A [`Vec`] of [`Pod`] elements that lives in a memory-mapped file.
+///
+/// `FileVec<T>` derefs to `Vec<T, FileAlloc>`, so everything a `Vec` can do
+/// works on it directly. See the [module documentation](self) for how the
+/// file is laid out and when the length is saved.
+pub struct FileVec<T: Pod> {
+    vec: Vec<T, FileAlloc>,
+}
+
+impl<T: Pod> FileVec<T> {
+    /// Opens the vector stored in the file at `path`, creating an empty one
+    /// if the file does not exist or is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or mapped, or if it
+    /// holds a vector of a different element size or alignment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is zero-sized, or aligned to more than 64 bytes.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<FileVec<T>> {
+        assert!(mem::size_of::<T>() != 0, "zero-sized types cannot be stored in a file");
+        assert!(mem::align_of::<T>() <= HEADER_SIZE, "alignment too large for FileVec");
+
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+        let file_len = file.metadata()?.len() as usize;
+        let alloc = FileAlloc {
+            file,
+            elem_size: mem::size_of::<T>(),
+            elem_align: mem::align_of::<T>(),
+            map: Cell::new(ptr::null_mut()),
+            map_len: Cell::new(0),
+        };
+
+        if file_len == 0 {
+            return Ok(FileVec { vec: Vec::new_in(alloc) });
+        }
+        if file_len < HEADER_SIZE {
+            return Err(invalid_data("file is too short to hold a vector"));
+        }
+
+        let data = alloc.resize(file_len - HEADER_SIZE).map_err(|_| io::Error::last_os_error())?;
+        let header = unsafe { ptr::read(alloc.map.get() as *const Header) };
+        if header.magic != MAGIC {
+            return Err(invalid_data("file does not hold a vector"));
+        }
+        if header.elem_size != mem::size_of::<T>() as u64
+            || header.elem_align != mem::align_of::<T>() as u64
+        {
+            return Err(invalid_data("file holds a vector of a different element type"));
+        }
+
+        let (data, capacity) = if debug_memory::ENABLED {
+            guard_tail::<T>(&alloc, data)?
+        } else {
+            (data, data.len() / mem::size_of::<T>())
+        };
+        let len = header.len as usize;
+        if len > capacity {
+            return Err(invalid_data("vector length exceeds the file size"));
+        }
+
+        let ptr = data.cast::<u8>().as_ptr() as *mut T;
+        let vec = unsafe { Vec::from_raw_parts_unchecked_in(ptr, len, capacity, alloc) };
+        Ok(FileVec { vec })
+    }
+
+    /// Writes the current length to the file header and flushes the mapping
+    /// to disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error reported by `msync`.
+    pub fn sync(&mut self) -> io::Result<()> {
+        let alloc = self.vec.allocator();
+        if let Some(header) = alloc.header() {
+            unsafe {
+                (*header).len = self.vec.len() as u64;
+                if msync(alloc.map.get() as *mut c_void, alloc.map_len.get(), MS_SYNC) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the file backing this vector.
+    pub fn file(&self) -> &File {
+        &self.vec.allocator().file
+    }
+}
+
+impl<T: Pod> Deref for FileVec<T> {
+    type Target = Vec<T, FileAlloc>;
+
+    fn deref(&self) -> &Vec<T, FileAlloc> {
+        &self.vec
+    }
+}
+
+impl<T: Pod> DerefMut for FileVec<T> {
+    fn deref_mut(&mut self) -> &mut Vec<T, FileAlloc> {
+        &mut self.vec
+    }
+}
+
+impl<T: Pod> Drop for FileVec<T> {
+    fn drop(&mut self) {
+        // Only the length needs saving; the kernel writes the mapping back on
+        // its own once it is unmapped.
+        if let Some(header) = self.vec.allocator().header() {
+            unsafe { (*header).len = self.vec.len() as u64 }
+        }
+    }
+}
+
+impl<T: Pod + fmt::Debug> fmt::Debug for FileVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.vec[..], f)
+    }
+}
+
+// Under `debug-memory`, `RawVec` expects a canary after the buffer. Files
+// written with the feature already end in one; others are extended to make
+// room for it. Returns the buffer and its capacity.
+fn guard_tail<T: Pod>(alloc: &FileAlloc, data: NonNull<[u8]>) -> io::Result<(NonNull<[u8]>, usize)> {
+    let size = mem::size_of::<T>();
+    if let Some(guarded) = data.len().checked_sub(CANARY_LEN) {
+        let end = unsafe { data.cast::<u8>().as_ptr().add(guarded) };
+        if guarded % size == 0 && unsafe { debug_memory::has_canary(end) } {
+            return Ok((data, guarded / size));
+        }
+    }
+    let capacity = data.len() / size;
+    let data = alloc.resize(capacity * size + CANARY_LEN).map_err(|_| io::Error::last_os_error())?;
+    unsafe { debug_memory::write_canary(data.cast::<u8>().as_ptr().add(capacity * size)) };
+    Ok((data, capacity))
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
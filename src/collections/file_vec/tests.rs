@@ -0,0 +1,90 @@
+use super::*;
+
+use std::fs;
+use std::path::PathBuf;
+
+// A file in the temporary directory, removed again when dropped.
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str) -> TempFile {
+        let path = std::env::temp_dir().join(format!("file_vec-{}-{}", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        TempFile(path)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn reopening_restores_the_vector() {
+    let file = TempFile::new("reopen");
+
+    let mut v = FileVec::<u64>::open(&file.0).unwrap();
+    assert!(v.is_empty());
+    v.extend(0..1000);
+    drop(v);
+
+    let mut v = FileVec::<u64>::open(&file.0).unwrap();
+    assert_eq!(v.len(), 1000);
+    assert!(v.iter().copied().eq(0..1000));
+
+    // Shrinking truncates the file, and the length survives a sync.
+    v.truncate(10);
+    v.shrink_to_fit();
+    v.sync().unwrap();
+    let file_len = v.file().metadata().unwrap().len() as usize;
+    assert!(file_len < HEADER_SIZE + 1000 * 8);
+    drop(v);
+
+    let v = FileVec::<u64>::open(&file.0).unwrap();
+    assert_eq!(v[..], [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn a_vector_of_another_element_type_is_rejected() {
+    let file = TempFile::new("mismatch");
+    let mut v = FileVec::<u32>::open(&file.0).unwrap();
+    v.push(1);
+    drop(v);
+
+    let err = FileVec::<u64>::open(&file.0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    let err = FileVec::<[u8; 4]>::open(&file.0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    // The file is left alone.
+    assert_eq!(FileVec::<u32>::open(&file.0).unwrap()[..], [1]);
+}
+
+#[test]
+fn files_that_do_not_hold_a_vector_are_rejected() {
+    let file = TempFile::new("garbage");
+
+    fs::write(&file.0, b"too short").unwrap();
+    let err = FileVec::<u8>::open(&file.0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    fs::write(&file.0, [0xAB; 4096]).unwrap();
+    let err = FileVec::<u8>::open(&file.0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn a_truncated_file_is_rejected() {
+    let file = TempFile::new("truncated");
+    let mut v = FileVec::<u64>::open(&file.0).unwrap();
+    v.extend(0..100);
+    drop(v);
+
+    let f = OpenOptions::new().write(true).open(&file.0).unwrap();
+    f.set_len((HEADER_SIZE + 10 * 8) as u64).unwrap();
+    drop(f);
+
+    let err = FileVec::<u64>::open(&file.0).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
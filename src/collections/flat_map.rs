@@ -0,0 +1,812 @@
+//! A map kept as a vector of entries sorted by key, written `FlatMap<K, V>`.
+//!
+//! A [`FlatMap`] is to a tree map what a [`SortedVec`] is to a tree set: its
+//! `(key, value)` pairs live in one [`Vec`], ordered by key, and a lookup is
+//! a binary search over it. That makes small lookup tables that are built
+//! once and read often faster and more compact than a tree, while
+//! [`insert`] and [`remove`] cost *O*(*n*) because they shift the entries
+//! after the one they touch.
+//!
+//! Filling a map from many pairs at once is cheaper with [`extend`] or
+//! [`collect`], which sort once, or with [`merge`].
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_collections::flat_map::FlatMap;
+//!
+//! let mut ports: FlatMap<&str, u16> =
+//!     [("https", 443), ("ssh", 22), ("http", 80)].into_iter().collect();
+//!
+//! assert_eq!(ports.get("ssh"), Some(&22));
+//! assert_eq!(ports.insert("ssh", 2222), Some(22));
+//! *ports.entry("ftp").or_insert(20) += 1;
+//!
+//! let keys: Vec<_> = ports.keys().copied().collect();
+//! assert_eq!(keys, ["ftp", "http", "https", "ssh"]);
+//! assert_eq!(ports["ftp"], 21);
+//! ```
+//!
+//! [`SortedVec`]: crate::sorted_vec::SortedVec
+//! [`insert`]: FlatMap::insert
+//! [`remove`]: FlatMap::remove
+//! [`extend`]: Extend::extend
+//! [`collect`]: Iterator::collect
+//! [`merge`]: FlatMap::merge
+
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::mem;
+use core::ops::{Index, RangeBounds};
+use core::slice;
+
+use crate::algorithms::{partition_point, timsort_by};
+use crate::sorted_vec::{extend_sorted, merge_by, range_indices};
+use crate::vec::{self, Vec};
+
+#[cfg(test)]
+mod tests;
+
+/// A map from ordered keys to values, stored as a sorted vector of pairs.
+///
+/// See the [module documentation](self) for an overview.
+pub struct FlatMap<K, V> {
+    entries: Vec<(K, V)>,
+}
+
+impl<K, V> FlatMap<K, V> {
+    /// Constructs a new, empty `FlatMap<K, V>`.
+    pub fn new() -> Self {
+        FlatMap { entries: Vec::new() }
+    }
+
+    /// Constructs a new, empty `FlatMap<K, V>` with room for `capacity`
+    /// entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        FlatMap { entries: Vec::with_capacity(capacity) }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of entries the map can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    /// Extracts a slice of the entries, sorted by key.
+    ///
+    /// The indices returned by [`lower_bound`], [`upper_bound`] and
+    /// [`index_of`] are indices into this slice.
+    ///
+    /// [`lower_bound`]: FlatMap::lower_bound
+    /// [`upper_bound`]: FlatMap::upper_bound
+    /// [`index_of`]: FlatMap::index_of
+    pub fn as_slice(&self) -> &[(K, V)] {
+        &self.entries
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Removes and returns the entry at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_index(&mut self, index: usize) -> (K, V) {
+        self.entries.remove(index)
+    }
+
+    /// Retains only the entries for which `f` returns `true`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.entries.drain_filter(|(key, value)| !f(key, value)).for_each(drop);
+    }
+
+    /// Returns an iterator over the entries, sorted by key.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { inner: self.entries.iter() }
+    }
+
+    /// Returns an iterator over the entries, sorted by key, with mutable
+    /// references to the values.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut { inner: self.entries.iter_mut() }
+    }
+
+    /// Returns an iterator over the keys, in ascending order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Returns an iterator over the values, sorted by their keys.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    /// Returns an iterator over mutable references to the values, sorted by
+    /// their keys.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut { inner: self.iter_mut() }
+    }
+
+    /// Converts the map into a `Vec` of its entries sorted by key, without
+    /// copying them.
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.entries
+    }
+}
+
+impl<K: Ord, V> FlatMap<K, V> {
+    /// Inserts `value` under `key`.
+    ///
+    /// If the map already had an entry for `key`, its value is replaced and
+    /// the old value returned. The key itself is not updated.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Removes the entry for `key` and returns its value, if there was one.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Removes the entry for `key` and returns it, if there was one.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.index_of(key)?;
+        Some(self.entries.remove(index))
+    }
+
+    /// Returns a reference to the value for `key`, if any.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    /// Returns the entry for `key`, if any.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (key, value) = &self.entries[self.index_of(key)?];
+        Some((key, value))
+    }
+
+    /// Returns a mutable reference to the value for `key`, if any.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.index_of(key)?;
+        Some(&mut self.entries[index].1)
+    }
+
+    /// Returns `true` if the map has an entry for `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.index_of(key).is_some()
+    }
+
+    /// Returns the index of the entry for `key`, if any.
+    pub fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.lower_bound(key);
+        match self.entries.get(index) {
+            Some((k, _)) if k.borrow() == key => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the first entry whose key is not less than
+    /// `key`, or `len()` if there is none.
+    pub fn lower_bound<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        partition_point(&self.entries, |(k, _)| k.borrow() < key)
+    }
+
+    /// Returns the index of the first entry whose key is greater than
+    /// `key`, or `len()` if there is none.
+    pub fn upper_bound<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        partition_point(&self.entries, |(k, _)| k.borrow() <= key)
+    }
+
+    /// Returns an iterator over the entries whose keys lie in `range`,
+    /// sorted by key.
+    ///
+    /// A range whose start lies after its end is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::flat_map::FlatMap;
+    ///
+    /// let squares: FlatMap<u32, u32> = (0..10).map(|i| (i, i * i)).collect();
+    /// let v: Vec<_> = squares.range(3..6).map(|(_, &sq)| sq).collect();
+    /// assert_eq!(v, [9, 16, 25]);
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let indices = range_indices(&self.entries, &range, entry_key::<K, V, Q>);
+        Iter { inner: self.entries[indices].iter() }
+    }
+
+    /// Returns the entry for `key`, for in-place manipulation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::flat_map::FlatMap;
+    ///
+    /// let mut counts = FlatMap::new();
+    /// for word in "a b a c a b".split(' ') {
+    ///     *counts.entry(word).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(counts.as_slice(), [("a", 3), ("b", 2), ("c", 1)]);
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        let index = self.lower_bound(&key);
+        match self.entries.get(index) {
+            Some((k, _)) if *k == key => Entry::Occupied(OccupiedEntry { map: self, index }),
+            _ => Entry::Vacant(VacantEntry { map: self, key, index }),
+        }
+    }
+
+    /// Moves every entry of `other` into `self`, merging the two sorted
+    /// sequences in linear time.
+    ///
+    /// For keys present in both maps, the value from `other` replaces the
+    /// one in `self`, as if it had been inserted.
+    pub fn merge(&mut self, other: FlatMap<K, V>) {
+        let entries = mem::take(&mut self.entries);
+        self.entries = merge_by(entries, other.entries, cmp_keys, |kept, (_, value)| {
+            kept.1 = value;
+        });
+    }
+}
+
+fn entry_key<K: Borrow<Q>, V, Q: ?Sized>(entry: &(K, V)) -> &Q {
+    entry.0.borrow()
+}
+
+fn cmp_keys<K: Ord, V>(a: &(K, V), b: &(K, V)) -> core::cmp::Ordering {
+    a.0.cmp(&b.0)
+}
+
+// Of a run of entries with equal keys, keeps the key of the first and the
+// value of the last, which is what inserting them in order would do.
+fn same_key<K: Ord, V>(later: &mut (K, V), earlier: &mut (K, V)) -> bool {
+    if later.0 == earlier.0 {
+        mem::swap(&mut later.1, &mut earlier.1);
+        true
+    } else {
+        false
+    }
+}
+
+impl<K: Clone, V: Clone> Clone for FlatMap<K, V> {
+    fn clone(&self) -> Self {
+        FlatMap { entries: self.entries.clone() }
+    }
+
+    fn clone_from(&mut self, other: &Self) {
+        self.entries.clone_from(&other.entries);
+    }
+}
+
+impl<K, V> Default for FlatMap<K, V> {
+    fn default() -> Self {
+        FlatMap::new()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for FlatMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Hash, V: Hash> Hash for FlatMap<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&self.entries[..], state)
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for FlatMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries[..] == other.entries[..]
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for FlatMap<K, V> {}
+
+impl<K, Q, V> Index<&Q> for FlatMap<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    /// Returns a reference to the value for `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map has no entry for `key`.
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K: Ord, V> From<Vec<(K, V)>> for FlatMap<K, V> {
+    /// Sorts the entries by key in place. Of several entries with equal
+    /// keys, the value of the last one is kept.
+    fn from(mut entries: Vec<(K, V)>) -> Self {
+        timsort_by(&mut entries, cmp_keys);
+        entries.dedup_by(same_key);
+        FlatMap { entries }
+    }
+}
+
+impl<K, V> From<FlatMap<K, V>> for Vec<(K, V)> {
+    fn from(map: FlatMap<K, V>) -> Self {
+        map.entries
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for FlatMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        FlatMap::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for FlatMap<K, V> {
+    /// Inserts every pair of `iter` into the map.
+    ///
+    /// The pairs are appended and the vector is sorted once, instead of
+    /// inserting them one by one. As with [`insert`](FlatMap::insert), the
+    /// last value given for a key wins.
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        extend_sorted(&mut self.entries, iter, cmp_keys, same_key);
+    }
+}
+
+impl<'a, K: Ord + Copy + 'a, V: Copy + 'a> Extend<(&'a K, &'a V)> for FlatMap<K, V> {
+    fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)))
+    }
+}
+
+impl<K, V> IntoIterator for FlatMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = vec::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> vec::IntoIter<(K, V)> {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a FlatMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut FlatMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Entries
+////////////////////////////////////////////////////////////////////////////////
+
+/// The entry for a key in a `FlatMap`, which may be vacant or occupied.
+///
+/// This `enum` is created by [`FlatMap::entry`].
+pub enum Entry<'a, K, V> {
+    /// The map has no entry for the key.
+    Vacant(VacantEntry<'a, K, V>),
+    /// The map has an entry for the key.
+    Occupied(OccupiedEntry<'a, K, V>),
+}
+
+impl<'a, K, V> Entry<'a, K, V> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Vacant(entry) => entry.key(),
+            Entry::Occupied(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns a mutable
+    /// reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, and returns
+    /// a mutable reference to the value.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default()),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// Inserts the result of calling `default` with the key if the entry is
+    /// vacant, and returns a mutable reference to the value.
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// Calls `f` on the value if the entry is occupied.
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+        }
+    }
+}
+
+impl<'a, K, V: Default> Entry<'a, K, V> {
+    /// Inserts `V::default()` if the entry is vacant, and returns a mutable
+    /// reference to the value.
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+/// A vacant entry in a `FlatMap`, part of the [`Entry`] enum.
+pub struct VacantEntry<'a, K, V> {
+    map: &'a mut FlatMap<K, V>,
+    key: K,
+    // Where the entry goes to keep the map sorted.
+    index: usize,
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> {
+    /// Returns the key that would be inserted.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the key without inserting anything.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts `value` under the entry's key, and returns a mutable
+    /// reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let entries = &mut self.map.entries;
+        entries.insert(self.index, (self.key, value));
+        &mut entries[self.index].1
+    }
+}
+
+/// An occupied entry in a `FlatMap`, part of the [`Entry`] enum.
+pub struct OccupiedEntry<'a, K, V> {
+    map: &'a mut FlatMap<K, V>,
+    index: usize,
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.map.entries[self.index].0
+    }
+
+    /// Returns the index of the entry in [`FlatMap::as_slice`].
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns a reference to the value.
+    pub fn get(&self) -> &V {
+        &self.map.entries[self.index].1
+    }
+
+    /// Returns a mutable reference to the value.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.entries[self.index].1
+    }
+
+    /// Converts the entry into a mutable reference to the value that lives
+    /// as long as the map borrow.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.entries[self.index].1
+    }
+
+    /// Replaces the value and returns the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the entry and returns its value.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Removes the entry and returns it.
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.entries.remove(self.index)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator over the entries of a `FlatMap`.
+///
+/// This `struct` is created by [`FlatMap::iter`] and [`FlatMap::range`].
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    /// Returns the remaining entries as a slice.
+    pub fn as_slice(&self) -> &'a [(K, V)] {
+        self.inner.as_slice()
+    }
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Iter { inner: self.inner.clone() }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Iter<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        self.inner.next().map(|(key, value)| (key, value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+        self.inner.next_back().map(|(key, value)| (key, value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+/// An iterator over the entries of a `FlatMap`, with mutable references to
+/// the values.
+///
+/// This `struct` is created by [`FlatMap::iter_mut`].
+pub struct IterMut<'a, K, V> {
+    inner: slice::IterMut<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        self.inner.next().map(|(key, value)| (&*key, value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for IterMut<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(&'a K, &'a mut V)> {
+        self.inner.next_back().map(|(key, value)| (&*key, value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+/// An iterator over the keys of a `FlatMap`.
+///
+/// This `struct` is created by [`FlatMap::keys`].
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<K, V> Clone for Keys<'_, K, V> {
+    fn clone(&self) -> Self {
+        Keys { inner: self.inner.clone() }
+    }
+}
+
+impl<K: fmt::Debug, V> fmt::Debug for Keys<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a K> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Keys<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a K> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+/// An iterator over the values of a `FlatMap`.
+///
+/// This `struct` is created by [`FlatMap::values`].
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<K, V> Clone for Values<'_, K, V> {
+    fn clone(&self) -> Self {
+        Values { inner: self.inner.clone() }
+    }
+}
+
+impl<K, V: fmt::Debug> fmt::Debug for Values<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a V> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a V> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+/// An iterator over mutable references to the values of a `FlatMap`.
+///
+/// This `struct` is created by [`FlatMap::values_mut`].
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut V> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for ValuesMut<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut V> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K, V> ExactSizeIterator for ValuesMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for ValuesMut<'_, K, V> {}
@@ -0,0 +1,79 @@
+use super::*;
+
+use std::collections::BTreeMap;
+use std::vec::Vec as StdVec;
+
+use crate::test_util::Rng;
+
+// A key from a small range, so that keys collide often.
+fn random_key(rng: &mut Rng) -> u32 {
+    (rng.next() % 32) as u32
+}
+
+fn random_pairs(rng: &mut Rng) -> StdVec<(u32, u64)> {
+    let len = rng.next() % 12;
+    (0..len).map(|_| (random_key(rng), rng.next())).collect()
+}
+
+// Applies one random operation to both maps and checks that they agree on
+// what it returned.
+fn step(rng: &mut Rng, map: &mut FlatMap<u32, u64>, model: &mut BTreeMap<u32, u64>) {
+    let key = random_key(rng);
+    match rng.next() % 9 {
+        0 | 1 => {
+            let value = rng.next();
+            assert_eq!(map.insert(key, value), model.insert(key, value));
+        }
+        2 => assert_eq!(map.remove(&key), model.remove(&key)),
+        3 => {
+            let value = rng.next();
+            *map.entry(key).and_modify(|v| *v += 1).or_insert(value) += 2;
+            *model.entry(key).and_modify(|v| *v += 1).or_insert(value) += 2;
+        }
+        4 => {
+            let pairs = random_pairs(rng);
+            map.extend(pairs.iter().copied());
+            model.extend(pairs);
+        }
+        5 => {
+            let pairs = random_pairs(rng);
+            map.merge(pairs.iter().copied().collect());
+            model.extend(pairs);
+        }
+        6 => {
+            let end = random_key(rng);
+            let got: StdVec<_> = map.range(key..=end).collect();
+            let expected: StdVec<_> =
+                if key <= end { model.range(key..=end).collect() } else { StdVec::new() };
+            assert_eq!(got, expected);
+        }
+        7 => {
+            let lower = map.lower_bound(&key);
+            let upper = map.upper_bound(&key);
+            assert_eq!(lower, model.range(..key).count());
+            assert_eq!(upper, model.range(..=key).count());
+        }
+        _ => {
+            let keep = |k: &u32, v: &mut u64| {
+                *v = v.wrapping_add(*k as u64);
+                k % 5 != 0
+            };
+            map.retain(keep);
+            model.retain(keep);
+        }
+    }
+}
+
+#[test]
+fn random_operations_match_btree_map() {
+    for seed in 0..200 {
+        let mut rng = Rng::new(seed);
+        let mut map = FlatMap::new();
+        let mut model = BTreeMap::new();
+        for _ in 0..200 {
+            step(&mut rng, &mut map, &mut model);
+            let expected: StdVec<_> = model.iter().map(|(&k, &v)| (k, v)).collect();
+            assert_eq!(map.as_slice(), expected, "seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,463 @@
+//! An arena whose handles detect when their value is gone, written
+//! `GenArena<T>`.
+//!
+//! A [`GenArena`] keeps its values in a [`Vec`] of slots and reuses the slots
+//! of removed values through a free list, so inserting and removing are
+//! *O*(1) and never move the other values. Its [`Handle`]s pair the slot
+//! index with the slot's *generation*, which goes up every time the slot's
+//! value is removed. A handle to a removed value therefore never reaches the
+//! value that later takes over its slot: looking it up just returns `None`.
+//! A [`Slab`] is the same storage behind a smaller interface.
+//!
+//! This makes handles a safe replacement for pointers between nodes of a
+//! tree or graph: a dangling handle is caught instead of being undefined
+//! behavior, and there is nothing to free by hand.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_collections::gen_arena::{GenArena, Handle};
+//!
+//! struct Node {
+//!     value: i32,
+//!     children: Vec<Handle>,
+//! }
+//!
+//! let mut tree = GenArena::new();
+//! let leaf = tree.insert(Node { value: 2, children: Vec::new() });
+//! let root = tree.insert(Node { value: 1, children: vec![leaf] });
+//!
+//! let sum: i32 = tree[root].children.iter().map(|&h| tree[h].value).sum();
+//! assert_eq!(sum, 2);
+//!
+//! // The root still holds a handle to the removed leaf, but it no longer
+//! // resolves, even once the slot is reused.
+//! tree.remove(leaf);
+//! let other = tree.insert(Node { value: 3, children: Vec::new() });
+//! assert_eq!(other.index(), leaf.index());
+//! assert!(tree.get(tree[root].children[0]).is_none());
+//! ```
+//!
+//! [`Slab`]: crate::slab::Slab
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::mem;
+use core::ops::{Index, IndexMut};
+use core::slice;
+
+use crate::vec::Vec;
+
+#[cfg(test)]
+mod tests;
+
+/// A handle to a value in a [`GenArena`]: a slot index plus the generation
+/// the slot had when the value was inserted.
+///
+/// Handles are only meaningful for the arena that returned them.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Handle {
+    index: usize,
+    generation: u64,
+}
+
+impl Handle {
+    /// Returns the index of the slot the handle points at.
+    pub fn index(self) -> usize {
+        self.index
+    }
+
+    /// Returns the generation of the slot the handle was issued for.
+    pub fn generation(self) -> u64 {
+        self.generation
+    }
+}
+
+#[derive(Clone)]
+enum Entry<T> {
+    /// Holds the next free slot, or `slots.len()` if this is the last one.
+    Vacant(usize),
+    Occupied(T),
+}
+
+#[derive(Clone)]
+struct Slot<T> {
+    /// Goes up whenever the value in the slot is removed.
+    generation: u64,
+    entry: Entry<T>,
+}
+
+/// An arena of values addressed by generational [`Handle`]s.
+///
+/// See the [module documentation](self) for an overview.
+pub struct GenArena<T> {
+    slots: Vec<Slot<T>>,
+    /// The number of occupied slots.
+    len: usize,
+    /// The head of the free list, or `slots.len()` if it is empty.
+    next: usize,
+}
+
+impl<T> GenArena<T> {
+    /// Constructs a new, empty `GenArena<T>`.
+    pub fn new() -> Self {
+        GenArena { slots: Vec::new(), len: 0, next: 0 }
+    }
+
+    /// Constructs a new, empty `GenArena<T>` with room for `capacity`
+    /// values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        GenArena { slots: Vec::with_capacity(capacity), len: 0, next: 0 }
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of values the arena can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more values.
+    ///
+    /// Free slots count towards the values the arena can hold.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        let free = self.slots.len() - self.len;
+        if additional > free {
+            self.slots.reserve(additional - free);
+        }
+    }
+
+    /// Removes all values.
+    ///
+    /// The slots are kept, so that the handles issued so far stay stale
+    /// rather than pointing at the values inserted next.
+    pub fn clear(&mut self) {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if let Entry::Occupied(_) = slot.entry {
+                slot.generation += 1;
+            }
+            slot.entry = Entry::Vacant(index + 1);
+        }
+        self.len = 0;
+        self.next = 0;
+    }
+
+    /// Returns the handle the next insertion will return.
+    pub(crate) fn vacant_handle(&self) -> Handle {
+        let generation = self.slots.get(self.next).map_or(0, |slot| slot.generation);
+        Handle { index: self.next, generation }
+    }
+
+    /// Inserts `value` and returns its handle.
+    pub fn insert(&mut self, value: T) -> Handle {
+        self.insert_with(|_| value)
+    }
+
+    /// Inserts the value `f` makes from the handle it will have, and
+    /// returns the handle.
+    ///
+    /// This lets a value store its own handle.
+    pub fn insert_with<F>(&mut self, f: F) -> Handle
+    where
+        F: FnOnce(Handle) -> T,
+    {
+        let index = self.next;
+        let handle = if index == self.slots.len() {
+            let handle = Handle { index, generation: 0 };
+            self.slots.push(Slot { generation: 0, entry: Entry::Occupied(f(handle)) });
+            self.next = index + 1;
+            handle
+        } else {
+            let slot = &mut self.slots[index];
+            let handle = Handle { index, generation: slot.generation };
+            let next = match slot.entry {
+                Entry::Vacant(next) => next,
+                Entry::Occupied(_) => unreachable!("free list points at an occupied slot"),
+            };
+            // Only unlink the slot once `f` has returned, in case it panics.
+            slot.entry = Entry::Occupied(f(handle));
+            self.next = next;
+            handle
+        };
+        self.len += 1;
+        handle
+    }
+
+    /// Removes and returns the value for `handle`, or returns `None` if it
+    /// is stale.
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let slot = self.slots.get_mut(handle.index)?;
+        if slot.generation != handle.generation || matches!(slot.entry, Entry::Vacant(_)) {
+            return None;
+        }
+        slot.generation += 1;
+        match mem::replace(&mut slot.entry, Entry::Vacant(self.next)) {
+            Entry::Occupied(value) => {
+                self.next = handle.index;
+                self.len -= 1;
+                Some(value)
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+    }
+
+    /// Returns `true` if `handle` points at a value.
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// Returns a reference to the value for `handle`, or `None` if it is
+    /// stale.
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        match self.slots.get(handle.index) {
+            Some(Slot { generation, entry: Entry::Occupied(value) })
+                if *generation == handle.generation =>
+            {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value for `handle`, or `None` if
+    /// it is stale.
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        resolve(self.slots.get_mut(handle.index), handle)
+    }
+
+    /// Returns mutable references to the values for two handles, for
+    /// linking nodes to each other.
+    ///
+    /// # Panics
+    ///
+    /// Panics if both handles point at the same slot.
+    pub fn get2_mut(&mut self, a: Handle, b: Handle) -> (Option<&mut T>, Option<&mut T>) {
+        assert!(a.index != b.index, "both handles point at slot {}", a.index);
+        if a.index > b.index {
+            let (b, a) = self.get2_mut(b, a);
+            return (a, b);
+        }
+        if b.index >= self.slots.len() {
+            return (self.get_mut(a), None);
+        }
+        let (head, tail) = self.slots.split_at_mut(b.index);
+        (resolve(head.get_mut(a.index), a), resolve(tail.first_mut(), b))
+    }
+
+    /// Retains only the values for which `f` returns `true`, passing it
+    /// each handle and value in slot order.
+    ///
+    /// The handles of the retained values stay valid.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Handle, &mut T) -> bool,
+    {
+        for index in 0..self.slots.len() {
+            let slot = &mut self.slots[index];
+            let handle = Handle { index, generation: slot.generation };
+            let keep = match &mut slot.entry {
+                Entry::Occupied(value) => f(handle, value),
+                Entry::Vacant(_) => true,
+            };
+            if !keep {
+                self.remove(handle);
+            }
+        }
+    }
+
+    /// Returns an iterator over the handles and values, in slot order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { slots: self.slots.iter().enumerate(), len: self.len }
+    }
+
+    /// Returns an iterator over the handles and mutable references to the
+    /// values, in slot order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { slots: self.slots.iter_mut().enumerate(), len: self.len }
+    }
+}
+
+fn resolve<T>(slot: Option<&mut Slot<T>>, handle: Handle) -> Option<&mut T> {
+    match slot {
+        Some(Slot { generation, entry: Entry::Occupied(value) })
+            if *generation == handle.generation =>
+        {
+            Some(value)
+        }
+        _ => None,
+    }
+}
+
+impl<T: Clone> Clone for GenArena<T> {
+    /// Clones the arena along with its generations, so that its handles
+    /// work on the clone too.
+    fn clone(&self) -> Self {
+        GenArena { slots: self.slots.clone(), len: self.len, next: self.next }
+    }
+}
+
+impl<T> Default for GenArena<T> {
+    fn default() -> Self {
+        GenArena::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for GenArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Index<Handle> for GenArena<T> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// Panics if `handle` is stale.
+    fn index(&self, handle: Handle) -> &T {
+        self.get(handle).expect("stale arena handle")
+    }
+}
+
+impl<T> IndexMut<Handle> for GenArena<T> {
+    /// # Panics
+    ///
+    /// Panics if `handle` is stale.
+    fn index_mut(&mut self, handle: Handle) -> &mut T {
+        self.get_mut(handle).expect("stale arena handle")
+    }
+}
+
+impl<'a, T> IntoIterator for &'a GenArena<T> {
+    type Item = (Handle, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut GenArena<T> {
+    type Item = (Handle, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator over the handles and values of a `GenArena`.
+///
+/// This `struct` is created by [`GenArena::iter`].
+pub struct Iter<'a, T> {
+    slots: core::iter::Enumerate<slice::Iter<'a, Slot<T>>>,
+    /// The number of values not yet yielded.
+    len: usize,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter { slots: self.slots.clone(), len: self.len }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Handle, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<(Handle, &'a T)> {
+        for (index, slot) in &mut self.slots {
+            if let Entry::Occupied(value) = &slot.entry {
+                self.len -= 1;
+                return Some((Handle { index, generation: slot.generation }, value));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(Handle, &'a T)> {
+        while let Some((index, slot)) = self.slots.next_back() {
+            if let Entry::Occupied(value) = &slot.entry {
+                self.len -= 1;
+                return Some((Handle { index, generation: slot.generation }, value));
+            }
+        }
+        None
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An iterator over the handles and mutable references to the values of a
+/// `GenArena`.
+///
+/// This `struct` is created by [`GenArena::iter_mut`].
+pub struct IterMut<'a, T> {
+    slots: core::iter::Enumerate<slice::IterMut<'a, Slot<T>>>,
+    /// The number of values not yet yielded.
+    len: usize,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (Handle, &'a mut T);
+
+    #[inline]
+    fn next(&mut self) -> Option<(Handle, &'a mut T)> {
+        for (index, slot) in &mut self.slots {
+            if let Entry::Occupied(value) = &mut slot.entry {
+                self.len -= 1;
+                return Some((Handle { index, generation: slot.generation }, value));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(Handle, &'a mut T)> {
+        while let Some((index, slot)) = self.slots.next_back() {
+            if let Entry::Occupied(value) = &mut slot.entry {
+                self.len -= 1;
+                return Some((Handle { index, generation: slot.generation }, value));
+            }
+        }
+        None
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
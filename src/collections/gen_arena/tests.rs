@@ -0,0 +1,80 @@
+use super::*;
+
+use std::collections::HashMap;
+use std::vec::Vec as StdVec;
+
+use crate::test_util::Rng;
+
+#[test]
+fn random_operations_match_a_hash_map() {
+    for seed in 0..100 {
+        let mut rng = Rng::new(seed);
+        let mut arena = GenArena::new();
+        let mut live: HashMap<Handle, u64> = HashMap::new();
+        // Every handle ever issued, live or not.
+        let mut issued: StdVec<Handle> = StdVec::new();
+
+        for _ in 0..300 {
+            match rng.below(10) {
+                0..=3 => {
+                    let value = rng.next();
+                    let handle = arena.insert(value);
+                    assert!(!issued.contains(&handle), "seed {}", seed);
+                    issued.push(handle);
+                    live.insert(handle, value);
+                }
+                4..=6 if !issued.is_empty() => {
+                    // Often a stale handle, which must not remove anything.
+                    let handle = issued[rng.below(issued.len())];
+                    assert_eq!(arena.remove(handle), live.remove(&handle), "seed {}", seed);
+                }
+                7 => {
+                    let odd = rng.next() % 2;
+                    arena.retain(|_, value| *value % 2 != odd);
+                    live.retain(|_, value| *value % 2 != odd);
+                }
+                8 if !issued.is_empty() => {
+                    let handle = issued[rng.below(issued.len())];
+                    if let Some(value) = arena.get_mut(handle) {
+                        *value += 1;
+                    }
+                    if let Some(value) = live.get_mut(&handle) {
+                        *value += 1;
+                    }
+                }
+                9 if rng.below(10) == 0 => {
+                    arena.clear();
+                    live.clear();
+                }
+                _ => {}
+            }
+
+            assert_eq!(arena.len(), live.len());
+            for handle in &issued {
+                assert_eq!(arena.get(*handle), live.get(handle), "seed {}", seed);
+            }
+            let mut got: StdVec<(Handle, u64)> = arena.iter().map(|(h, &v)| (h, v)).collect();
+            let mut expected: StdVec<(Handle, u64)> = live.iter().map(|(&h, &v)| (h, v)).collect();
+            got.sort();
+            expected.sort();
+            assert_eq!(got, expected, "seed {}", seed);
+        }
+    }
+}
+
+#[test]
+fn get2_mut_links_two_nodes() {
+    let mut arena = GenArena::new();
+    let a = arena.insert(StdVec::new());
+    let b = arena.insert(StdVec::new());
+    let (x, y) = arena.get2_mut(b, a);
+    x.unwrap().push(a);
+    y.unwrap().push(b);
+    assert_eq!(arena[a], [b]);
+    assert_eq!(arena[b], [a]);
+
+    arena.remove(a);
+    let (x, y) = arena.get2_mut(a, b);
+    assert!(x.is_none());
+    assert!(y.is_some());
+}
@@ -0,0 +1,51 @@
+//! `Vec`, `RawVec` and the containers built on them.
+//!
+//! [`vec`](mod@vec) and [`raw_vec`] started out as copies of the ones in liballoc.
+//! This crate builds them, and everything built on them, on stable Rust:
+//!
+//! * [`alloc::Allocator`] replaces the unstable allocator API, with
+//!   [`alloc::Global`] forwarding to the global allocator.
+//! * `extend` and `collect` take the old `TrustedLen` fast path for every
+//!   iterator with an exact size hint, without trusting the hint.
+//! * The zeroed fast path of [`vec!`](macro@vec) needs the element type to
+//!   be known to be [`pod::Zeroable`] where the macro is used. Generic code
+//!   calls [`vec::from_zeroable_elem`] to get it.
+//! * `collect` recognizes a `vec::IntoIter`, so that it can reuse its
+//!   buffer, by comparing type ids rather than through specialization.
+//! * Conversions to and from `Box<[T]>` are only available for vectors using
+//!   [`alloc::Global`], since std's `Box` has no allocator parameter on
+//!   stable.
+//!
+//! # Features
+//!
+//! * `debug-memory`: canaries after every buffer and poisoned spare
+//!   capacity, see [`raw_vec::debug_memory`].
+
+pub mod alloc;
+mod unique;
+
+#[cfg(test)]
+pub(crate) mod test_util;
+
+#[macro_use]
+mod macros;
+
+pub mod algorithms;
+pub mod aligned_vec;
+pub mod append_vec;
+pub mod bit_vec;
+pub mod codec;
+pub mod cow_vec;
+pub mod diff;
+pub mod file_vec;
+pub mod flat_map;
+pub mod gen_arena;
+pub mod par;
+pub mod pod;
+pub mod raw_vec;
+pub mod seg_vec;
+pub mod slab;
+pub mod small_vec;
+pub mod sorted_vec;
+pub mod vec;
+pub mod vec_deque;
@@ -0,0 +1,63 @@
+/// Creates a [`Vec`] containing the arguments.
+///
+/// `vec!` allows `Vec`s to be defined with the same syntax as array expressions.
+/// There are two forms of this macro:
+///
+/// - Create a [`Vec`] containing a given list of elements:
+///
+/// ```
+/// use rusty_collections::vec;
+///
+/// let v = vec![1, 2, 3];
+/// assert_eq!(v[0], 1);
+/// assert_eq!(v[1], 2);
+/// assert_eq!(v[2], 3);
+/// ```
+///
+/// - Create a [`Vec`] from a given element and size:
+///
+/// ```
+/// use rusty_collections::vec;
+///
+/// let v = vec![1; 3];
+/// assert_eq!(v, [1, 1, 1]);
+/// ```
+///
+/// Note that unlike array expressions this syntax supports all elements
+/// which implement [`Clone`] and the number of elements doesn't have to be
+/// a constant.
+///
+/// This will use `clone` to duplicate an expression, so one should be careful
+/// using this with types having a nonstandard `Clone` implementation. For
+/// example, `vec![Rc::new(1); 5]` will create a vector of five references
+/// to the same boxed integer value, not five references pointing to independently
+/// boxed integers.
+///
+/// Also, note that `vec![expr; 0]` is allowed, and produces an empty vector.
+/// This will still evaluate `expr`, however, and immediately drop the resulting value, so
+/// be mindful of side effects.
+///
+/// If the element type implements [`Zeroable`] and `elem` is zero, the memory
+/// is zeroed by the allocator instead, as with [`from_zeroable_elem`]. That
+/// needs the element type to be known where the macro is used: in code that
+/// is generic over `T: Clone`, `vec![elem; n]` always clones.
+///
+/// [`Vec`]: crate::vec::Vec
+/// [`Zeroable`]: crate::pod::Zeroable
+/// [`from_zeroable_elem`]: crate::vec::from_zeroable_elem
+#[macro_export]
+macro_rules! vec {
+    () => (
+        $crate::vec::Vec::new()
+    );
+    ($elem:expr; $n:expr) => ({
+        #[allow(unused_imports)]
+        use $crate::vec::{CloneKind as _, ZeroableKind as _};
+        match $elem {
+            elem => (&$crate::vec::__elem_probe(&elem)).__elem_kind().from_elem(elem, $n),
+        }
+    });
+    ($($x:expr),+ $(,)?) => (
+        $crate::vec::Vec::from([$($x),+])
+    );
+}
@@ -0,0 +1,625 @@
+//! Parallel iteration and bulk operations for [`Vec`].
+//!
+//! The methods in this module split a vector into contiguous pieces and
+//! process each piece on its own thread, using [`std::thread::scope`] so the
+//! threads can borrow the vector. No thread pool is kept around: every call
+//! spawns its threads and joins them before it returns.
+//!
+//! * [`Vec::par_iter`], [`Vec::par_iter_mut`] and [`Vec::par_chunks`] return
+//!   a [`ParIter`], which supports `map`, `filter`, `for_each`, `collect`,
+//!   `reduce`, `sum` and `count`. Results come back in the vector's order.
+//! * [`Vec::par_sort`] is a stable sort: the pieces are sorted in parallel,
+//!   then merged pairwise, each round of merges in parallel too.
+//! * [`Vec::par_extend`] appends the output of a `ParIter`.
+//! * [`Vec::par_retain`] evaluates its predicate in parallel.
+//!
+//! The number of threads defaults to [`std::thread::available_parallelism`]
+//! and can be changed for the whole process with [`set_threads`], or for one
+//! `ParIter` with [`ParIter::threads`]. Pieces are never shorter than
+//! [`ParIter::min_len`] elements, so small vectors are processed on the
+//! calling thread without spawning anything.
+//!
+//! If a closure panics on any thread, the panic is resumed on the calling
+//! thread once every piece has finished.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_collections::vec::Vec;
+//!
+//! let mut v: Vec<u64> = (0..1_000_000).collect();
+//!
+//! v.par_iter_mut().for_each(|x| *x *= 3);
+//! let evens: Vec<u64> = v.par_iter().filter(|&&x| x % 2 == 0).map(|&x| x / 2).collect();
+//! assert_eq!(evens.len(), 500_000);
+//!
+//! let total: u64 = v.par_iter().threads(4).map(|&x| x).sum();
+//! assert_eq!(total, 3 * (999_999 * 1_000_000 / 2));
+//!
+//! v.par_sort_by(|a, b| b.cmp(a));
+//! assert_eq!(v[0], 2_999_997);
+//! ```
+
+use core::cmp::{self, Ordering};
+use core::iter::Sum;
+use core::mem;
+use core::slice;
+use core::sync::atomic::{self, AtomicUsize};
+
+use std::panic;
+use std::thread;
+
+use crate::algorithms;
+use crate::alloc::Allocator;
+use crate::raw_vec::GrowthPolicy;
+use crate::vec::Vec;
+
+#[cfg(test)]
+mod tests;
+
+/// Pieces are at least this long unless [`ParIter::min_len`] says otherwise.
+const DEFAULT_MIN_LEN: usize = 1024;
+
+/// `sum` adds up this many items at a time, so it needs no more memory than
+/// this per thread.
+const SUM_BATCH: usize = 256;
+
+// `0` means "ask the OS".
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets the number of threads parallel operations use by default.
+///
+/// Passing `0` goes back to [`std::thread::available_parallelism`].
+pub fn set_threads(threads: usize) {
+    THREADS.store(threads, atomic::Ordering::Relaxed);
+}
+
+/// Returns the number of threads parallel operations use by default.
+pub fn threads() -> usize {
+    match THREADS.load(atomic::Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
+/// Something that can be split into contiguous pieces, each of which is then
+/// iterated over sequentially on its own thread.
+///
+/// This is implemented for `&[T]`, `&mut [T]` and [`Chunks`].
+pub trait Producer: Send + Sized {
+    /// The type of the items produced.
+    type Item;
+    /// The sequential iterator over one piece.
+    type IntoIter: Iterator<Item = Self::Item>;
+
+    /// Returns the number of items.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no items.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Splits into the first `index` items and the rest.
+    fn split_at(self, index: usize) -> (Self, Self);
+
+    /// Returns a sequential iterator over the items.
+    fn into_iter(self) -> Self::IntoIter;
+}
+
+impl<'a, T: Sync> Producer for &'a [T] {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        <[T]>::split_at(self, index)
+    }
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T: Send> Producer for &'a mut [T] {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        self.split_at_mut(index)
+    }
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// A [`Producer`] of non-overlapping chunks of a slice.
+///
+/// This `struct` is created by [`Vec::par_chunks`].
+#[derive(Debug)]
+pub struct Chunks<'a, T> {
+    slice: &'a [T],
+    size: usize,
+}
+
+impl<'a, T: Sync> Producer for Chunks<'a, T> {
+    type Item = &'a [T];
+    type IntoIter = slice::Chunks<'a, T>;
+
+    fn len(&self) -> usize {
+        self.slice.len().div_ceil(self.size)
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let mid = cmp::min(index * self.size, self.slice.len());
+        let (head, tail) = self.slice.split_at(mid);
+        (Chunks { slice: head, size: self.size }, Chunks { slice: tail, size: self.size })
+    }
+
+    fn into_iter(self) -> slice::Chunks<'a, T> {
+        self.slice.chunks(self.size)
+    }
+}
+
+/// A step applied to every item of a [`ParIter`], on the thread that
+/// produced the item.
+///
+/// Stages are built by [`ParIter::map`] and [`ParIter::filter`].
+pub trait Stage<In>: Sync {
+    /// The type of the items passed on.
+    type Out;
+
+    /// Processes `item`, passing the result on to `sink`, if there is one.
+    fn feed<K: FnMut(Self::Out)>(&self, item: In, sink: &mut K);
+}
+
+/// The [`Stage`] that passes every item on unchanged.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Identity;
+
+impl<In> Stage<In> for Identity {
+    type Out = In;
+
+    #[inline]
+    fn feed<K: FnMut(In)>(&self, item: In, sink: &mut K) {
+        sink(item)
+    }
+}
+
+/// The [`Stage`] created by [`ParIter::map`].
+#[derive(Clone, Debug)]
+pub struct Map<S, F> {
+    stage: S,
+    f: F,
+}
+
+impl<In, R, S, F> Stage<In> for Map<S, F>
+where
+    S: Stage<In>,
+    F: Fn(S::Out) -> R + Sync,
+{
+    type Out = R;
+
+    #[inline]
+    fn feed<K: FnMut(R)>(&self, item: In, sink: &mut K) {
+        let f = &self.f;
+        self.stage.feed(item, &mut |x| sink(f(x)))
+    }
+}
+
+/// The [`Stage`] created by [`ParIter::filter`].
+#[derive(Clone, Debug)]
+pub struct Filter<S, F> {
+    stage: S,
+    pred: F,
+}
+
+impl<In, S, F> Stage<In> for Filter<S, F>
+where
+    S: Stage<In>,
+    F: Fn(&S::Out) -> bool + Sync,
+{
+    type Out = S::Out;
+
+    #[inline]
+    fn feed<K: FnMut(S::Out)>(&self, item: In, sink: &mut K) {
+        let pred = &self.pred;
+        self.stage.feed(item, &mut |x| {
+            if pred(&x) {
+                sink(x)
+            }
+        })
+    }
+}
+
+/// A parallel iterator over the items of a [`Producer`], passed through a
+/// [`Stage`].
+///
+/// Unlike an [`Iterator`], a `ParIter` is consumed all at once by one of its
+/// consuming methods, such as [`for_each`] or [`collect`].
+///
+/// [`for_each`]: ParIter::for_each
+/// [`collect`]: ParIter::collect
+#[derive(Debug)]
+#[must_use = "parallel iterators do nothing unless consumed"]
+pub struct ParIter<P, S = Identity> {
+    producer: P,
+    stage: S,
+    threads: usize,
+    min_len: usize,
+}
+
+impl<P: Producer> ParIter<P> {
+    fn new(producer: P) -> Self {
+        ParIter { producer, stage: Identity, threads: threads(), min_len: DEFAULT_MIN_LEN }
+    }
+}
+
+impl<P: Producer, S: Stage<P::Item>> ParIter<P, S> {
+    /// Uses at most `threads` threads, instead of the default from
+    /// [`threads()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is zero.
+    pub fn threads(mut self, threads: usize) -> Self {
+        assert!(threads != 0, "a parallel iterator needs at least one thread");
+        self.threads = threads;
+        self
+    }
+
+    /// Never gives a thread fewer than `min_len` items of the producer.
+    ///
+    /// Raise this for cheap per-item work, where spawning a thread costs more
+    /// than it saves; lower it for expensive work on few items. The default
+    /// is 1024.
+    pub fn min_len(mut self, min_len: usize) -> Self {
+        self.min_len = cmp::max(min_len, 1);
+        self
+    }
+
+    /// Applies `f` to every item.
+    pub fn map<R, F>(self, f: F) -> ParIter<P, Map<S, F>>
+    where
+        F: Fn(S::Out) -> R + Sync,
+    {
+        let ParIter { producer, stage, threads, min_len } = self;
+        ParIter { producer, stage: Map { stage, f }, threads, min_len }
+    }
+
+    /// Keeps only the items for which `pred` returns `true`.
+    pub fn filter<F>(self, pred: F) -> ParIter<P, Filter<S, F>>
+    where
+        F: Fn(&S::Out) -> bool + Sync,
+    {
+        let ParIter { producer, stage, threads, min_len } = self;
+        ParIter { producer, stage: Filter { stage, pred }, threads, min_len }
+    }
+
+    /// Calls `f` on every item.
+    ///
+    /// The order in which items are visited is unspecified.
+    pub fn for_each<F>(self, f: F)
+    where
+        F: Fn(S::Out) + Sync,
+    {
+        self.drive(|stage, piece| {
+            for item in piece {
+                stage.feed(item, &mut |x| f(x));
+            }
+        });
+    }
+
+    /// Collects the items into a [`Vec`], in order.
+    pub fn collect(self) -> Vec<S::Out>
+    where
+        S::Out: Send,
+    {
+        let pieces = self.collect_pieces();
+        let mut out = Vec::with_capacity(pieces.iter().map(Vec::len).sum());
+        for mut piece in pieces {
+            out.append(&mut piece);
+        }
+        out
+    }
+
+    /// Combines the items with `op`, starting every piece from `identity()`.
+    ///
+    /// `op` must be associative, and `identity()` must be its neutral
+    /// element, or the result depends on how the items were split.
+    pub fn reduce<ID, OP>(self, identity: ID, op: OP) -> S::Out
+    where
+        S::Out: Send,
+        ID: Fn() -> S::Out + Sync,
+        OP: Fn(S::Out, S::Out) -> S::Out + Sync,
+    {
+        let partials = self.drive(|stage, piece| {
+            let mut acc = Some(identity());
+            for item in piece {
+                stage.feed(item, &mut |x| acc = Some(op(acc.take().unwrap(), x)));
+            }
+            acc.unwrap()
+        });
+        partials.into_iter().fold(identity(), &op)
+    }
+
+    /// Sums the items.
+    pub fn sum<R>(self) -> R
+    where
+        R: Sum<S::Out> + Sum<R> + Send,
+    {
+        let partials = self.drive(|stage, piece| {
+            let mut sums = Vec::new();
+            let mut batch = Vec::with_capacity(SUM_BATCH);
+            for item in piece {
+                stage.feed(item, &mut |x| {
+                    batch.push(x);
+                    if batch.len() == SUM_BATCH {
+                        sums.push(batch.drain(..).sum::<R>());
+                    }
+                });
+            }
+            sums.push(batch.into_iter().sum::<R>());
+            sums.into_iter().sum::<R>()
+        });
+        partials.into_iter().sum()
+    }
+
+    /// Counts the items.
+    pub fn count(self) -> usize {
+        let partials = self.drive(|stage, piece| {
+            let mut count = 0;
+            for item in piece {
+                stage.feed(item, &mut |_| count += 1);
+            }
+            count
+        });
+        partials.into_iter().sum()
+    }
+
+    fn collect_pieces(self) -> Vec<Vec<S::Out>>
+    where
+        S::Out: Send,
+    {
+        self.drive(|stage, piece| {
+            let mut items = Vec::with_capacity(piece.size_hint().0);
+            for item in piece {
+                stage.feed(item, &mut |x| items.push(x));
+            }
+            items
+        })
+    }
+
+    // Splits the producer into one piece per thread and runs `work` on each,
+    // returning the results in order.
+    fn drive<R, W>(self, work: W) -> Vec<R>
+    where
+        R: Send,
+        W: Fn(&S, P::IntoIter) -> R + Sync,
+    {
+        let ParIter { producer, stage, threads, min_len } = self;
+        let len = producer.len();
+        let count = cmp::max(cmp::min(threads, len / min_len), 1);
+
+        let mut pieces = Vec::with_capacity(count);
+        let mut rest = producer;
+        for i in 0..count - 1 {
+            // Spread the remainder over the first pieces.
+            let size = len / count + (i < len % count) as usize;
+            let (head, tail) = rest.split_at(size);
+            pieces.push(head);
+            rest = tail;
+        }
+        pieces.push(rest);
+
+        let stage = &stage;
+        run_all(pieces, |piece: P| work(stage, piece.into_iter()))
+    }
+}
+
+// Runs `work` on every item, each on its own scoped thread except the last,
+// which runs on the calling thread. Returns the results in order. If any of
+// them panics, the first panic is resumed once all of them are done.
+fn run_all<I, R, W>(items: Vec<I>, work: W) -> Vec<R>
+where
+    I: Send,
+    R: Send,
+    W: Fn(I) -> R + Sync,
+{
+    let mut items = items.into_iter();
+    let last = match items.next_back() {
+        Some(last) => last,
+        None => return Vec::new(),
+    };
+    let work = &work;
+    thread::scope(|scope| {
+        let handles: Vec<_> = items.map(|item| scope.spawn(move || work(item))).collect();
+        let last = panic::catch_unwind(panic::AssertUnwindSafe(|| work(last)));
+
+        let mut results = Vec::with_capacity(handles.len() + 1);
+        let mut payload = None;
+        for handle in handles {
+            match handle.join() {
+                Ok(result) => results.push(result),
+                Err(err) => payload = payload.or(Some(err)),
+            }
+        }
+        match last {
+            Ok(result) => results.push(result),
+            Err(err) => payload = payload.or(Some(err)),
+        }
+        if let Some(payload) = payload {
+            panic::resume_unwind(payload);
+        }
+        results
+    })
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Returns a parallel iterator over references to the elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec::Vec;
+    ///
+    /// let v: Vec<u32> = (1..=10_000).collect();
+    /// assert_eq!(v.par_iter().filter(|&&x| x % 3 == 0).count(), 3333);
+    /// ```
+    pub fn par_iter(&self) -> ParIter<&[T]>
+    where
+        T: Sync,
+    {
+        ParIter::new(self.as_slice())
+    }
+
+    /// Returns a parallel iterator over mutable references to the elements.
+    pub fn par_iter_mut(&mut self) -> ParIter<&mut [T]>
+    where
+        T: Send,
+    {
+        ParIter::new(self.as_mut_slice())
+    }
+
+    /// Returns a parallel iterator over `chunk_size` elements at a time.
+    ///
+    /// The last chunk is shorter if `chunk_size` does not divide the length.
+    /// [`ParIter::min_len`] counts chunks, not elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec::Vec;
+    ///
+    /// let v: Vec<u32> = (0..10).collect();
+    /// let sums: Vec<u32> = v.par_chunks(4).map(|c| c.iter().sum()).collect();
+    /// assert_eq!(sums, [6, 22, 17]);
+    /// ```
+    pub fn par_chunks(&self, chunk_size: usize) -> ParIter<Chunks<'_, T>>
+    where
+        T: Sync,
+    {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        ParIter::new(Chunks { slice: self.as_slice(), size: chunk_size })
+    }
+
+    /// Sorts the vector in parallel.
+    ///
+    /// This sort is stable. It uses [`threads()`] threads and allocates
+    /// about half the vector's size in scratch space.
+    pub fn par_sort(&mut self)
+    where
+        T: Ord + Send,
+    {
+        self.par_sort_by(T::cmp)
+    }
+
+    /// Sorts the vector in parallel with a comparator function.
+    ///
+    /// See [`par_sort`](Vec::par_sort).
+    pub fn par_sort_by<F>(&mut self, compare: F)
+    where
+        T: Send,
+        F: Fn(&T, &T) -> Ordering + Sync,
+    {
+        let v = self.as_mut_slice();
+        let len = v.len();
+        let count = cmp::min(threads(), len / DEFAULT_MIN_LEN);
+        if count <= 1 || mem::size_of::<T>() == 0 {
+            algorithms::timsort_by(v, &compare);
+            return;
+        }
+
+        // Sort one run per thread...
+        let mut run = len.div_ceil(count);
+        run_all(v.chunks_mut(run).collect(), |chunk| algorithms::timsort_by(chunk, &compare));
+
+        // ...then merge neighbouring runs, halving their number every round.
+        while run < len {
+            run_all(v.chunks_mut(2 * run).collect(), |pair| {
+                if pair.len() > run {
+                    let mut buf = Vec::<T>::with_capacity(pair.len() - run);
+                    let is_less = &mut |a: &T, b: &T| compare(a, b) == Ordering::Less;
+                    unsafe { algorithms::merge(pair, run, buf.as_mut_ptr(), is_less) };
+                }
+            });
+            run *= 2;
+        }
+    }
+
+    /// Sorts the vector in parallel with a key extraction function.
+    ///
+    /// See [`par_sort`](Vec::par_sort). The key function may be called more
+    /// than once per element.
+    pub fn par_sort_by_key<K, F>(&mut self, f: F)
+    where
+        T: Send,
+        K: Ord,
+        F: Fn(&T) -> K + Sync,
+    {
+        self.par_sort_by(|a, b| f(a).cmp(&f(b)))
+    }
+
+    /// Appends the items of a parallel iterator, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::{vec, vec::Vec};
+    ///
+    /// let input: Vec<u32> = (0..5).collect();
+    /// let mut out = vec![100];
+    /// out.par_extend(input.par_iter().map(|x| x * x));
+    /// assert_eq!(out, [100, 0, 1, 4, 9, 16]);
+    /// ```
+    pub fn par_extend<P, S>(&mut self, iter: ParIter<P, S>)
+    where
+        T: Send,
+        P: Producer,
+        S: Stage<P::Item, Out = T>,
+    {
+        let pieces = iter.collect_pieces();
+        self.reserve(pieces.iter().map(Vec::len).sum());
+        for piece in pieces {
+            self.extend(piece);
+        }
+    }
+
+    /// Retains only the elements for which `keep` returns `true`, calling
+    /// `keep` in parallel.
+    ///
+    /// The order of the retained elements is preserved. Rejected elements
+    /// are dropped on the calling thread, after every call to `keep` has
+    /// returned, so a panic in `keep` leaves the vector unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec::Vec;
+    ///
+    /// let mut v: Vec<u32> = (0..10).collect();
+    /// v.par_retain(|&x| x % 3 != 0);
+    /// assert_eq!(v, [1, 2, 4, 5, 7, 8]);
+    /// ```
+    pub fn par_retain<F>(&mut self, keep: F)
+    where
+        T: Sync,
+        F: Fn(&T) -> bool + Sync,
+    {
+        let verdicts = self.par_iter().map(&keep).collect();
+        let mut verdicts = verdicts.into_iter();
+        self.retain(|_| verdicts.next().unwrap());
+    }
+}
@@ -0,0 +1,130 @@
+use super::*;
+
+const LENS: [usize; 6] = [0, 1, 2, 7, 100, 5000];
+
+fn range(len: usize) -> Vec<u64> {
+    (0..len as u64).collect()
+}
+
+#[test]
+fn pieces_cover_every_item_once_and_in_order() {
+    for len in LENS {
+        let v = range(len);
+        for threads in [1, 2, 3, 8] {
+            let iter = || v.par_iter().threads(threads).min_len(1);
+            let doubled = iter().map(|&x| x * 2).collect();
+            assert_eq!(doubled, v.iter().map(|&x| x * 2).collect::<Vec<_>>());
+            let odd = iter().filter(|&&x| x % 2 == 1).map(|&x| x).collect();
+            assert_eq!(odd, v.iter().copied().filter(|x| x % 2 == 1).collect::<Vec<_>>());
+            assert_eq!(iter().count(), len);
+            assert_eq!(iter().map(|&x| x).sum::<u64>(), v.iter().sum());
+            assert_eq!(iter().map(|&x| x).reduce(|| 0, u64::max), len.saturating_sub(1) as u64);
+
+            let visits = AtomicUsize::new(0);
+            iter().for_each(|_| {
+                visits.fetch_add(1, atomic::Ordering::Relaxed);
+            });
+            assert_eq!(visits.into_inner(), len);
+        }
+    }
+}
+
+#[test]
+fn par_iter_mut_reaches_every_element() {
+    for len in LENS {
+        let mut v = range(len);
+        v.par_iter_mut().threads(4).min_len(1).for_each(|x| *x += 1);
+        assert_eq!(v, (1..=len as u64).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn par_chunks_splits_on_chunk_boundaries() {
+    let v = range(1000);
+    for size in [1, 3, 64, 999, 1000, 2000] {
+        let sums = v.par_chunks(size).threads(4).min_len(1).map(|c| c.iter().sum::<u64>()).collect();
+        let expected: Vec<u64> = v.chunks(size).map(|c| c.iter().sum::<u64>()).collect();
+        assert_eq!(sums, expected);
+    }
+}
+
+#[test]
+#[should_panic(expected = "chunk size must be non-zero")]
+fn par_chunks_rejects_zero() {
+    let _ = range(10).par_chunks(0);
+}
+
+#[test]
+fn par_sort_is_stable() {
+    // Enough elements, and threads, for the sort to split into runs and
+    // merge them. Other tests do not depend on the default thread count.
+    set_threads(4);
+    assert_eq!(threads(), 4);
+
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let mut v: Vec<(u16, usize)> = (0..20_000)
+        .map(|i| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            ((state % 100) as u16, i)
+        })
+        .collect();
+    let mut expected: Vec<_> = v.iter().copied().collect();
+    expected.sort_by_key(|&(key, _)| key);
+
+    v.par_sort_by_key(|&(key, _)| key);
+    assert_eq!(v, expected);
+
+    v.par_sort_by(|a, b| b.1.cmp(&a.1));
+    expected.sort_by_key(|&(_, i)| core::cmp::Reverse(i));
+    assert_eq!(v, expected);
+
+    v.par_sort();
+    expected.sort();
+    assert_eq!(v, expected);
+
+    let mut units: Vec<()> = (0..5000).map(|_| ()).collect();
+    units.par_sort();
+    assert_eq!(units.len(), 5000);
+}
+
+#[test]
+fn par_extend_and_par_retain_keep_order() {
+    let input = range(3000);
+    let mut out: Vec<u64> = Vec::new();
+    out.push(7);
+    out.par_extend(input.par_iter().threads(3).min_len(1).map(|x| x + 1));
+    assert_eq!(out[0], 7);
+    assert_eq!(out[1..], *(1..=3000).collect::<Vec<_>>());
+
+    let mut v = range(3000);
+    v.par_retain(|&x| x % 7 != 0);
+    assert_eq!(v, (0..3000).filter(|x| x % 7 != 0).collect::<Vec<_>>());
+}
+
+#[test]
+fn a_panic_is_resumed_after_every_piece_finishes() {
+    let v = range(1000);
+    let visits = AtomicUsize::new(0);
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        v.par_iter().threads(4).min_len(1).for_each(|&x| {
+            visits.fetch_add(1, atomic::Ordering::Relaxed);
+            if x == 10 {
+                panic!("boom");
+            }
+        });
+    }));
+    let payload = result.unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"boom"));
+    // Only the piece that panicked stopped early.
+    assert!(visits.into_inner() > 750);
+
+    // A panicking `par_retain` predicate leaves the vector as it was.
+    let mut v = range(100);
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        v.par_retain(|&x| if x == 50 { panic!("boom") } else { x % 2 == 0 });
+    }));
+    assert!(result.is_err());
+    assert_eq!(v, range(100));
+}
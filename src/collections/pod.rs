@@ -0,0 +1,217 @@
+//! Plain-old-data element types.
+//!
+//! Some containers hand their memory to something that only understands
+//! bytes, such as a file or a socket, and read it back later without running
+//! any constructor. That is only sound for types where every bit pattern is
+//! a valid value and that own nothing outside their own bytes. The [`Pod`]
+//! trait marks those types.
+//!
+//! The weaker [`Zeroable`] trait marks types for which all zero bytes is a
+//! valid value. [`from_zeroable_elem`] uses it to ask the allocator for
+//! zeroed memory instead of cloning a zero `elem` into every slot.
+//!
+//! [`from_zeroable_elem`]: crate::vec::from_zeroable_elem
+
+use core::num::{NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize};
+use core::ptr::NonNull;
+
+#[cfg(test)]
+mod tests;
+
+/// Types that can be copied to and from raw bytes.
+///
+/// # Safety
+///
+/// Implementing `Pod` for a type asserts that:
+///
+/// * every bit pattern of `size_of::<Self>()` bytes is a valid value,
+///   so the type may not contain `bool`, `char`, enums, references or
+///   `NonNull`-like niches,
+/// * the type has no padding bytes, so that writing out a value never leaks
+///   uninitialized memory, which in practice means `#[repr(C)]` or
+///   `#[repr(transparent)]` with fields laid out back to back, and
+/// * the type holds no pointers or handles whose meaning would be lost
+///   outside the current process.
+///
+/// `Pod` types are `Copy` and `'static`, since a value rebuilt from bytes
+/// can neither run a destructor nor borrow from anything.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(
+            unsafe impl Pod for $t {}
+        )*
+    }
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// Types whose all-zero bit pattern is a valid value.
+///
+/// [`from_zeroable_elem`] calls [`is_zero`] on `elem`, and if it returns
+/// `true`, allocates zeroed memory for the `n` elements instead of cloning
+/// `elem` `n` times. For large tables of zero-initialized structs this skips a
+/// pass over the memory, and the allocator can often hand out pages that
+/// are already zero.
+///
+/// # Safety
+///
+/// Implementing `Zeroable` for a type asserts that whenever [`is_zero`]
+/// returns `true` for a value, a value made of `size_of::<Self>()` zero
+/// bytes is valid and indistinguishable from a clone of it. Padding bytes
+/// may differ.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_collections::pod::Zeroable;
+/// use rusty_collections::vec;
+///
+/// #[derive(Clone)]
+/// struct Counter {
+///     hits: u64,
+///     window: [u32; 4],
+///     label: Option<Box<str>>,
+/// }
+///
+/// unsafe impl Zeroable for Counter {
+///     fn is_zero(&self) -> bool {
+///         self.hits.is_zero() && self.window.is_zero() && self.label.is_zero()
+///     }
+/// }
+///
+/// let empty = Counter { hits: 0, window: [0; 4], label: None };
+/// let table = vec::from_zeroable_elem(empty, 1 << 16);
+/// assert!(table.iter().all(Zeroable::is_zero));
+/// ```
+///
+/// [`is_zero`]: Zeroable::is_zero
+/// [`from_zeroable_elem`]: crate::vec::from_zeroable_elem
+pub unsafe trait Zeroable {
+    /// Returns `true` if the value is represented by zero bytes only,
+    /// ignoring padding.
+    fn is_zero(&self) -> bool;
+}
+
+macro_rules! impl_zeroable {
+    ($t:ty, $is_zero:expr) => {
+        unsafe impl Zeroable for $t {
+            #[inline]
+            fn is_zero(&self) -> bool {
+                $is_zero(*self)
+            }
+        }
+    };
+}
+
+impl_zeroable!(i8, |x| x == 0);
+impl_zeroable!(i16, |x| x == 0);
+impl_zeroable!(i32, |x| x == 0);
+impl_zeroable!(i64, |x| x == 0);
+impl_zeroable!(i128, |x| x == 0);
+impl_zeroable!(isize, |x| x == 0);
+
+impl_zeroable!(u8, |x| x == 0);
+impl_zeroable!(u16, |x| x == 0);
+impl_zeroable!(u32, |x| x == 0);
+impl_zeroable!(u64, |x| x == 0);
+impl_zeroable!(u128, |x| x == 0);
+impl_zeroable!(usize, |x| x == 0);
+
+impl_zeroable!(bool, |x: bool| !x);
+impl_zeroable!(char, |x| x == '\0');
+
+impl_zeroable!(f32, |x: f32| x.to_bits() == 0);
+impl_zeroable!(f64, |x: f64| x.to_bits() == 0);
+
+impl_zeroable!(Option<NonZeroU8>, |x: Option<NonZeroU8>| x.is_none());
+impl_zeroable!(Option<NonZeroU16>, |x: Option<NonZeroU16>| x.is_none());
+impl_zeroable!(Option<NonZeroU32>, |x: Option<NonZeroU32>| x.is_none());
+impl_zeroable!(Option<NonZeroU64>, |x: Option<NonZeroU64>| x.is_none());
+impl_zeroable!(Option<NonZeroU128>, |x: Option<NonZeroU128>| x.is_none());
+impl_zeroable!(Option<NonZeroUsize>, |x: Option<NonZeroUsize>| x.is_none());
+
+unsafe impl Zeroable for () {
+    #[inline]
+    fn is_zero(&self) -> bool {
+        true
+    }
+}
+
+unsafe impl<T> Zeroable for *const T {
+    #[inline]
+    fn is_zero(&self) -> bool {
+        (*self).is_null()
+    }
+}
+
+unsafe impl<T> Zeroable for *mut T {
+    #[inline]
+    fn is_zero(&self) -> bool {
+        (*self).is_null()
+    }
+}
+
+// `Option<&T>`, `Option<Box<T>>` and `Option<NonNull<T>>` are guaranteed to
+// represent `None` as null. For fat pointers, the bytes that would be the
+// pointer metadata in the `Some` variant are padding in the `None` variant,
+// so ignoring them and zero-initializing instead is ok.
+// `Option<&mut T>` never implements `Clone`, so `vec![elem; n]` would never
+// use an impl for it.
+
+unsafe impl<T: ?Sized> Zeroable for Option<&T> {
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.is_none()
+    }
+}
+
+unsafe impl<T: ?Sized> Zeroable for Option<Box<T>> {
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.is_none()
+    }
+}
+
+unsafe impl<T: ?Sized> Zeroable for Option<NonNull<T>> {
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.is_none()
+    }
+}
+
+unsafe impl<T: Zeroable, const N: usize> Zeroable for [T; N] {
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.iter().all(T::is_zero)
+    }
+}
+
+macro_rules! impl_zeroable_tuple {
+    ($($name:ident)+) => {
+        unsafe impl<$($name: Zeroable),+> Zeroable for ($($name,)+) {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn is_zero(&self) -> bool {
+                let ($(ref $name,)+) = *self;
+                $($name.is_zero())&&+
+            }
+        }
+    };
+}
+
+impl_zeroable_tuple!(A);
+impl_zeroable_tuple!(A B);
+impl_zeroable_tuple!(A B C);
+impl_zeroable_tuple!(A B C D);
+impl_zeroable_tuple!(A B C D E);
+impl_zeroable_tuple!(A B C D E F);
+impl_zeroable_tuple!(A B C D E F G);
+impl_zeroable_tuple!(A B C D E F G H);
+impl_zeroable_tuple!(A B C D E F G H I);
+impl_zeroable_tuple!(A B C D E F G H I J);
+impl_zeroable_tuple!(A B C D E F G H I J K);
+impl_zeroable_tuple!(A B C D E F G H I J K L);
@@ -0,0 +1,108 @@
+use super::*;
+
+use core::mem;
+use core::slice;
+
+use crate::vec::from_zeroable_elem;
+
+// Returns `true` if every byte of `x` is zero. Only for types without
+// padding, whose bytes are all initialized.
+fn all_zero_bytes<T>(x: &T) -> bool {
+    let bytes = unsafe { slice::from_raw_parts(x as *const T as *const u8, mem::size_of::<T>()) };
+    bytes.iter().all(|&b| b == 0)
+}
+
+// Checks that `is_zero` agrees with the bytes of each value, and that the
+// all-zero value of the type is zero.
+fn check<T: Zeroable>(values: &[T]) {
+    for x in values {
+        assert_eq!(x.is_zero(), all_zero_bytes(x));
+    }
+    let zeroed: T = unsafe { mem::zeroed() };
+    assert!(zeroed.is_zero());
+}
+
+#[test]
+fn integers_are_zero_only_when_zero() {
+    check(&[0i8, 1, -1, i8::MIN, i8::MAX]);
+    check(&[0i16, 1, -1, i16::MIN]);
+    check(&[0i32, 1, -1, i32::MIN]);
+    check(&[0i64, 1, -1, i64::MIN]);
+    check(&[0i128, 1, -1, i128::MIN]);
+    check(&[0isize, 1, -1, isize::MIN]);
+    check(&[0u8, 1, u8::MAX]);
+    check(&[0u16, 1, u16::MAX]);
+    check(&[0u32, 1, u32::MAX]);
+    check(&[0u64, 1, u64::MAX]);
+    check(&[0u128, 1, u128::MAX]);
+    check(&[0usize, 1, usize::MAX]);
+    check(&[false, true]);
+    check(&['\0', 'a', char::MAX]);
+}
+
+#[test]
+fn only_positive_zero_floats_are_zero() {
+    check(&[0.0f32, -0.0, 1.0, f32::NAN, f32::MIN_POSITIVE]);
+    check(&[0.0f64, -0.0, 1.0, f64::NAN, f64::MIN_POSITIVE]);
+    assert!(!(-0.0f32).is_zero());
+    assert!(!(-0.0f64).is_zero());
+
+    // So `-0.0` keeps its sign through the zeroed fast path.
+    let v = from_zeroable_elem(-0.0f64, 100);
+    assert!(v.iter().all(|x| x.to_bits() == (-0.0f64).to_bits()));
+    let v = from_zeroable_elem(0.0f64, 100);
+    assert!(v.iter().all(|x| x.to_bits() == 0));
+}
+
+#[test]
+fn null_pointers_and_none_are_zero() {
+    let x = 5u32;
+    let nz = NonZeroU32::new(7);
+    check(&[None, nz]);
+    check(&[None, NonZeroU8::new(1)]);
+    check(&[None, NonZeroU16::new(1)]);
+    check(&[None, NonZeroU64::new(1)]);
+    check(&[None, NonZeroU128::new(1)]);
+    check(&[None, NonZeroUsize::new(1)]);
+    check(&[core::ptr::null::<u32>(), &x]);
+    check(&[core::ptr::null_mut::<u32>(), &x as *const u32 as *mut u32]);
+    check(&[None, Some(&x)]);
+    check(&[None, Some(NonNull::from(&x))]);
+    check(&[None, Some(Box::new(1u8))]);
+
+    // Fat pointers: only the `Some` case has metadata to check.
+    assert!(None::<&[u8]>.is_zero());
+    assert!(!Some(&[1u8, 2][..]).is_zero());
+    assert!(None::<Box<str>>.is_zero());
+    assert!(!Some(Box::<str>::from("")).is_zero());
+    assert!(().is_zero());
+}
+
+#[test]
+fn arrays_and_tuples_are_zero_when_every_element_is() {
+    check(&[[0u16; 4], [0, 0, 0, 1], [1, 0, 0, 0]]);
+    check(&[[0.0f32; 2], [0.0, -0.0]]);
+    check::<[u8; 0]>(&[[]]);
+
+    // Tuples may have padding, so only `is_zero` itself is checked.
+    assert!((0u8, 0u32).is_zero());
+    assert!(!(0u8, 1u32).is_zero());
+    assert!(!(0u8, -0.0f64).is_zero());
+    assert!((0u8, None::<Box<u8>>, [0u16; 3], false).is_zero());
+    assert!((0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0u8).is_zero());
+    assert!(!(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1u8).is_zero());
+    let zeroed: (u8, u32, Option<&u8>) = unsafe { mem::zeroed() };
+    assert!(zeroed.is_zero());
+}
+
+#[test]
+fn pod_covers_numbers_and_arrays_of_them() {
+    fn pod<T: Pod>() -> usize {
+        mem::size_of::<T>()
+    }
+
+    assert_eq!(pod::<u8>() + pod::<i128>() + pod::<f64>(), 25);
+    assert_eq!(pod::<isize>(), mem::size_of::<usize>());
+    assert_eq!(pod::<[[u16; 3]; 2]>(), 12);
+    assert_eq!(pod::<[f32; 0]>(), 0);
+}
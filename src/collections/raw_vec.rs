@@ -1,21 +1,26 @@
-#![unstable(feature = "raw_vec_internals", reason = "implementation detail", issue = "none")]
 #![doc(hidden)]
 
 use core::alloc::LayoutError;
 use core::cmp;
-use core::intrinsics;
+use core::fmt;
+use core::hint;
 use core::mem::{self, ManuallyDrop, MaybeUninit};
 use core::ops::Drop;
-use core::ptr::{self, NonNull, Unique};
+use core::ptr::{self, NonNull};
 use core::slice;
 
+use crate::alloc::TryReserveError::{self, *};
 use crate::alloc::{handle_alloc_error, Allocator, Global, Layout};
-use crate::boxed::Box;
-use crate::collections::TryReserveError::{self, *};
+use crate::unique::Unique;
 
 #[cfg(test)]
 mod tests;
 
+pub mod debug_memory;
+pub mod telemetry;
+
+use telemetry::{EventKind, Path};
+
 enum AllocInit {
     /// The contents of the new memory are uninitialized.
     Uninitialized,
@@ -37,6 +42,7 @@ enum AllocInit {
 /// * Calls `handle_alloc_error` for fallible allocations.
 /// * Contains a `ptr::Unique` and thus endows the user with all related benefits.
 /// * Uses the excess returned from the allocator to use the largest available capacity.
+/// * Asks its [`GrowthPolicy`] how much to grow by whenever it has to reallocate.
 ///
 /// This type does not in anyway inspect the memory that it manages. When dropped it *will*
 /// free its memory, but it *won't* try to drop its contents. It is up to the user of `RawVec`
@@ -46,10 +52,11 @@ enum AllocInit {
 /// `usize::MAX`. This means that you need to be careful when round-tripping this type with a
 /// `Box<[T]>`, since `capacity()` won't yield the length.
 #[allow(missing_debug_implementations)]
-pub struct RawVec<T, A: Allocator = Global> {
+pub struct RawVec<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
     ptr: Unique<T>,
     cap: usize,
     alloc: A,
+    growth: G,
 }
 
 impl<T> RawVec<T, Global> {
@@ -71,6 +78,7 @@ impl<T> RawVec<T, Global> {
     /// `RawVec` with capacity `0`. If `T` is zero-sized, then it makes a
     /// `RawVec` with capacity `usize::MAX`. Useful for implementing
     /// delayed allocation.
+    #[allow(clippy::new_without_default)]
     pub const fn new() -> Self {
         Self::new_in(Global)
     }
@@ -111,39 +119,28 @@ impl<T> RawVec<T, Global> {
     pub unsafe fn from_raw_parts(ptr: *mut T, capacity: usize) -> Self {
         unsafe { Self::from_raw_parts_in(ptr, capacity, Global) }
     }
-}
-
-impl<T, A: Allocator> RawVec<T, A> {
-    /// Like `new`, but parameterized over the choice of allocator for
-    /// the returned `RawVec`.
-    #[rustc_allow_const_fn_unstable(const_fn)]
-    pub const fn new_in(alloc: A) -> Self {
-        // `cap: 0` means "unallocated". zero-sized types are ignored.
-        Self { ptr: Unique::dangling(), cap: 0, alloc }
-    }
-
-    /// Like `with_capacity`, but parameterized over the choice of
-    /// allocator for the returned `RawVec`.
-    #[inline]
-    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
-        Self::allocate_in(capacity, AllocInit::Uninitialized, alloc)
-    }
-
-    /// Like `with_capacity_zeroed`, but parameterized over the choice
-    /// of allocator for the returned `RawVec`.
-    #[inline]
-    pub fn with_capacity_zeroed_in(capacity: usize, alloc: A) -> Self {
-        Self::allocate_in(capacity, AllocInit::Zeroed, alloc)
-    }
 
     /// Converts a `Box<[T]>` into a `RawVec<T>`.
-    pub fn from_box(slice: Box<[T], A>) -> Self {
+    pub fn from_box(slice: Box<[T]>) -> Self {
         unsafe {
-            let (slice, alloc) = Box::into_raw_with_allocator(slice);
-            RawVec::from_raw_parts_in(slice.as_mut_ptr(), slice.len(), alloc)
+            let slice = Box::into_raw(slice);
+            let mut ptr = slice as *mut T;
+            if mem::size_of::<T>() != 0 && slice.len() != 0 {
+                // The box was allocated without a canary.
+                let size = mem::size_of::<T>() * slice.len();
+                let layout = Layout::from_size_align_unchecked(size, mem::align_of::<T>());
+                let block = NonNull::new_unchecked(ptr as *mut u8);
+                match debug_memory::adopt(&Global, block, layout) {
+                    Ok(block) => ptr = block.cast().as_ptr(),
+                    Err(_) => handle_alloc_error(layout),
+                }
+            }
+            RawVec::from_raw_parts(ptr, slice.len())
         }
     }
+}
 
+impl<T, G: GrowthPolicy> RawVec<T, Global, G> {
     /// Converts the entire buffer into `Box<[MaybeUninit<T>]>` with the specified `len`.
     ///
     /// Note that this will correctly reconstitute any `cap` changes
@@ -156,49 +153,93 @@ impl<T, A: Allocator> RawVec<T, A> {
     ///
     /// Note, that the requested capacity and `self.capacity()` could differ, as
     /// an allocator could overallocate and return a greater memory block than requested.
-    pub unsafe fn into_box(self, len: usize) -> Box<[MaybeUninit<T>], A> {
+    pub unsafe fn into_box(self, len: usize) -> Box<[MaybeUninit<T>]> {
         // Sanity-check one half of the safety requirement (we cannot check the other half).
         debug_assert!(
             len <= self.capacity(),
             "`len` must be smaller than or equal to `self.capacity()`"
         );
 
-        let me = ManuallyDrop::new(self);
+        let mut me = ManuallyDrop::new(self);
         unsafe {
-            let slice = slice::from_raw_parts_mut(me.ptr() as *mut MaybeUninit<T>, len);
-            Box::from_raw_in(slice, ptr::read(&me.alloc))
+            let mut ptr = me.ptr();
+            if let Some((block, layout)) = me.current_memory() {
+                // The box frees the buffer without knowing about its canary.
+                match debug_memory::release(&me.alloc, block, layout) {
+                    Ok(block) => ptr = block.cast().as_ptr(),
+                    Err(_) => handle_alloc_error(layout),
+                }
+            }
+            let slice = slice::from_raw_parts_mut(ptr as *mut MaybeUninit<T>, len);
+            ptr::drop_in_place(&mut me.growth);
+            Box::from_raw(slice)
         }
     }
+}
+
+impl<T, A: Allocator> RawVec<T, A> {
+    /// Like `new`, but parameterized over the choice of allocator for
+    /// the returned `RawVec`.
+    pub const fn new_in(alloc: A) -> Self {
+        // `cap: 0` means "unallocated". zero-sized types are ignored.
+        Self { ptr: Unique::dangling(), cap: 0, alloc, growth: Doubling }
+    }
+
+    /// Like `with_capacity`, but parameterized over the choice of
+    /// allocator for the returned `RawVec`.
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self::allocate_in(capacity, AllocInit::Uninitialized, alloc)
+    }
+
+    /// Like `with_capacity_zeroed`, but parameterized over the choice
+    /// of allocator for the returned `RawVec`.
+    #[inline]
+    pub fn with_capacity_zeroed_in(capacity: usize, alloc: A) -> Self {
+        Self::allocate_in(capacity, AllocInit::Zeroed, alloc)
+    }
+
+    /// The same as `with_capacity_in`, but returns on errors instead of panicking or aborting.
+    #[inline]
+    pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, TryReserveError> {
+        Self::try_allocate_in(capacity, AllocInit::Uninitialized, alloc)
+    }
 
     fn allocate_in(capacity: usize, init: AllocInit, alloc: A) -> Self {
+        // We avoid `unwrap_or_else` here because it bloats the amount of
+        // LLVM IR generated.
+        match Self::try_allocate_in(capacity, init, alloc) {
+            Ok(this) => this,
+            Err(CapacityOverflow) => capacity_overflow(),
+            Err(AllocError { layout, .. }) => handle_alloc_error(layout),
+        }
+    }
+
+    fn try_allocate_in(
+        capacity: usize,
+        init: AllocInit,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
         if mem::size_of::<T>() == 0 {
-            Self::new_in(alloc)
-        } else {
-            // We avoid `unwrap_or_else` here because it bloats the amount of
-            // LLVM IR generated.
-            let layout = match Layout::array::<T>(capacity) {
-                Ok(layout) => layout,
-                Err(_) => capacity_overflow(),
-            };
-            match alloc_guard(layout.size()) {
-                Ok(_) => {}
-                Err(_) => capacity_overflow(),
-            }
-            let result = match init {
-                AllocInit::Uninitialized => alloc.allocate(layout),
-                AllocInit::Zeroed => alloc.allocate_zeroed(layout),
-            };
-            let ptr = match result {
-                Ok(ptr) => ptr,
-                Err(_) => handle_alloc_error(layout),
-            };
-
-            Self {
-                ptr: unsafe { Unique::new_unchecked(ptr.cast().as_ptr()) },
-                cap: Self::capacity_from_bytes(ptr.len()),
-                alloc,
-            }
+            return Ok(Self::new_in(alloc));
+        }
+
+        let layout = Layout::array::<T>(capacity).map_err(|_| CapacityOverflow)?;
+        alloc_guard(layout.size())?;
+        let zeroed = matches!(init, AllocInit::Zeroed);
+        let result = debug_memory::allocate(&alloc, layout, zeroed);
+        let ptr = result.map_err(|_| AllocError { layout, non_exhaustive: () })?;
+
+        let this = Self {
+            ptr: unsafe { Unique::new_unchecked(ptr.cast().as_ptr()) },
+            cap: Self::capacity_from_bytes(ptr.len()),
+            alloc,
+            growth: Doubling,
+        };
+        if this.cap != 0 {
+            this.observe(EventKind::Alloc, Path::WithCapacity, 0, None);
         }
+        Ok(this)
     }
 
     /// Reconstitutes a `RawVec` from a pointer, capacity, and allocator.
@@ -213,7 +254,27 @@ impl<T, A: Allocator> RawVec<T, A> {
     /// guaranteed.
     #[inline]
     pub unsafe fn from_raw_parts_in(ptr: *mut T, capacity: usize, alloc: A) -> Self {
-        Self { ptr: unsafe { Unique::new_unchecked(ptr) }, cap: capacity, alloc }
+        Self { ptr: unsafe { Unique::new_unchecked(ptr) }, cap: capacity, alloc, growth: Doubling }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> RawVec<T, A, G> {
+    /// Replaces the growth policy of this `RawVec`, keeping its allocation.
+    ///
+    /// The old policy is dropped. Only future calls to `reserve` and
+    /// `reserve_exact` are affected; the current capacity is left untouched.
+    pub fn with_growth<H: GrowthPolicy>(self, growth: H) -> RawVec<T, A, H> {
+        let me = ManuallyDrop::new(self);
+        unsafe {
+            let alloc = ptr::read(&me.alloc);
+            drop(ptr::read(&me.growth));
+            RawVec { ptr: me.ptr, cap: me.cap, alloc, growth }
+        }
+    }
+
+    /// Returns a shared reference to the growth policy of this `RawVec`.
+    pub fn growth(&self) -> &G {
+        &self.growth
     }
 
     /// Gets a raw pointer to the start of the allocation. Note that this is
@@ -274,10 +335,9 @@ impl<T, A: Allocator> RawVec<T, A> {
     /// # Examples
     ///
     /// ```
-    /// # #![feature(raw_vec_internals)]
     /// # extern crate alloc;
     /// # use std::ptr;
-    /// # use alloc::raw_vec::RawVec;
+    /// # use rusty_collections::raw_vec::RawVec;
     /// struct MyVec<T> {
     ///     buf: RawVec<T>,
     ///     len: usize,
@@ -359,7 +419,7 @@ impl<T, A: Allocator> RawVec<T, A> {
     }
 }
 
-impl<T, A: Allocator> RawVec<T, A> {
+impl<T, A: Allocator, G: GrowthPolicy> RawVec<T, A, G> {
     /// Returns if the buffer needs to grow to fulfill the needed extra capacity.
     /// Mainly used to make inlining reserve-calls possible without inlining `grow`.
     fn needs_to_grow(&self, len: usize, additional: usize) -> bool {
@@ -396,31 +456,19 @@ impl<T, A: Allocator> RawVec<T, A> {
         // Nothing we can really do about these checks, sadly.
         let required_cap = len.checked_add(additional).ok_or(CapacityOverflow)?;
 
-        // This guarantees exponential growth. The doubling cannot overflow
-        // because `cap <= isize::MAX` and the type of `cap` is `usize`.
-        let cap = cmp::max(self.cap * 2, required_cap);
-
-        // Tiny Vecs are dumb. Skip to:
-        // - 8 if the element size is 1, because any heap allocators is likely
-        //   to round up a request of less than 8 bytes to at least 8 bytes.
-        // - 4 if elements are moderate-sized (<= 1 KiB).
-        // - 1 otherwise, to avoid wasting too much space for very short Vecs.
-        // Note that `min_non_zero_cap` is computed statically.
-        let elem_size = mem::size_of::<T>();
-        let min_non_zero_cap = if elem_size == 1 {
-            8
-        } else if elem_size <= 1024 {
-            4
-        } else {
-            1
-        };
-        let cap = cmp::max(min_non_zero_cap, cap);
+        // The policy decides how much slack to add. It may never hand back
+        // less than what was asked for, so clamp in case it tries to.
+        let cap = self.growth.amortized(self.cap, required_cap, mem::size_of::<T>());
+        let cap = cmp::max(cap, required_cap);
 
         let new_layout = Layout::array::<T>(cap);
 
         // `finish_grow` is non-generic over `T`.
-        let ptr = finish_grow(new_layout, self.current_memory(), &mut self.alloc)?;
+        let old_cap = self.cap;
+        let current_memory = self.current_memory();
+        let ptr = finish_grow(new_layout, current_memory, &mut self.alloc)?;
         self.set_ptr(ptr);
+        self.observe_grow(Path::GrowAmortized, old_cap, current_memory);
         Ok(())
     }
 
@@ -434,31 +482,79 @@ impl<T, A: Allocator> RawVec<T, A> {
             return Err(CapacityOverflow);
         }
 
-        let cap = len.checked_add(additional).ok_or(CapacityOverflow)?;
+        let required_cap = len.checked_add(additional).ok_or(CapacityOverflow)?;
+        let cap = self.growth.exact(self.cap, required_cap, mem::size_of::<T>());
+        let cap = cmp::max(cap, required_cap);
         let new_layout = Layout::array::<T>(cap);
 
         // `finish_grow` is non-generic over `T`.
-        let ptr = finish_grow(new_layout, self.current_memory(), &mut self.alloc)?;
+        let old_cap = self.cap;
+        let current_memory = self.current_memory();
+        let ptr = finish_grow(new_layout, current_memory, &mut self.alloc)?;
         self.set_ptr(ptr);
+        self.observe_grow(Path::GrowExact, old_cap, current_memory);
         Ok(())
     }
 
     fn shrink(&mut self, amount: usize) -> Result<(), TryReserveError> {
         assert!(amount <= self.capacity(), "Tried to shrink to a larger capacity");
 
-        let (ptr, layout) = if let Some(mem) = self.current_memory() { mem } else { return Ok(()) };
+        let (old_ptr, layout) =
+            if let Some(mem) = self.current_memory() { mem } else { return Ok(()) };
         let new_size = amount * mem::size_of::<T>();
 
         let ptr = unsafe {
             let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
-            self.alloc.shrink(ptr, layout, new_layout).map_err(|_| TryReserveError::AllocError {
-                layout: new_layout,
-                non_exhaustive: (),
+            debug_memory::shrink(&self.alloc, old_ptr, layout, new_layout).map_err(|_| {
+                TryReserveError::AllocError { layout: new_layout, non_exhaustive: () }
             })?
         };
+        let old_cap = self.cap;
         self.set_ptr(ptr);
+        let kind = if amount == 0 { EventKind::Free } else { EventKind::Shrink };
+        self.observe(kind, Path::Shrink, old_cap, Some(old_ptr));
         Ok(())
     }
+
+    #[inline]
+    fn observe_grow(
+        &self,
+        path: Path,
+        old_cap: usize,
+        old_memory: Option<(NonNull<u8>, Layout)>,
+    ) {
+        let kind = if old_memory.is_some() { EventKind::Grow } else { EventKind::Alloc };
+        self.observe(kind, path, old_cap, old_memory.map(|(ptr, _)| ptr));
+    }
+
+    // Reports a change of the buffer to the telemetry hook, if there is one.
+    // `old_ptr` is the block the buffer lived in before, if it had one.
+    #[inline]
+    fn observe(&self, kind: EventKind, path: Path, old_cap: usize, old_ptr: Option<NonNull<u8>>) {
+        if !telemetry::enabled() {
+            return;
+        }
+        let new_cap = if kind == EventKind::Free { 0 } else { self.cap };
+        let in_place = match old_ptr {
+            Some(ptr) => kind != EventKind::Free && ptr.as_ptr() == self.ptr() as *mut u8,
+            None => false,
+        };
+        let bytes_moved = if old_ptr.is_some() && !in_place && kind != EventKind::Free {
+            cmp::min(old_cap, new_cap) * mem::size_of::<T>()
+        } else {
+            0
+        };
+        telemetry::report(&telemetry::Event {
+            kind,
+            path,
+            type_name: core::any::type_name::<T>(),
+            elem_size: mem::size_of::<T>(),
+            old_capacity: old_cap,
+            new_capacity: new_cap,
+            bytes_moved,
+            in_place,
+        });
+    }
 }
 
 // This function is outside `RawVec` to minimize compile times. See the comment
@@ -483,25 +579,191 @@ where
         debug_assert_eq!(old_layout.align(), new_layout.align());
         unsafe {
             // The allocator checks for alignment equality
-            intrinsics::assume(old_layout.align() == new_layout.align());
-            alloc.grow(ptr, old_layout, new_layout)
+            hint::assert_unchecked(old_layout.align() == new_layout.align());
+            debug_memory::grow(alloc, ptr, old_layout, new_layout)
         }
     } else {
-        alloc.allocate(new_layout)
+        debug_memory::allocate(alloc, new_layout, false)
     };
 
     memory.map_err(|_| AllocError { layout: new_layout, non_exhaustive: () })
 }
 
-unsafe impl<#[may_dangle] T, A: Allocator> Drop for RawVec<T, A> {
+impl<T, A: Allocator, G: GrowthPolicy> Drop for RawVec<T, A, G> {
     /// Frees the memory owned by the `RawVec` *without* trying to drop its contents.
     fn drop(&mut self) {
         if let Some((ptr, layout)) = self.current_memory() {
-            unsafe { self.alloc.deallocate(ptr, layout) }
+            self.observe(EventKind::Free, Path::Drop, self.cap, None);
+            unsafe { debug_memory::deallocate(&self.alloc, ptr, layout) }
+        }
+    }
+}
+
+/// A strategy for choosing the new capacity of a [`RawVec`] that has run out of room.
+///
+/// `RawVec` consults its policy every time `reserve` or `reserve_exact` has to
+/// reallocate. The policy only picks a number; overflow checks, the
+/// `isize::MAX` guard and the actual reallocation all stay in `RawVec`.
+///
+/// Zero-sized element types never reach the policy, since their capacity is
+/// always `usize::MAX`.
+pub trait GrowthPolicy {
+    /// Returns the capacity to grow to when `reserve` needs room for
+    /// `required` elements of `elem_size` bytes and only `cap` are allocated.
+    ///
+    /// Returning less than `required` is not an error: `RawVec` raises the
+    /// result to `required`. Returning something huge is not unsafe either,
+    /// it just turns into a capacity overflow or allocation failure.
+    fn amortized(&self, cap: usize, required: usize, elem_size: usize) -> usize;
+
+    /// Like [`amortized`], but called by `reserve_exact`.
+    ///
+    /// The default asks for exactly `required` elements.
+    ///
+    /// [`amortized`]: GrowthPolicy::amortized
+    #[inline]
+    fn exact(&self, cap: usize, required: usize, elem_size: usize) -> usize {
+        let _ = (cap, elem_size);
+        required
+    }
+}
+
+/// Doubles the capacity on every amortized growth.
+///
+/// This is the default policy of `RawVec` and `Vec`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Doubling;
+
+impl GrowthPolicy for Doubling {
+    #[inline]
+    fn amortized(&self, cap: usize, required: usize, elem_size: usize) -> usize {
+        // This guarantees exponential growth. The doubling cannot overflow
+        // because `cap <= isize::MAX` and the type of `cap` is `usize`.
+        let cap = cmp::max(cap * 2, required);
+        cmp::max(min_non_zero_cap(elem_size), cap)
+    }
+}
+
+/// Grows the capacity by half of its current value on every amortized growth.
+///
+/// Wastes at most a third of the buffer instead of half of it, at the price
+/// of more frequent reallocations than [`Doubling`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OneAndHalf;
+
+impl GrowthPolicy for OneAndHalf {
+    #[inline]
+    fn amortized(&self, cap: usize, required: usize, elem_size: usize) -> usize {
+        // Cannot overflow for the same reason as in `Doubling`.
+        let cap = cmp::max(cap + cap / 2, required);
+        cmp::max(min_non_zero_cap(elem_size), cap)
+    }
+}
+
+/// Grows the capacity in fixed steps of `N` elements.
+///
+/// `reserve` rounds the required capacity up to the next multiple of `N`,
+/// so pushing one element at a time reallocates every `N` pushes. This gives
+/// up amortized *O*(1) `push` in exchange for predictable memory use.
+/// `reserve_exact` is not rounded.
+///
+/// `Chunked<0>` behaves like exact growth.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Chunked<const N: usize>;
+
+impl<const N: usize> GrowthPolicy for Chunked<N> {
+    #[inline]
+    fn amortized(&self, _cap: usize, required: usize, _elem_size: usize) -> usize {
+        if N == 0 {
+            return required;
+        }
+        match required.checked_add(N - 1) {
+            Some(rounded) => rounded / N * N,
+            // Let `RawVec` report the overflow.
+            None => required,
         }
     }
 }
 
+/// Grows like [`Doubling`], then rounds the size of the buffer in bytes up to
+/// a multiple of `PAGE`.
+///
+/// Unlike the other built-in policies this also rounds `reserve_exact`,
+/// because an allocator that hands out whole pages would round it anyway.
+/// Elements that do not evenly divide `PAGE` leave a few bytes unused at the
+/// end of the buffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PageRounded<const PAGE: usize>;
+
+impl<const PAGE: usize> PageRounded<PAGE> {
+    #[inline]
+    fn round(cap: usize, elem_size: usize) -> usize {
+        if PAGE == 0 {
+            return cap;
+        }
+        let rounded = cap
+            .checked_mul(elem_size)
+            .and_then(|bytes| bytes.checked_add(PAGE - 1))
+            .map(|bytes| bytes / PAGE * PAGE / elem_size);
+        // On overflow, let `RawVec` report it for the unrounded capacity.
+        rounded.unwrap_or(cap)
+    }
+}
+
+impl<const PAGE: usize> GrowthPolicy for PageRounded<PAGE> {
+    #[inline]
+    fn amortized(&self, cap: usize, required: usize, elem_size: usize) -> usize {
+        Self::round(Doubling.amortized(cap, required, elem_size), elem_size)
+    }
+
+    #[inline]
+    fn exact(&self, _cap: usize, required: usize, elem_size: usize) -> usize {
+        Self::round(required, elem_size)
+    }
+}
+
+/// Grows to whatever a closure returns.
+///
+/// The closure is called as `f(cap, required, elem_size)` for `reserve`, with
+/// the same meaning as in [`GrowthPolicy::amortized`]. `reserve_exact` does
+/// not call it.
+#[derive(Clone, Copy)]
+pub struct GrowWith<F>(pub F);
+
+impl<F> GrowthPolicy for GrowWith<F>
+where
+    F: Fn(usize, usize, usize) -> usize,
+{
+    #[inline]
+    fn amortized(&self, cap: usize, required: usize, elem_size: usize) -> usize {
+        (self.0)(cap, required, elem_size)
+    }
+}
+
+impl<F> fmt::Debug for GrowWith<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("GrowWith { .. }")
+    }
+}
+
+// Tiny Vecs are dumb. Skip to:
+// - 8 if the element size is 1, because any heap allocators is likely
+//   to round up a request of less than 8 bytes to at least 8 bytes.
+// - 4 if elements are moderate-sized (<= 1 KiB).
+// - 1 otherwise, to avoid wasting too much space for very short Vecs.
+// Note that `elem_size` is a constant after inlining, so this is computed
+// statically.
+#[inline]
+fn min_non_zero_cap(elem_size: usize) -> usize {
+    if elem_size == 1 {
+        8
+    } else if elem_size <= 1024 {
+        4
+    } else {
+        1
+    }
+}
+
 // Central function for reserve error handling.
 #[inline]
 fn handle_reserve(result: Result<(), TryReserveError>) {
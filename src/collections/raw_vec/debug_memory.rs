@@ -0,0 +1,304 @@
+//! Memory checking for `RawVec` and `Vec`, enabled by the `debug-memory`
+//! cargo feature.
+//!
+//! With the feature on:
+//!
+//! * Every buffer is allocated with [`CANARY_LEN`] extra bytes after it,
+//!   filled with a canary pattern. The canary is checked whenever the buffer
+//!   is grown, shrunk or freed, and a write past the end of the buffer
+//!   panics there instead of corrupting the heap.
+//! * Spare capacity is filled with a poison pattern: fresh memory after an
+//!   allocation or grow, the slots past the length after `Vec::shrink_to`
+//!   and `Vec::shrink_to_fit`, and the slots given up by `Vec::set_len`.
+//! * `Vec::set_len` and `Vec::from_raw_parts` check the slots they mark as
+//!   initialized, and panic if one of them still holds poison. This catches
+//!   a `set_len` after `spare_capacity_mut` that forgot to write an element,
+//!   before anything reads it. The crate's own code sets lengths without
+//!   this check, since it only ever exposes elements it wrote itself.
+//!
+//! Poison is an 8-byte word, anchored to 8-byte aligned addresses, so a slot
+//! is only reported when an aligned word inside the exposed range is still
+//! poison. Elements smaller than that are checked in runs. Real data that
+//! happens to contain the poison word is reported too; the word was picked
+//! to make that unlikely.
+//!
+//! Reading an element's padding, or the unused bytes of an enum variant, is
+//! undefined behavior, and stable Rust cannot tell from generic code which
+//! types have such bytes. So only the elements of primitive integer and
+//! float types, and arrays of them, are checked for poison. Canaries and
+//! poisoning work for every type.
+//!
+//! Without the feature every function here forwards straight to the
+//! allocator, and the checks compile away.
+//!
+//! Buffers crossing into a `Box` lose their canary, and buffers coming from
+//! a `Box` gain one, so boxes and vectors can still be converted into each
+//! other. Buffers passed to `Vec::from_raw_parts` are expected to come from
+//! `Vec::into_raw_parts`, as always, and so already carry a canary.
+
+use core::alloc::Layout;
+use core::any;
+use core::mem;
+use core::ops::Range;
+use core::ptr::{self, NonNull};
+
+use crate::alloc::{self, AllocError, Allocator};
+
+/// Whether the `debug-memory` feature is enabled.
+pub const ENABLED: bool = cfg!(feature = "debug-memory");
+
+/// The word written into spare capacity.
+pub const POISON: u64 = 0xDEAD_BEEF_DEAD_BEEF;
+
+/// The byte repeated after the end of each buffer.
+pub const CANARY: u8 = 0xC5;
+
+/// Number of canary bytes after each buffer.
+pub const CANARY_LEN: usize = 16;
+
+// The layout actually requested from the allocator for a buffer of `layout`.
+fn padded(layout: Layout) -> Result<Layout, AllocError> {
+    if !ENABLED || layout.size() == 0 {
+        return Ok(layout);
+    }
+    let size = layout.size().checked_add(CANARY_LEN).ok_or(AllocError)?;
+    Layout::from_size_align(size, layout.align()).map_err(|_| AllocError)
+}
+
+/// Writes the canary after a buffer ending at `end`.
+///
+/// # Safety
+///
+/// `end` must be valid for writes of [`CANARY_LEN`] bytes.
+pub unsafe fn write_canary(end: *mut u8) {
+    unsafe { ptr::write_bytes(end, CANARY, CANARY_LEN) }
+}
+
+/// Returns `true` if the canary after a buffer ending at `end` is intact.
+///
+/// # Safety
+///
+/// `end` must be valid for reads of [`CANARY_LEN`] bytes.
+pub unsafe fn has_canary(end: *const u8) -> bool {
+    (0..CANARY_LEN).all(|i| unsafe { *end.add(i) } == CANARY)
+}
+
+// Panics if the canary of the buffer at `ptr` has been overwritten.
+unsafe fn check_canary(ptr: NonNull<u8>, layout: Layout) {
+    if ENABLED && layout.size() != 0 && !unsafe { has_canary(ptr.as_ptr().add(layout.size())) } {
+        canary_overwritten(ptr, layout);
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn canary_overwritten(ptr: NonNull<u8>, layout: Layout) -> ! {
+    panic!(
+        "debug-memory: write past the end of the {}-byte buffer at {:p}",
+        layout.size(),
+        ptr.as_ptr()
+    );
+}
+
+// Sets up the canary of a freshly (re)allocated block and trims the slice
+// handed back to the caller: the excess the allocator reported is where the
+// canary lives.
+unsafe fn finish(block: NonNull<[u8]>, layout: Layout) -> NonNull<[u8]> {
+    if !ENABLED || layout.size() == 0 {
+        return block;
+    }
+    let ptr = block.cast::<u8>().as_ptr();
+    unsafe { write_canary(ptr.add(layout.size())) };
+    NonNull::slice_from_raw_parts(unsafe { NonNull::new_unchecked(ptr) }, layout.size())
+}
+
+/// Fills `len` bytes at `ptr` with poison.
+///
+/// # Safety
+///
+/// `ptr` must be valid for writes of `len` bytes.
+pub unsafe fn poison(ptr: *mut u8, len: usize) {
+    if !ENABLED {
+        return;
+    }
+    let bytes = POISON.to_ne_bytes();
+    for i in 0..len {
+        unsafe {
+            let p = ptr.add(i);
+            *p = bytes[p as usize % 8];
+        }
+    }
+}
+
+/// Panics if any of the elements `range` of the buffer at `ptr` still holds
+/// poison. `what` names the operation that exposed them.
+///
+/// Only checks element types that [`has_no_padding`]; it does nothing for
+/// the others.
+///
+/// # Safety
+///
+/// The elements `range` of `ptr` must be valid for reads.
+pub unsafe fn check_initialized<T>(ptr: *const T, range: Range<usize>, what: &str) {
+    let elem_size = mem::size_of::<T>();
+    if !ENABLED || elem_size == 0 || !has_no_padding::<T>() {
+        return;
+    }
+    let base = ptr as usize;
+    let (start, end) = (base + range.start * elem_size, base + range.end * elem_size);
+    let mut word = (start + 7) & !7;
+    while word + 8 <= end {
+        if unsafe { ptr::read(word as *const u64) } == POISON {
+            uninitialized(what, (word - base) / elem_size);
+        }
+        word += 8;
+    }
+}
+
+/// Returns `true` if every byte of a `T` is part of its value, so that
+/// initialized `T`s can be read as words.
+///
+/// Generic code cannot ask whether `T` is [`Pod`] on stable Rust, so this
+/// recognizes the primitive integer and float types, and arrays of them, by
+/// their name. It returns `false` for every other type, including ones
+/// without padding.
+///
+/// [`Pod`]: crate::pod::Pod
+pub fn has_no_padding<T>() -> bool {
+    fn is_number(name: &str) -> bool {
+        match name.strip_prefix('[').and_then(|rest| rest.rsplit_once("; ")) {
+            Some((elem, _)) => is_number(elem),
+            None => matches!(
+                name,
+                "u8" | "u16" | "u32" | "u64" | "u128" | "usize"
+                    | "i8" | "i16" | "i32" | "i64" | "i128" | "isize"
+                    | "f32" | "f64"
+            ),
+        }
+    }
+    is_number(any::type_name::<T>())
+}
+
+#[cold]
+#[inline(never)]
+fn uninitialized(what: &str, index: usize) -> ! {
+    panic!("debug-memory: `{}` exposed uninitialized element {}", what, index);
+}
+
+/// Allocates a block for `layout`, followed by a canary.
+///
+/// Unless `zeroed` is set, the block is poisoned.
+pub fn allocate<A: Allocator>(
+    alloc: &A,
+    layout: Layout,
+    zeroed: bool,
+) -> Result<NonNull<[u8]>, AllocError> {
+    let outer = padded(layout)?;
+    let block = if zeroed { alloc.allocate_zeroed(outer)? } else { alloc.allocate(outer)? };
+    unsafe {
+        if !zeroed {
+            poison(block.cast::<u8>().as_ptr(), layout.size());
+        }
+        Ok(finish(block, layout))
+    }
+}
+
+/// Checks the canary and frees a block from [`allocate`].
+///
+/// # Safety
+///
+/// As for [`Allocator::deallocate`], with `layout` the layout the block was
+/// allocated, grown or shrunk to through this module.
+pub unsafe fn deallocate<A: Allocator>(alloc: &A, ptr: NonNull<u8>, layout: Layout) {
+    unsafe {
+        check_canary(ptr, layout);
+        // `padded` only fails for layouts that could not have been allocated.
+        alloc.deallocate(ptr, padded(layout).unwrap_or(layout));
+    }
+}
+
+/// Checks the canary, grows a block from [`allocate`] and poisons the new
+/// part of it.
+///
+/// # Safety
+///
+/// As for [`Allocator::grow`].
+pub unsafe fn grow<A: Allocator>(
+    alloc: &A,
+    ptr: NonNull<u8>,
+    old_layout: Layout,
+    new_layout: Layout,
+) -> Result<NonNull<[u8]>, AllocError> {
+    unsafe {
+        check_canary(ptr, old_layout);
+        let old_outer = padded(old_layout)?;
+        let block = alloc.grow(ptr, old_outer, padded(new_layout)?)?;
+        poison(block.cast::<u8>().as_ptr().add(old_layout.size()), new_layout.size() - old_layout.size());
+        Ok(finish(block, new_layout))
+    }
+}
+
+/// Checks the canary and shrinks a block from [`allocate`].
+///
+/// Shrinking to zero bytes frees the block and returns a dangling pointer.
+///
+/// # Safety
+///
+/// As for [`Allocator::shrink`].
+pub unsafe fn shrink<A: Allocator>(
+    alloc: &A,
+    ptr: NonNull<u8>,
+    old_layout: Layout,
+    new_layout: Layout,
+) -> Result<NonNull<[u8]>, AllocError> {
+    unsafe {
+        if ENABLED && new_layout.size() == 0 {
+            // A zero-sized block is never freed, so its canary would leak.
+            deallocate(alloc, ptr, old_layout);
+            return Ok(NonNull::slice_from_raw_parts(alloc::dangling(new_layout), 0));
+        }
+        check_canary(ptr, old_layout);
+        let block = alloc.shrink(ptr, padded(old_layout)?, padded(new_layout)?)?;
+        Ok(finish(block, new_layout))
+    }
+}
+
+/// Takes a block allocated by `alloc` without a canary, like the buffer of a
+/// `Box<[T]>`, and adds one.
+///
+/// # Safety
+///
+/// `ptr` must be a block currently allocated by `alloc` with `layout`.
+pub unsafe fn adopt<A: Allocator>(
+    alloc: &A,
+    ptr: NonNull<u8>,
+    layout: Layout,
+) -> Result<NonNull<u8>, AllocError> {
+    if !ENABLED || layout.size() == 0 {
+        return Ok(ptr);
+    }
+    unsafe {
+        let block = alloc.grow(ptr, layout, padded(layout)?)?;
+        Ok(finish(block, layout).cast())
+    }
+}
+
+/// Checks the canary of a block from [`allocate`] and removes it, so the
+/// block can be freed by code that does not know about canaries.
+///
+/// # Safety
+///
+/// As for [`Allocator::shrink`], with the same layout before and after.
+pub unsafe fn release<A: Allocator>(
+    alloc: &A,
+    ptr: NonNull<u8>,
+    layout: Layout,
+) -> Result<NonNull<u8>, AllocError> {
+    if !ENABLED || layout.size() == 0 {
+        return Ok(ptr);
+    }
+    unsafe {
+        check_canary(ptr, layout);
+        Ok(alloc.shrink(ptr, padded(layout)?, layout)?.cast())
+    }
+}
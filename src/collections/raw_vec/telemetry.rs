@@ -0,0 +1,344 @@
+//! Opt-in reporting of the allocations made by `RawVec`.
+//!
+//! Nothing is reported until a hook is installed with [`set_hook`]. From then
+//! on every `RawVec` calls the hook with an [`Event`] whenever it allocates,
+//! grows, shrinks or frees its buffer. Without a hook the only cost is one
+//! relaxed atomic load per reallocation.
+//!
+//! [`StatsTable`] aggregates events per element type, which is the quickest
+//! way to find the vectors that should have been created with
+//! `with_capacity`:
+//!
+//! ```
+//! use rusty_collections::vec::telemetry::{self, Event, StatsTable};
+//! use rusty_collections::vec::Vec;
+//!
+//! static STATS: StatsTable<64> = StatsTable::new();
+//!
+//! fn record(event: &Event) {
+//!     STATS.record(event);
+//! }
+//!
+//! telemetry::set_hook(record);
+//!
+//! let mut v = Vec::new();
+//! for i in 0..100u64 {
+//!     v.push(i);
+//! }
+//! drop(v);
+//!
+//! let stats = STATS.get(core::any::type_name::<u64>()).unwrap();
+//! assert!(stats.grows > 0);
+//! assert_eq!(stats.allocations, stats.frees);
+//! ```
+
+use core::fmt;
+use core::hint;
+use core::mem;
+use core::ptr;
+use core::slice;
+use core::str;
+use core::sync::atomic::{AtomicPtr, AtomicU8, AtomicUsize, Ordering};
+
+use crate::vec::Vec;
+
+/// What happened to a buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// A buffer was allocated where there was none before.
+    Alloc,
+    /// An existing buffer was enlarged.
+    Grow,
+    /// An existing buffer was made smaller, but not freed.
+    Shrink,
+    /// The buffer was released.
+    Free,
+}
+
+/// The `RawVec` operation that caused an event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Path {
+    /// `with_capacity` and its variants.
+    WithCapacity,
+    /// `reserve`, through `grow_amortized`. This is what `push` ends up in.
+    GrowAmortized,
+    /// `reserve_exact`, through `grow_exact`.
+    GrowExact,
+    /// `shrink_to_fit`.
+    Shrink,
+    /// Dropping the `RawVec`.
+    Drop,
+}
+
+/// A single allocation event reported to the hook.
+///
+/// Capacities are counted in elements, byte counts in bytes.
+#[derive(Clone, Copy, Debug)]
+pub struct Event {
+    /// What happened to the buffer.
+    pub kind: EventKind,
+    /// Which operation made it happen.
+    pub path: Path,
+    /// The element type of the vector, as returned by `core::any::type_name`.
+    pub type_name: &'static str,
+    /// The size of one element.
+    pub elem_size: usize,
+    /// The capacity before the event, `0` for [`EventKind::Alloc`].
+    pub old_capacity: usize,
+    /// The capacity after the event, `0` for [`EventKind::Free`].
+    pub new_capacity: usize,
+    /// How many bytes the allocator had to copy to a new block.
+    ///
+    /// This is `0` when the reallocation happened in place. `RawVec` does
+    /// not know how many elements are initialized, so this counts the whole
+    /// old block (or the new one, when shrinking), like `realloc` does.
+    pub bytes_moved: usize,
+    /// Whether a grow or shrink kept the buffer at the same address.
+    pub in_place: bool,
+}
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Registers a hook to be called for every `RawVec` allocation event,
+/// replacing any previously registered hook.
+///
+/// The hook runs in the middle of the operation that triggered it, so it
+/// must not panic, and it must not create or grow vectors itself unless it
+/// is prepared to be called recursively.
+pub fn set_hook(hook: fn(&Event)) {
+    HOOK.store(hook as *mut (), Ordering::SeqCst);
+}
+
+/// Unregisters the current hook, returning it.
+///
+/// Returns `None` if no hook was registered.
+pub fn take_hook() -> Option<fn(&Event)> {
+    let hook = HOOK.swap(ptr::null_mut(), Ordering::SeqCst);
+    if hook.is_null() { None } else { Some(unsafe { mem::transmute::<*mut (), fn(&Event)>(hook) }) }
+}
+
+/// Returns `true` if a hook is registered.
+///
+/// `RawVec` checks this before assembling an [`Event`], so the non-generic
+/// reporting code stays out of its hot paths.
+#[inline]
+pub(super) fn enabled() -> bool {
+    !HOOK.load(Ordering::Relaxed).is_null()
+}
+
+#[cold]
+#[inline(never)]
+pub(super) fn report(event: &Event) {
+    let hook = HOOK.load(Ordering::SeqCst);
+    if !hook.is_null() {
+        let hook: fn(&Event) = unsafe { mem::transmute(hook) };
+        hook(event);
+    }
+}
+
+/// Aggregated events for one element type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TypeStats {
+    /// The element type, as returned by `core::any::type_name`.
+    pub type_name: &'static str,
+    /// The size of one element.
+    pub elem_size: usize,
+    /// Number of buffers allocated.
+    pub allocations: usize,
+    /// Number of times a buffer was enlarged.
+    pub grows: usize,
+    /// How many of those `grows` kept the buffer in place.
+    pub grows_in_place: usize,
+    /// How many of those `grows` came from `reserve_exact`.
+    pub exact_grows: usize,
+    /// Number of times a buffer was made smaller without being freed.
+    pub shrinks: usize,
+    /// Number of buffers freed.
+    pub frees: usize,
+    /// Total bytes copied by reallocations that could not happen in place.
+    pub bytes_moved: usize,
+    /// The largest capacity seen for this type.
+    pub peak_capacity: usize,
+}
+
+const EMPTY: u8 = 0;
+const CLAIMING: u8 = 1;
+const READY: u8 = 2;
+
+struct Slot {
+    state: AtomicU8,
+    name_ptr: AtomicPtr<u8>,
+    name_len: AtomicUsize,
+    elem_size: AtomicUsize,
+    allocations: AtomicUsize,
+    grows: AtomicUsize,
+    grows_in_place: AtomicUsize,
+    exact_grows: AtomicUsize,
+    shrinks: AtomicUsize,
+    frees: AtomicUsize,
+    bytes_moved: AtomicUsize,
+    peak_capacity: AtomicUsize,
+}
+
+impl Slot {
+    // `StatsTable::new` is `const`, so its slots are built from a constant.
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY: Slot = Slot {
+        state: AtomicU8::new(EMPTY),
+        name_ptr: AtomicPtr::new(ptr::null_mut()),
+        name_len: AtomicUsize::new(0),
+        elem_size: AtomicUsize::new(0),
+        allocations: AtomicUsize::new(0),
+        grows: AtomicUsize::new(0),
+        grows_in_place: AtomicUsize::new(0),
+        exact_grows: AtomicUsize::new(0),
+        shrinks: AtomicUsize::new(0),
+        frees: AtomicUsize::new(0),
+        bytes_moved: AtomicUsize::new(0),
+        peak_capacity: AtomicUsize::new(0),
+    };
+
+    // Only valid once `state` is `READY`.
+    fn type_name(&self) -> &'static str {
+        let ptr = self.name_ptr.load(Ordering::Relaxed);
+        let len = self.name_len.load(Ordering::Relaxed);
+        unsafe { str::from_utf8_unchecked(slice::from_raw_parts(ptr, len)) }
+    }
+
+    fn snapshot(&self) -> TypeStats {
+        TypeStats {
+            type_name: self.type_name(),
+            elem_size: self.elem_size.load(Ordering::Relaxed),
+            allocations: self.allocations.load(Ordering::Relaxed),
+            grows: self.grows.load(Ordering::Relaxed),
+            grows_in_place: self.grows_in_place.load(Ordering::Relaxed),
+            exact_grows: self.exact_grows.load(Ordering::Relaxed),
+            shrinks: self.shrinks.load(Ordering::Relaxed),
+            frees: self.frees.load(Ordering::Relaxed),
+            bytes_moved: self.bytes_moved.load(Ordering::Relaxed),
+            peak_capacity: self.peak_capacity.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A fixed-size table of [`TypeStats`], one entry per element type.
+///
+/// The table lives in a `static` and is filled from a hook, see the
+/// [module documentation](self). It never allocates, so recording an event
+/// cannot recurse into `RawVec`. Element types beyond the first `SLOTS` are
+/// only counted by [`untracked`].
+///
+/// Counters are updated independently of each other, so a snapshot taken
+/// while other threads are recording may be slightly inconsistent.
+///
+/// [`untracked`]: StatsTable::untracked
+pub struct StatsTable<const SLOTS: usize> {
+    slots: [Slot; SLOTS],
+    untracked: AtomicUsize,
+}
+
+impl<const SLOTS: usize> StatsTable<SLOTS> {
+    /// Creates an empty table.
+    pub const fn new() -> Self {
+        StatsTable { slots: [Slot::EMPTY; SLOTS], untracked: AtomicUsize::new(0) }
+    }
+
+    /// Adds an event to the entry for its element type.
+    pub fn record(&self, event: &Event) {
+        let slot = match self.slot(event.type_name, event.elem_size) {
+            Some(slot) => slot,
+            None => {
+                self.untracked.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+        };
+
+        match event.kind {
+            EventKind::Alloc => {
+                slot.allocations.fetch_add(1, Ordering::Relaxed);
+            }
+            EventKind::Grow => {
+                slot.grows.fetch_add(1, Ordering::Relaxed);
+                if event.in_place {
+                    slot.grows_in_place.fetch_add(1, Ordering::Relaxed);
+                }
+                if event.path == Path::GrowExact {
+                    slot.exact_grows.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            EventKind::Shrink => {
+                slot.shrinks.fetch_add(1, Ordering::Relaxed);
+            }
+            EventKind::Free => {
+                slot.frees.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        slot.bytes_moved.fetch_add(event.bytes_moved, Ordering::Relaxed);
+        slot.peak_capacity.fetch_max(event.new_capacity, Ordering::Relaxed);
+    }
+
+    /// Returns the entry for `type_name`, if any events were recorded for it.
+    pub fn get(&self, type_name: &str) -> Option<TypeStats> {
+        self.ready_slots().find(|slot| slot.type_name() == type_name).map(Slot::snapshot)
+    }
+
+    /// Returns a copy of every entry, in the order the types were first seen.
+    pub fn snapshot(&self) -> Vec<TypeStats> {
+        self.ready_slots().map(Slot::snapshot).collect()
+    }
+
+    /// Returns the number of events that were dropped because the table was
+    /// full.
+    pub fn untracked(&self) -> usize {
+        self.untracked.load(Ordering::Relaxed)
+    }
+
+    fn ready_slots(&self) -> impl Iterator<Item = &Slot> {
+        self.slots.iter().filter(|slot| slot.state.load(Ordering::Acquire) == READY)
+    }
+
+    // Finds the slot for `type_name`, claiming an empty one if this is the
+    // first event for the type.
+    fn slot(&self, type_name: &'static str, elem_size: usize) -> Option<&Slot> {
+        for slot in &self.slots {
+            loop {
+                match slot.state.load(Ordering::Acquire) {
+                    READY => {
+                        if slot.type_name() == type_name {
+                            return Some(slot);
+                        }
+                        break;
+                    }
+                    CLAIMING => hint::spin_loop(),
+                    _ => {
+                        if slot
+                            .state
+                            .compare_exchange(EMPTY, CLAIMING, Ordering::Acquire, Ordering::Relaxed)
+                            .is_ok()
+                        {
+                            slot.name_ptr.store(type_name.as_ptr() as *mut u8, Ordering::Relaxed);
+                            slot.name_len.store(type_name.len(), Ordering::Relaxed);
+                            slot.elem_size.store(elem_size, Ordering::Relaxed);
+                            slot.state.store(READY, Ordering::Release);
+                            return Some(slot);
+                        }
+                        // Somebody else claimed it first, look at it again.
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<const SLOTS: usize> Default for StatsTable<SLOTS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SLOTS: usize> fmt::Debug for StatsTable<SLOTS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.ready_slots().map(Slot::snapshot)).finish()
+    }
+}
@@ -0,0 +1,267 @@
+use super::*;
+
+use std::cell::Cell;
+
+use crate::alloc::AllocError;
+
+// Bytes `debug-memory` adds after every block for its canary.
+const PAD: usize = if debug_memory::ENABLED { debug_memory::CANARY_LEN } else { 0 };
+
+#[test]
+fn allocator_param() {
+    // Writing a test of integration between third-party
+    // allocators and `RawVec` is a little tricky because the `RawVec`
+    // API does not expose fallible allocation methods, so we
+    // cannot check what happens when allocator is exhausted
+    // (beyond detecting a panic).
+    //
+    // Instead, this just checks that the `RawVec` methods do at
+    // least go through the Allocator API when it reserves
+    // storage.
+
+    // A dumb allocator that consumes a fixed amount of fuel
+    // before allocation attempts start failing.
+    struct BoundedAlloc {
+        fuel: Cell<usize>,
+    }
+    unsafe impl Allocator for BoundedAlloc {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            let size = layout.size();
+            if size > self.fuel.get() {
+                return Err(AllocError);
+            }
+            match Global.allocate(layout) {
+                ok @ Ok(_) => {
+                    self.fuel.set(self.fuel.get() - size);
+                    ok
+                }
+                err @ Err(_) => err,
+            }
+        }
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            unsafe { Global.deallocate(ptr, layout) }
+        }
+    }
+
+    let a = BoundedAlloc { fuel: Cell::new(500) };
+    let mut v: RawVec<u8, _> = RawVec::with_capacity_in(50, a);
+    assert_eq!(v.alloc.fuel.get(), 450 - PAD);
+    v.reserve(50, 150); // (causes a realloc, thus using 50 + 150 = 200 units of fuel)
+    assert_eq!(v.alloc.fuel.get(), 250 - 2 * PAD);
+}
+
+#[test]
+fn reserve_does_not_overallocate() {
+    {
+        let mut v: RawVec<u32> = RawVec::new();
+        // First, `reserve` allocates like `reserve_exact`.
+        v.reserve(0, 9);
+        assert_eq!(9, v.capacity());
+    }
+
+    {
+        let mut v: RawVec<u32> = RawVec::new();
+        v.reserve(0, 7);
+        assert_eq!(7, v.capacity());
+        // 97 is more than double of 7, so `reserve` should work
+        // like `reserve_exact`.
+        v.reserve(7, 90);
+        assert_eq!(97, v.capacity());
+    }
+
+    {
+        let mut v: RawVec<u32> = RawVec::new();
+        v.reserve(0, 12);
+        assert_eq!(12, v.capacity());
+        v.reserve(12, 3);
+        // 3 is less than half of 12, so `reserve` must grow
+        // exponentially. At the time of writing this test grow
+        // factor is 2, so new capacity is 24, however, grow factor
+        // of 1.5 is OK too. Hence `>= 18` in assert.
+        assert!(v.capacity() >= 12 + 12 / 2);
+    }
+}
+
+#[test]
+fn zero_sized_types_never_allocate() {
+    let mut v: RawVec<()> = RawVec::with_capacity(10);
+    assert_eq!(v.capacity(), usize::MAX);
+    v.reserve(0, usize::MAX);
+    v.reserve_exact(0, usize::MAX);
+    assert!(v.try_reserve(1, usize::MAX).is_err());
+    assert_eq!(v.capacity(), usize::MAX);
+}
+
+// The capacities `reserve(len, 1)` goes through when called on a full
+// buffer, starting from empty.
+fn growth_steps<G: GrowthPolicy>(growth: G, steps: usize) -> Vec<usize> {
+    let mut v: RawVec<u64, Global, G> = RawVec::new().with_growth(growth);
+    let mut caps = Vec::new();
+    for _ in 0..steps {
+        let len = v.capacity();
+        v.reserve(len, 1);
+        caps.push(v.capacity());
+    }
+    caps
+}
+
+#[test]
+fn growth_policies() {
+    assert_eq!(growth_steps(Doubling, 5), [4, 8, 16, 32, 64]);
+    assert_eq!(growth_steps(OneAndHalf, 5), [4, 6, 9, 13, 19]);
+    assert_eq!(growth_steps(Chunked::<10>, 3), [10, 20, 30]);
+    assert_eq!(growth_steps(Chunked::<0>, 3), [1, 2, 3]);
+    // 4096 bytes hold 512 `u64`s.
+    assert_eq!(growth_steps(PageRounded::<4096>, 2), [512, 1024]);
+    assert_eq!(growth_steps(GrowWith(|cap, _, _| cap + 3), 3), [3, 6, 9]);
+}
+
+#[test]
+fn reserve_exact_ignores_amortized_policies() {
+    let mut v: RawVec<u64, Global, Chunked<10>> = RawVec::new().with_growth(Chunked::<10>);
+    v.reserve_exact(0, 3);
+    assert_eq!(v.capacity(), 3);
+
+    let mut v = RawVec::<u64>::new().with_growth(PageRounded::<4096>);
+    v.reserve_exact(0, 3);
+    assert_eq!(v.capacity(), 512);
+}
+
+#[test]
+fn box_round_trip() {
+    let b: Box<[u32]> = Box::new([1, 2, 3]);
+    let v = RawVec::from_box(b);
+    assert_eq!(v.capacity(), 3);
+    let b = unsafe { v.into_box(3).assume_init() };
+    assert_eq!(*b, [1, 2, 3]);
+
+    let empty: Box<[u32]> = Box::new([]);
+    let v = RawVec::from_box(empty);
+    assert_eq!(v.capacity(), 0);
+    let b = unsafe { v.into_box(0).assume_init() };
+    assert!(b.is_empty());
+}
+
+#[test]
+fn only_plain_numbers_are_checked_for_poison() {
+    assert!(debug_memory::has_no_padding::<u8>());
+    assert!(debug_memory::has_no_padding::<f64>());
+    assert!(debug_memory::has_no_padding::<[[i32; 2]; 3]>());
+    assert!(!debug_memory::has_no_padding::<Option<u64>>());
+    assert!(!debug_memory::has_no_padding::<(u8, u32)>());
+    assert!(!debug_memory::has_no_padding::<core::mem::MaybeUninit<u64>>());
+    assert!(!debug_memory::has_no_padding::<[core::mem::MaybeUninit<u8>; 8]>());
+}
+
+#[test]
+#[cfg(feature = "debug-memory")]
+fn canary_catches_a_write_past_the_end() {
+    let buf: RawVec<u8> = RawVec::with_capacity(10);
+    let end = unsafe { buf.ptr().add(10) };
+    assert!(unsafe { debug_memory::has_canary(end) });
+
+    // The canary lives in the same block, so the stray write stays inside
+    // memory that was allocated.
+    unsafe { *end.add(3) = 0 };
+    let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| drop(buf))).unwrap_err();
+    let msg = err.downcast_ref::<String>().unwrap();
+    assert!(msg.starts_with("debug-memory: write past the end of the 10-byte buffer"), "{}", msg);
+}
+
+#[test]
+#[cfg(feature = "debug-memory")]
+fn canary_is_checked_when_growing() {
+    let mut buf: RawVec<u32> = RawVec::with_capacity(4);
+    unsafe { *(buf.ptr().add(4) as *mut u8) = 0 };
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| buf.reserve(4, 10)));
+    assert!(result.is_err());
+    // The block was neither grown nor freed: restore the canary so `buf`
+    // can be dropped.
+    unsafe { debug_memory::write_canary(buf.ptr().add(4) as *mut u8) };
+}
+
+// The element type of the vectors watched by the telemetry test. No other
+// test uses it, so events from tests running in parallel do not mix in.
+struct Watched(#[allow(dead_code)] u64);
+
+static WATCHED: telemetry::StatsTable<64> = telemetry::StatsTable::new();
+
+fn record_watched(event: &telemetry::Event) {
+    WATCHED.record(event);
+}
+
+// The hook is global, so this is the only test that installs one.
+#[test]
+fn telemetry_hook_sees_every_event() {
+    assert!(telemetry::take_hook().is_none());
+    telemetry::set_hook(record_watched);
+
+    let mut v: RawVec<Watched> = RawVec::new();
+    v.reserve(0, 10);
+    v.reserve(10, 1);
+    v.reserve_exact(20, 30);
+    v.shrink_to_fit(5);
+    let peak = 50;
+    drop(v);
+
+    assert!(telemetry::take_hook().is_some());
+    assert!(telemetry::take_hook().is_none());
+
+    // Nothing is recorded once the hook is gone.
+    drop(RawVec::<Watched>::with_capacity(1));
+
+    let stats = WATCHED.get(core::any::type_name::<Watched>()).unwrap();
+    assert_eq!(stats.elem_size, 8);
+    assert_eq!(stats.allocations, 1);
+    assert_eq!(stats.grows, 2);
+    assert_eq!(stats.exact_grows, 1);
+    assert_eq!(stats.shrinks, 1);
+    assert_eq!(stats.frees, 1);
+    assert_eq!(stats.peak_capacity, peak);
+}
+
+fn event(kind: telemetry::EventKind, type_name: &'static str) -> telemetry::Event {
+    telemetry::Event {
+        kind,
+        path: telemetry::Path::GrowAmortized,
+        type_name,
+        elem_size: 4,
+        old_capacity: 4,
+        new_capacity: 8,
+        bytes_moved: 16,
+        in_place: false,
+    }
+}
+
+#[test]
+fn stats_table_counts_per_type() {
+    use telemetry::EventKind::*;
+
+    let table: telemetry::StatsTable<2> = telemetry::StatsTable::new();
+    for kind in [Alloc, Grow, Grow, Shrink, Free] {
+        table.record(&event(kind, "a"));
+    }
+    let mut in_place = event(Grow, "b");
+    in_place.in_place = true;
+    in_place.bytes_moved = 0;
+    in_place.new_capacity = 100;
+    table.record(&in_place);
+
+    // The table is full, so a third type is only counted.
+    table.record(&event(Alloc, "c"));
+    table.record(&event(Free, "c"));
+    assert_eq!(table.untracked(), 2);
+    assert_eq!(table.get("c"), None);
+
+    let a = table.get("a").unwrap();
+    assert_eq!((a.allocations, a.grows, a.shrinks, a.frees), (1, 2, 1, 1));
+    assert_eq!(a.grows_in_place, 0);
+    assert_eq!(a.bytes_moved, 5 * 16);
+    assert_eq!(a.peak_capacity, 8);
+
+    let b = table.get("b").unwrap();
+    assert_eq!((b.grows, b.grows_in_place, b.bytes_moved, b.peak_capacity), (1, 1, 0, 100));
+
+    let names: std::vec::Vec<_> = table.snapshot().iter().map(|s| s.type_name).collect();
+    assert_eq!(names, ["a", "b"]);
+}
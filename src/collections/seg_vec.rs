@@ -0,0 +1,752 @@
+//! A growable array type that never moves its elements, written `SegVec<T>`.
+//!
+//! A [`Vec`] grows by reallocating its buffer, which moves every element and
+//! invalidates all pointers into it. A `SegVec` instead keeps its elements in
+//! a list of separately allocated *segments*. When the last segment is full a
+//! new one is allocated next to it, and the elements already stored stay
+//! where they are for as long as they are in the vector.
+//!
+//! Segments either double in size, so that a vector of `n` elements uses
+//! `O(log n)` segments and wastes at most half of its capacity, or all have
+//! the same size.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_collections::seg_vec::SegVec;
+//!
+//! let mut v = SegVec::new();
+//! v.push(1);
+//! let first: *const i32 = &v[0];
+//!
+//! v.extend(2..1000);
+//! assert_eq!(first, &v[0] as *const i32);
+//! assert_eq!(v.len(), 999);
+//! ```
+//!
+//! [`push_shared`] appends through a shared reference, so references handed
+//! out earlier stay usable:
+//!
+//! ```
+//! use rusty_collections::seg_vec::SegVec;
+//!
+//! let names = SegVec::new();
+//! let a = names.push_shared(String::from("a"));
+//! let b = names.push_shared(String::from("b"));
+//! assert_eq!((a.as_str(), b.as_str()), ("a", "b"));
+//! ```
+//!
+//! [`push_shared`]: SegVec::push_shared
+
+use core::cell::{Cell, UnsafeCell};
+use core::cmp;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Index, IndexMut, RangeFrom};
+use core::ptr;
+
+use crate::raw_vec::RawVec;
+use crate::vec::Vec;
+
+#[cfg(test)]
+mod tests;
+
+/// The size of the first segment of a `SegVec` created with [`SegVec::new`].
+const DEFAULT_FIRST_SEGMENT: usize = 8;
+
+/// A contiguous-per-segment growable array whose elements never move.
+///
+/// Indexing is `O(1)`: the segment holding an index is found with a shift
+/// and a leading-zeros count, or a division for fixed-size segments.
+///
+/// See the [module documentation](self) for an overview.
+pub struct SegVec<T> {
+    // `UnsafeCell` and `Cell` let `push_shared` grow the vector through a
+    // shared reference. Nothing ever hands out a reference into `segments`
+    // itself, only into the buffers it points to, which never move.
+    segments: UnsafeCell<Vec<RawVec<T>>>,
+    len: Cell<usize>,
+    first: usize,
+    geometric: bool,
+}
+
+impl<T> SegVec<T> {
+    /// Constructs a new, empty `SegVec<T>` whose segments double in size,
+    /// starting at 8 elements.
+    ///
+    /// The vector will not allocate until elements are pushed onto it.
+    #[inline]
+    pub fn new() -> SegVec<T> {
+        SegVec::with_first_segment(DEFAULT_FIRST_SEGMENT)
+    }
+
+    /// Constructs a new, empty `SegVec<T>` whose segments double in size,
+    /// starting at `first` elements rounded up to a power of two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `first` is zero.
+    pub fn with_first_segment(first: usize) -> SegVec<T> {
+        assert!(first != 0, "segments must hold at least one element");
+        SegVec::with_layout(first.next_power_of_two(), true)
+    }
+
+    /// Constructs a new, empty `SegVec<T>` whose segments all hold exactly
+    /// `len` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::seg_vec::SegVec;
+    ///
+    /// let mut v = SegVec::with_fixed_segments(100);
+    /// v.push(1);
+    /// assert_eq!(v.capacity(), 100);
+    /// v.extend(0..100);
+    /// assert_eq!(v.capacity(), 200);
+    /// ```
+    pub fn with_fixed_segments(len: usize) -> SegVec<T> {
+        assert!(len != 0, "segments must hold at least one element");
+        SegVec::with_layout(len, false)
+    }
+
+    fn with_layout(first: usize, geometric: bool) -> SegVec<T> {
+        // Zero-sized elements never allocate, so a single segment of
+        // unlimited capacity holds all of them.
+        let (first, geometric) =
+            if mem::size_of::<T>() == 0 { (usize::MAX, false) } else { (first, geometric) };
+        SegVec { segments: UnsafeCell::new(Vec::new()), len: Cell::new(0), first, geometric }
+    }
+
+    /// Returns the number of elements in the vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements the vector can hold without allocating
+    /// another segment.
+    pub fn capacity(&self) -> usize {
+        let segments = self.segments().len();
+        (0..segments).fold(0, |cap, seg| cap.saturating_add(self.segment_capacity(seg)))
+    }
+
+    #[inline]
+    fn segments(&self) -> &Vec<RawVec<T>> {
+        unsafe { &*self.segments.get() }
+    }
+
+    // Maps an index to a segment and an offset into it.
+    #[inline]
+    fn locate(&self, index: usize) -> (usize, usize) {
+        if self.geometric {
+            // Segment `k` holds `first << k` elements and starts at index
+            // `first * (2^k - 1)`.
+            let j = index / self.first + 1;
+            let seg = (usize::BITS - 1 - j.leading_zeros()) as usize;
+            (seg, index - self.first * ((1 << seg) - 1))
+        } else {
+            (index / self.first, index % self.first)
+        }
+    }
+
+    #[inline]
+    fn segment_capacity(&self, seg: usize) -> usize {
+        if self.geometric { self.first << seg } else { self.first }
+    }
+
+    // Returns a pointer to the slot for `index`, which must lie in an
+    // allocated segment.
+    #[inline]
+    fn slot(&self, index: usize) -> *mut T {
+        let (seg, offset) = self.locate(index);
+        unsafe { self.segments().get_unchecked(seg).ptr().add(offset) }
+    }
+
+    /// Returns a reference to an element, or `None` if `index` is out of
+    /// bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len() { Some(unsafe { &*self.slot(index) }) } else { None }
+    }
+
+    /// Returns a mutable reference to an element, or `None` if `index` is out
+    /// of bounds.
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len() { Some(unsafe { &mut *self.slot(index) }) } else { None }
+    }
+
+    /// Returns a reference to the first element, or `None` if it is empty.
+    #[inline]
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns a reference to the last element, or `None` if it is empty.
+    #[inline]
+    pub fn last(&self) -> Option<&T> {
+        self.len().checked_sub(1).and_then(|i| self.get(i))
+    }
+
+    // Makes sure the slot for the next element is allocated and returns it.
+    fn next_slot(&self) -> *mut T {
+        let len = self.len();
+        if len == usize::MAX {
+            // Only reachable with zero-sized elements.
+            panic!("capacity overflow");
+        }
+        let (seg, offset) = self.locate(len);
+        // No reference into `segments` is live here: `self.segments()` is
+        // only ever used for short reads.
+        let segments = unsafe { &mut *self.segments.get() };
+        if seg == segments.len() {
+            segments.push(RawVec::with_capacity(self.segment_capacity(seg)));
+        }
+        unsafe { segments.get_unchecked(seg).ptr().add(offset) }
+    }
+
+    /// Appends an element to the back of the vector.
+    ///
+    /// Allocates a new segment if the last one is full. Elements already in
+    /// the vector do not move.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new segment exceeds `isize::MAX` bytes.
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        self.push_shared(value);
+    }
+
+    /// Appends an element through a shared reference and returns a reference
+    /// to it.
+    ///
+    /// Since elements never move, references returned by earlier calls stay
+    /// valid. This is what interners and arenas need: hand out `&T` and keep
+    /// adding to the vector behind them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new segment exceeds `isize::MAX` bytes.
+    pub fn push_shared(&self, value: T) -> &T {
+        let slot = self.next_slot();
+        unsafe {
+            ptr::write(slot, value);
+            self.len.set(self.len() + 1);
+            &*slot
+        }
+    }
+
+    /// Removes the last element from the vector and returns it, or [`None`]
+    /// if it is empty.
+    ///
+    /// The segment stays allocated.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        let len = self.len();
+        if len == 0 {
+            None
+        } else {
+            self.len.set(len - 1);
+            unsafe { Some(ptr::read(self.slot(len - 1))) }
+        }
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping
+    /// the rest.
+    ///
+    /// If `len` is greater than the vector's current length, this has no
+    /// effect. Segments stay allocated; see [`shrink_to_fit`].
+    ///
+    /// [`shrink_to_fit`]: SegVec::shrink_to_fit
+    pub fn truncate(&mut self, len: usize) {
+        let old_len = self.len();
+        if len >= old_len {
+            return;
+        }
+        // Shrink first, so a panicking destructor leaks instead of double
+        // dropping.
+        self.len.set(len);
+        unsafe { self.drop_range(len, old_len) }
+    }
+
+    // Drops the elements in `start..end`, one segment at a time.
+    unsafe fn drop_range(&mut self, start: usize, end: usize) {
+        let mut index = start;
+        while index < end {
+            let (seg, offset) = self.locate(index);
+            let count = cmp::min(end - index, self.segment_capacity(seg) - offset);
+            unsafe {
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.slot(index), count));
+            }
+            index += count;
+        }
+    }
+
+    /// Clears the vector, removing all values.
+    ///
+    /// Segments stay allocated.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Frees the segments that hold no elements.
+    pub fn shrink_to_fit(&mut self) {
+        let used = if self.is_empty() { 0 } else { self.locate(self.len() - 1).0 + 1 };
+        self.segments.get_mut().truncate(used);
+    }
+
+    /// Creates a draining iterator that removes the elements from `range.start`
+    /// to the end of the vector and yields them in order.
+    ///
+    /// Like every other operation on a `SegVec`, this never moves the
+    /// elements that stay. If the iterator is dropped early, the remaining
+    /// elements are dropped with it; if it is leaked, they are leaked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::seg_vec::SegVec;
+    ///
+    /// let mut v: SegVec<_> = (0..20).collect();
+    /// let tail: Vec<_> = v.drain(15..).collect();
+    /// assert_eq!(tail, [15, 16, 17, 18, 19]);
+    /// assert_eq!(v.len(), 15);
+    /// ```
+    pub fn drain(&mut self, range: RangeFrom<usize>) -> Drain<'_, T> {
+        let len = self.len();
+        let start = range.start;
+        if start > len {
+            panic!("drain start (is {}) should be <= len (is {})", start, len);
+        }
+        // The drained elements belong to the iterator from here on.
+        self.len.set(start);
+        Drain { vec: self, current: start, end: len }
+    }
+
+    /// Returns an iterator over the elements.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { vec: self, current: 0, end: self.len() }
+    }
+
+    /// Returns an iterator that allows modifying each element.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let end = self.len();
+        IterMut { vec: self, current: 0, end, marker: PhantomData }
+    }
+
+    /// Returns an iterator over the elements, one slice per segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::seg_vec::SegVec;
+    ///
+    /// let v: SegVec<_> = (0..10).collect();
+    /// let lens: Vec<_> = v.segment_slices().map(|s| s.len()).collect();
+    /// assert_eq!(lens, [8, 2]);
+    /// ```
+    pub fn segment_slices(&self) -> impl Iterator<Item = &[T]> + '_ {
+        let len = self.len();
+        let mut index = 0;
+        core::iter::from_fn(move || {
+            if index == len {
+                return None;
+            }
+            let (seg, offset) = self.locate(index);
+            let count = cmp::min(len - index, self.segment_capacity(seg) - offset);
+            let slice = unsafe { core::slice::from_raw_parts(self.slot(index), count) };
+            index += count;
+            Some(slice)
+        })
+    }
+}
+
+impl<T: Clone> Clone for SegVec<T> {
+    fn clone(&self) -> Self {
+        let mut vec = SegVec::with_layout(self.first, self.geometric);
+        vec.extend(self.iter().cloned());
+        vec
+    }
+}
+
+impl<T> Default for SegVec<T> {
+    /// Creates an empty `SegVec<T>`.
+    fn default() -> SegVec<T> {
+        SegVec::new()
+    }
+}
+
+impl<T> Drop for SegVec<T> {
+    fn drop(&mut self) {
+        let len = self.len();
+        unsafe { self.drop_range(0, len) }
+        // the `RawVec`s in `segments` handle deallocation
+    }
+}
+
+impl<T> Index<usize> for SegVec<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        match self.get(index) {
+            Some(elem) => elem,
+            None => panic!("index out of bounds: the len is {} but the index is {}", self.len(), index),
+        }
+    }
+}
+
+impl<T> IndexMut<usize> for SegVec<T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        let len = self.len();
+        match self.get_mut(index) {
+            Some(elem) => elem,
+            None => panic!("index out of bounds: the len is {} but the index is {}", len, index),
+        }
+    }
+}
+
+impl<T> Extend<T> for SegVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a> Extend<&'a T> for SegVec<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+
+impl<T> FromIterator<T> for SegVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> SegVec<T> {
+        let mut vec = SegVec::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SegVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for SegVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for SegVec<T> {}
+
+impl<T: Hash> Hash for SegVec<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for elem in self.iter() {
+            elem.hash(state);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SegVec<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SegVec<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> IntoIterator for SegVec<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Creates a consuming iterator, that is, one that moves each value out of
+    /// the vector (from start to end). The vector cannot be used after calling
+    /// this.
+    fn into_iter(self) -> IntoIter<T> {
+        let end = self.len();
+        // The iterator owns the elements from here on; the vector only keeps
+        // the segments alive.
+        self.len.set(0);
+        IntoIter { vec: self, current: 0, end }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator over the elements of a `SegVec`.
+///
+/// This `struct` is created by [`SegVec::iter`].
+pub struct Iter<'a, T> {
+    vec: &'a SegVec<T>,
+    current: usize,
+    end: usize,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter { vec: self.vec, current: self.current, end: self.end }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        if self.current == self.end {
+            None
+        } else {
+            let elem = unsafe { &*self.vec.slot(self.current) };
+            self.current += 1;
+            Some(elem)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let exact = self.end - self.current;
+        (exact, Some(exact))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.current == self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(unsafe { &*self.vec.slot(self.end) })
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// A mutable iterator over the elements of a `SegVec`.
+///
+/// This `struct` is created by [`SegVec::iter_mut`].
+pub struct IterMut<'a, T> {
+    // Only used to locate slots; the elements are reached through the
+    // segment pointers, so handing out `&mut T` does not alias `vec`.
+    vec: &'a SegVec<T>,
+    current: usize,
+    end: usize,
+    marker: PhantomData<&'a mut T>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for IterMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IterMut").field(&self.end.saturating_sub(self.current)).finish()
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.current == self.end {
+            None
+        } else {
+            let elem = unsafe { &mut *self.vec.slot(self.current) };
+            self.current += 1;
+            Some(elem)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let exact = self.end - self.current;
+        (exact, Some(exact))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.current == self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(unsafe { &mut *self.vec.slot(self.end) })
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// An iterator that moves out of a `SegVec`.
+///
+/// This `struct` is created by the `into_iter` method on [`SegVec`]
+/// (provided by the [`IntoIterator`] trait).
+pub struct IntoIter<T> {
+    // Owns the segments, with its length set to zero. The live elements are
+    // the ones in `current..end`.
+    vec: SegVec<T>,
+    current: usize,
+    end: usize,
+}
+
+impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let iter = Iter { vec: &self.vec, current: self.current, end: self.end };
+        f.debug_tuple("IntoIter").field(&iter).finish()
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.current == self.end {
+            None
+        } else {
+            let elem = unsafe { ptr::read(self.vec.slot(self.current)) };
+            self.current += 1;
+            Some(elem)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let exact = self.end - self.current;
+        (exact, Some(exact))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.current == self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(unsafe { ptr::read(self.vec.slot(self.end)) })
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T> Drop for IntoIter<T> {
+    fn drop(&mut self) {
+        let (current, end) = (self.current, self.end);
+        self.current = end;
+        unsafe { self.vec.drop_range(current, end) }
+    }
+}
+
+/// A draining iterator over the tail of a `SegVec`.
+///
+/// This `struct` is created by [`SegVec::drain`].
+pub struct Drain<'a, T> {
+    vec: &'a mut SegVec<T>,
+    current: usize,
+    end: usize,
+}
+
+impl<T: fmt::Debug> fmt::Debug for Drain<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let iter = Iter { vec: &*self.vec, current: self.current, end: self.end };
+        f.debug_tuple("Drain").field(&iter).finish()
+    }
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.current == self.end {
+            None
+        } else {
+            let elem = unsafe { ptr::read(self.vec.slot(self.current)) };
+            self.current += 1;
+            Some(elem)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let exact = self.end - self.current;
+        (exact, Some(exact))
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.current == self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(unsafe { ptr::read(self.vec.slot(self.end)) })
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        // Nothing has to be moved back, the drained range is the tail.
+        let (current, end) = (self.current, self.end);
+        self.current = end;
+        unsafe { self.vec.drop_range(current, end) }
+    }
+}
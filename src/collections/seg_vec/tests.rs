@@ -0,0 +1,179 @@
+use super::*;
+
+use std::vec::Vec as StdVec;
+
+use crate::test_util::{drops, Counted, Rng};
+
+fn contents<T: Clone>(v: &SegVec<T>) -> StdVec<T> {
+    v.iter().cloned().collect()
+}
+
+// Applies one random operation to both vectors and checks that they agree
+// on what it returned.
+fn step(rng: &mut Rng, v: &mut SegVec<u32>, m: &mut StdVec<u32>) {
+    let x = rng.next() as u32;
+    match rng.below(10) {
+        0..=3 => {
+            v.push(x);
+            m.push(x);
+        }
+        4 => assert_eq!(v.pop(), m.pop()),
+        5 => {
+            let len = rng.below(m.len() + 1);
+            v.truncate(len);
+            m.truncate(len);
+        }
+        6 => {
+            let start = rng.below(m.len() + 1);
+            let drained: StdVec<u32> = v.drain(start..).collect();
+            assert_eq!(drained, m.drain(start..).collect::<StdVec<_>>());
+        }
+        7 => {
+            let extra = rng.below(40);
+            v.extend((0..extra).map(|i| i as u32));
+            m.extend((0..extra).map(|i| i as u32));
+        }
+        8 => {
+            v.shrink_to_fit();
+            if let Some(i) = m.len().checked_sub(1) {
+                v[i] = x;
+                m[i] = x;
+            }
+        }
+        _ if rng.below(4) == 0 => {
+            v.clear();
+            m.clear();
+        }
+        _ => {
+            if !m.is_empty() {
+                let i = rng.below(m.len());
+                assert_eq!(v.get(i), m.get(i));
+            }
+            assert_eq!(v.get(m.len()), None);
+        }
+    }
+    assert_eq!(v.len(), m.len());
+    assert_eq!(contents(v), *m);
+    assert_eq!(v.first(), m.first());
+    assert_eq!(v.last(), m.last());
+    assert!(v.capacity() >= v.len());
+}
+
+#[test]
+fn random_operations_match_std() {
+    for seed in 0..100 {
+        let mut rng = Rng::new(seed);
+        let layouts =
+            [SegVec::new(), SegVec::with_first_segment(3), SegVec::with_fixed_segments(5)];
+        for mut v in layouts {
+            let mut m = StdVec::new();
+            for _ in 0..200 {
+                step(&mut rng, &mut v, &mut m);
+            }
+        }
+    }
+}
+
+#[test]
+fn segments_grow_as_configured() {
+    let v: SegVec<u8> = (0..100).collect();
+    let lens: StdVec<_> = v.segment_slices().map(<[u8]>::len).collect();
+    assert_eq!(lens, [8, 16, 32, 44]);
+    assert_eq!(v.capacity(), 120);
+
+    // The first segment is rounded up to a power of two.
+    let mut v = SegVec::with_first_segment(3);
+    v.extend(0..10u8);
+    let lens: StdVec<_> = v.segment_slices().map(<[u8]>::len).collect();
+    assert_eq!(lens, [4, 6]);
+
+    let mut v = SegVec::with_fixed_segments(7);
+    v.extend(0..20u8);
+    let lens: StdVec<_> = v.segment_slices().map(<[u8]>::len).collect();
+    assert_eq!(lens, [7, 7, 6]);
+    assert_eq!(v.segment_slices().flatten().copied().collect::<StdVec<_>>(), contents(&v));
+}
+
+#[test]
+fn elements_never_move() {
+    let mut v = SegVec::new();
+    v.push(0u64);
+    let addrs: StdVec<*const u64> = (0..1000)
+        .map(|i| {
+            v.push(i);
+            v.last().unwrap() as *const u64
+        })
+        .collect();
+    for (i, &addr) in addrs.iter().enumerate() {
+        assert_eq!(&v[i + 1] as *const u64, addr);
+    }
+
+    // Dropping elements and freeing the empty segments leaves the rest
+    // where they were.
+    v.truncate(10);
+    v.shrink_to_fit();
+    assert_eq!(v.capacity(), 8 + 16);
+    assert_eq!(&v[9] as *const u64, addrs[8]);
+}
+
+#[test]
+fn push_shared_keeps_earlier_references_valid() {
+    let v = SegVec::with_first_segment(1);
+    let refs: StdVec<&String> = (0..100).map(|i| v.push_shared(i.to_string())).collect();
+    for (i, s) in refs.iter().enumerate() {
+        assert_eq!(**s, i.to_string());
+    }
+    assert_eq!(v.len(), 100);
+}
+
+#[test]
+fn drain_dropped_early_or_leaked() {
+    let mut v: SegVec<String> = (0..20).map(|i| i.to_string()).collect();
+    let mut drain = v.drain(5..);
+    assert_eq!(drain.next().as_deref(), Some("5"));
+    drop(drain);
+    assert_eq!(v.len(), 5);
+    v.push("x".to_string());
+    assert_eq!(v[5], "x");
+
+    let mut v: SegVec<String> = (0..20).map(|i| i.to_string()).collect();
+    mem::forget(v.drain(3..));
+    assert_eq!(contents(&v), ["0", "1", "2"]);
+}
+
+#[test]
+fn clone_and_compare() {
+    let v: SegVec<String> = (0..30).map(|i| i.to_string()).collect();
+    let w = v.clone();
+    assert_eq!(v, w);
+    assert_eq!(contents(&w), contents(&v));
+    assert_eq!(format!("{:?}", SegVec::from_iter([1, 2])), "[1, 2]");
+}
+
+#[test]
+fn zero_sized_types_never_allocate() {
+    let mut v = SegVec::new();
+    for _ in 0..1000 {
+        v.push(());
+    }
+    assert_eq!(v.len(), 1000);
+    assert_eq!(v.segment_slices().count(), 1);
+    assert_eq!(v.drain(10..).count(), 990);
+    assert_eq!(v.pop(), Some(()));
+    assert_eq!(v.into_iter().count(), 9);
+}
+
+#[test]
+fn each_element_is_dropped_once() {
+    for len in [0, 1, 7, 8, 9, 50] {
+        let (_, n) = drops(|| {
+            let mut v: SegVec<Counted> = (0..len).map(|_| Counted(0)).collect();
+            v.truncate(len / 2);
+            drop(v.pop());
+            v.extend((0..len).map(|_| Counted(0)));
+            let mut iter = v.into_iter();
+            drop(iter.next());
+        });
+        assert_eq!(n, 2 * len);
+    }
+}
@@ -0,0 +1,335 @@
+//! Pre-allocated storage for values of one type, written `Slab<T>`.
+//!
+//! A [`Slab`] is a [`GenArena`] with a smaller interface: it hands out a
+//! [`Key`] for each value inserted, reuses the slots of removed values
+//! through a free list so that inserting and removing are *O*(1), and never
+//! moves the other values.
+//!
+//! A key pairs the slot index with the slot's *generation*, which goes up
+//! every time the slot's value is removed. A key to a removed value
+//! therefore never reaches the value that later takes over its slot: looking
+//! it up just returns `None`. For values that need to know their own key, or
+//! to borrow two values mutably at once, use a [`GenArena`] directly.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_collections::slab::Slab;
+//!
+//! let mut connections = Slab::new();
+//! let a = connections.insert("a");
+//! let b = connections.insert("b");
+//! assert_eq!(connections[b], "b");
+//!
+//! assert_eq!(connections.remove(a), "a");
+//! assert_eq!(connections.get(a), None);
+//!
+//! // The free slot is reused, but the old key does not reach the new value.
+//! let c = connections.insert("c");
+//! assert_eq!(c.index(), a.index());
+//! assert_eq!(connections.get(a), None);
+//! assert_eq!(connections[c], "c");
+//! assert_eq!(connections.len(), 2);
+//! ```
+//!
+//! [`GenArena`]: crate::gen_arena::GenArena
+
+use core::fmt;
+use core::iter::{FromIterator, FusedIterator};
+use core::ops::{Index, IndexMut};
+
+use crate::gen_arena::{self, GenArena, Handle};
+
+#[cfg(test)]
+mod tests;
+
+/// A key to a value in a [`Slab`]: a slot index plus the generation the
+/// slot had when the value was inserted.
+///
+/// Keys are only meaningful for the slab that returned them.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key(Handle);
+
+impl Key {
+    /// Returns the index of the slot the key points at.
+    pub fn index(self) -> usize {
+        self.0.index()
+    }
+
+    /// Returns the generation of the slot the key was issued for.
+    pub fn generation(self) -> u64 {
+        self.0.generation()
+    }
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Key")
+            .field("index", &self.index())
+            .field("generation", &self.generation())
+            .finish()
+    }
+}
+
+/// Storage that hands out a stable, generation-checked [`Key`] for every
+/// value inserted.
+///
+/// See the [module documentation](self) for an overview.
+pub struct Slab<T> {
+    arena: GenArena<T>,
+}
+
+impl<T> Slab<T> {
+    /// Constructs a new, empty `Slab<T>`.
+    pub fn new() -> Self {
+        Slab { arena: GenArena::new() }
+    }
+
+    /// Constructs a new, empty `Slab<T>` with room for `capacity` values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Slab { arena: GenArena::with_capacity(capacity) }
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    /// Returns `true` if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    /// Returns the number of values the slab can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.arena.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more values.
+    ///
+    /// Free slots count towards the values the slab can hold.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        self.arena.reserve(additional)
+    }
+
+    /// Removes all values.
+    ///
+    /// The slots are kept, so that the keys handed out so far stay stale
+    /// rather than pointing at the values inserted next.
+    pub fn clear(&mut self) {
+        self.arena.clear()
+    }
+
+    /// Returns the key the next call to [`insert`](Slab::insert) will
+    /// return.
+    pub fn vacant_key(&self) -> Key {
+        Key(self.arena.vacant_handle())
+    }
+
+    /// Inserts `value` and returns its key.
+    pub fn insert(&mut self, value: T) -> Key {
+        Key(self.arena.insert(value))
+    }
+
+    /// Removes and returns the value for `key`, or returns `None` if it is
+    /// stale.
+    pub fn try_remove(&mut self, key: Key) -> Option<T> {
+        self.arena.remove(key.0)
+    }
+
+    /// Removes and returns the value for `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is stale.
+    pub fn remove(&mut self, key: Key) -> T {
+        self.try_remove(key).expect("invalid slab key")
+    }
+
+    /// Returns `true` if `key` points at a value.
+    pub fn contains(&self, key: Key) -> bool {
+        self.arena.contains(key.0)
+    }
+
+    /// Returns a reference to the value for `key`, or `None` if it is stale.
+    pub fn get(&self, key: Key) -> Option<&T> {
+        self.arena.get(key.0)
+    }
+
+    /// Returns a mutable reference to the value for `key`, or `None` if it
+    /// is stale.
+    pub fn get_mut(&mut self, key: Key) -> Option<&mut T> {
+        self.arena.get_mut(key.0)
+    }
+
+    /// Retains only the values for which `f` returns `true`, passing it
+    /// each key and value in slot order.
+    ///
+    /// The keys of the retained values stay valid.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Key, &mut T) -> bool,
+    {
+        self.arena.retain(|handle, value| f(Key(handle), value))
+    }
+
+    /// Returns an iterator over the keys and values, in slot order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { inner: self.arena.iter() }
+    }
+
+    /// Returns an iterator over the keys and mutable references to the
+    /// values, in slot order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { inner: self.arena.iter_mut() }
+    }
+}
+
+impl<T: Clone> Clone for Slab<T> {
+    /// Clones the slab along with its generations, so that its keys work on
+    /// the clone too.
+    fn clone(&self) -> Self {
+        Slab { arena: self.arena.clone() }
+    }
+}
+
+impl<T> Default for Slab<T> {
+    fn default() -> Self {
+        Slab::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Slab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Index<Key> for Slab<T> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// Panics if `key` is stale.
+    fn index(&self, key: Key) -> &T {
+        self.get(key).expect("invalid slab key")
+    }
+}
+
+impl<T> IndexMut<Key> for Slab<T> {
+    /// # Panics
+    ///
+    /// Panics if `key` is stale.
+    fn index_mut(&mut self, key: Key) -> &mut T {
+        self.get_mut(key).expect("invalid slab key")
+    }
+}
+
+impl<T> FromIterator<T> for Slab<T> {
+    /// Inserts the values in order, so that they get the slots `0`, `1`, ...
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut slab = Slab::with_capacity(iter.size_hint().0);
+        for value in iter {
+            slab.insert(value);
+        }
+        slab
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Slab<T> {
+    type Item = (Key, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Slab<T> {
+    type Item = (Key, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator over the keys and values of a `Slab`.
+///
+/// This `struct` is created by [`Slab::iter`].
+pub struct Iter<'a, T> {
+    inner: gen_arena::Iter<'a, T>,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter { inner: self.inner.clone() }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Key, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<(Key, &'a T)> {
+        self.inner.next().map(|(handle, value)| (Key(handle), value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(Key, &'a T)> {
+        self.inner.next_back().map(|(handle, value)| (Key(handle), value))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An iterator over the keys and mutable references to the values of a
+/// `Slab`.
+///
+/// This `struct` is created by [`Slab::iter_mut`].
+pub struct IterMut<'a, T> {
+    inner: gen_arena::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (Key, &'a mut T);
+
+    #[inline]
+    fn next(&mut self) -> Option<(Key, &'a mut T)> {
+        self.inner.next().map(|(handle, value)| (Key(handle), value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(Key, &'a mut T)> {
+        self.inner.next_back().map(|(handle, value)| (Key(handle), value))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
@@ -0,0 +1,183 @@
+use super::*;
+
+use std::collections::BTreeMap;
+use std::vec::Vec as StdVec;
+
+use crate::test_util::{drops, Counted, Rng};
+
+// Checks the slab against the live keys in `m`, and that none of the
+// removed keys in `dead` still reaches a value.
+fn check(slab: &Slab<u32>, m: &BTreeMap<Key, u32>, dead: &[Key]) {
+    assert_eq!(slab.len(), m.len());
+    assert!(slab.iter().map(|(k, &v)| (k, v)).eq(m.iter().map(|(&k, &v)| (k, v))));
+    assert!(slab.iter().rev().map(|(k, _)| k).eq(m.keys().rev().copied()));
+    assert_eq!(slab.iter().len(), m.len());
+    for (&key, &value) in m {
+        assert_eq!(slab.get(key), Some(&value));
+        assert_eq!(slab[key], value);
+    }
+    for &key in dead {
+        assert!(!slab.contains(key), "stale key {:?} still resolves", key);
+    }
+}
+
+#[test]
+fn random_operations_match_a_map_and_reject_stale_keys() {
+    for seed in 0..200 {
+        let mut rng = Rng::new(seed);
+        let mut slab = Slab::new();
+        let mut m = BTreeMap::new();
+        let mut dead = StdVec::new();
+        for _ in 0..200 {
+            let x = rng.next() as u32;
+            let live: StdVec<Key> = m.keys().copied().collect();
+            match rng.below(8) {
+                0..=2 => {
+                    let vacant = slab.vacant_key();
+                    let key = slab.insert(x);
+                    assert_eq!(key, vacant);
+                    assert!(m.insert(key, x).is_none(), "key {:?} handed out twice", key);
+                }
+                3 | 4 if !live.is_empty() => {
+                    let key = live[rng.below(live.len())];
+                    assert_eq!(slab.remove(key), m.remove(&key).unwrap());
+                    assert_eq!(slab.try_remove(key), None);
+                    dead.push(key);
+                }
+                5 if !dead.is_empty() => {
+                    let key = dead[rng.below(dead.len())];
+                    assert_eq!(slab.get_mut(key), None);
+                    assert_eq!(slab.try_remove(key), None);
+                }
+                6 if !live.is_empty() => {
+                    let key = live[rng.below(live.len())];
+                    *slab.get_mut(key).unwrap() = x;
+                    m.insert(key, x);
+                }
+                7 => {
+                    let modulus = rng.below(4) as u32 + 2;
+                    slab.retain(|key, value| {
+                        assert_eq!(m.get(&key), Some(&*value));
+                        *value % modulus != 0
+                    });
+                    m.retain(|key, value| {
+                        let keep = *value % modulus != 0;
+                        if !keep {
+                            dead.push(*key);
+                        }
+                        keep
+                    });
+                }
+                _ => {}
+            }
+            check(&slab, &m, &dead);
+        }
+    }
+}
+
+#[test]
+fn reused_slots_get_a_new_generation() {
+    let mut slab = Slab::new();
+    let a = slab.insert('a');
+    let b = slab.insert('b');
+    assert_eq!((a.index(), a.generation()), (0, 0));
+    assert_eq!((b.index(), b.generation()), (1, 0));
+
+    slab.remove(a);
+    slab.remove(b);
+    // The free list hands back the last removed slot first.
+    let c = slab.insert('c');
+    let d = slab.insert('d');
+    assert_eq!((c.index(), c.generation()), (1, 1));
+    assert_eq!((d.index(), d.generation()), (0, 1));
+    assert_eq!(slab.get(a), None);
+    assert_eq!(slab.get(b), None);
+    assert_eq!(slab[c], 'c');
+    assert_eq!(slab[d], 'd');
+    assert!(slab.capacity() >= 2);
+
+    slab.remove(d);
+    let e = slab.insert('e');
+    assert_eq!((e.index(), e.generation()), (0, 2));
+}
+
+#[test]
+fn clear_invalidates_every_key() {
+    let mut slab: Slab<u32> = (0..5).collect();
+    let keys: StdVec<Key> = slab.iter().map(|(k, _)| k).collect();
+    assert!(keys.iter().enumerate().all(|(i, k)| k.index() == i && k.generation() == 0));
+    slab.remove(keys[2]);
+
+    slab.clear();
+    assert!(slab.is_empty());
+    assert!(keys.iter().all(|&k| !slab.contains(k)));
+
+    // The slots are reused in order, each with a generation that the old
+    // keys do not have.
+    let fresh: StdVec<Key> = (10..15).map(|x| slab.insert(x)).collect();
+    for (old, new) in keys.iter().zip(&fresh) {
+        assert_eq!(old.index(), new.index());
+        assert_ne!(old.generation(), new.generation());
+        assert_eq!(slab.get(*old), None);
+    }
+    assert_eq!(slab.len(), 5);
+}
+
+#[test]
+fn iterators_skip_vacant_slots() {
+    let mut slab: Slab<u32> = (0..10).collect();
+    slab.retain(|key, _| key.index() % 3 != 0);
+    assert!(slab.iter().map(|(_, &v)| v).eq([1, 2, 4, 5, 7, 8]));
+    for (key, value) in &mut slab {
+        *value += key.index() as u32;
+    }
+    let mut iter = slab.iter_mut();
+    assert_eq!(iter.len(), 6);
+    assert_eq!(iter.next_back().map(|(_, v)| *v), Some(16));
+    assert_eq!(iter.next().map(|(_, v)| *v), Some(2));
+    assert_eq!(iter.len(), 4);
+    assert!((&slab).into_iter().map(|(_, &v)| v).eq([2, 4, 8, 10, 14, 16]));
+
+    let clone = slab.clone();
+    assert!(clone.iter().eq(slab.iter()));
+    let (key, _) = slab.iter().next().unwrap();
+    assert_eq!(format!("{:?}", Slab::<u8>::default()), "{}");
+    assert_eq!(clone[key], 2);
+}
+
+#[test]
+#[should_panic(expected = "invalid slab key")]
+fn indexing_with_a_stale_key_panics() {
+    let mut slab = Slab::new();
+    let key = slab.insert(1);
+    slab.remove(key);
+    slab.insert(2);
+    let _ = slab[key];
+}
+
+#[test]
+#[should_panic(expected = "invalid slab key")]
+fn removing_twice_panics() {
+    let mut slab = Slab::new();
+    let key = slab.insert(1);
+    slab.remove(key);
+    slab.remove(key);
+}
+
+#[test]
+fn each_value_is_dropped_once() {
+    let mut slab: Slab<Counted> = (0..10).map(|_| Counted(0)).collect();
+    let keys: StdVec<Key> = slab.iter().map(|(k, _)| k).collect();
+    let (_, n) = drops(|| {
+        drop(slab.remove(keys[3]));
+        let mut i = 0;
+        slab.retain(|_, _| {
+            i += 1;
+            i % 2 == 0
+        });
+    });
+    assert_eq!(n, 6);
+    assert_eq!(drops(|| slab.clear()).1, 4);
+    slab.insert(Counted(0));
+    assert_eq!(drops(|| drop(slab)).1, 1);
+}
@@ -0,0 +1,1221 @@
+//! A growable array type that keeps a small number of elements inline,
+//! written `SmallVec<T, N>`.
+//!
+//! A `SmallVec<T, N>` stores up to `N` elements directly inside the value,
+//! without touching the allocator. Once it has to hold more than that, it
+//! moves its contents into a heap buffer managed by a [`RawVec`] and from
+//! then on behaves like a [`Vec`].
+//!
+//! Most vectors in practice only ever hold a handful of elements, so picking
+//! an `N` that covers the common case removes the allocation entirely.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_collections::small_vec::SmallVec;
+//!
+//! let mut v: SmallVec<i32, 4> = SmallVec::new();
+//! v.push(1);
+//! v.push(2);
+//! assert!(!v.spilled());
+//!
+//! v.extend([3, 4, 5].iter().copied());
+//! assert!(v.spilled());
+//! assert_eq!(v, [1, 2, 3, 4, 5]);
+//! ```
+//!
+//! [`RawVec`]: crate::raw_vec::RawVec
+
+use core::cmp::{self, Ordering};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::ops::{self, Range, RangeBounds};
+use core::ptr::{self, NonNull};
+use core::slice;
+
+use crate::alloc::Global;
+use crate::raw_vec::RawVec;
+use crate::vec::{partition_dedup_by, slice_range, Vec};
+
+#[cfg(test)]
+mod tests;
+
+/// A contiguous growable array type that stores up to `N` elements inline.
+///
+/// `SmallVec` mirrors the API of [`Vec`]: it derefs to a slice, and `push`,
+/// `pop`, `insert`, `remove`, `drain`, `retain`, `dedup` and friends all
+/// behave the same way. The only difference is where the elements live.
+///
+/// # Capacity and spilling
+///
+/// A new `SmallVec` has a capacity of `N` and does not allocate. Growing past
+/// `N` elements *spills* the contents to the heap, which costs one allocation
+/// and a copy of the inline elements. Once spilled, the vector stays on the
+/// heap until [`shrink_to_fit`] finds that its elements fit inline again.
+///
+/// Zero-sized element types never spill, since any number of them fit
+/// inline.
+///
+/// Note that a `SmallVec` is as large as `N` elements plus a few words, even
+/// when it has spilled, so large values of `N` make the vector expensive to
+/// move around.
+///
+/// [`shrink_to_fit`]: SmallVec::shrink_to_fit
+pub struct SmallVec<T, const N: usize> {
+    len: usize,
+    data: SmallVecData<T, N>,
+}
+
+enum SmallVecData<T, const N: usize> {
+    Inline(MaybeUninit<[T; N]>),
+    Heap(RawVec<T>),
+}
+
+impl<T, const N: usize> SmallVec<T, N> {
+    /// Constructs a new, empty `SmallVec<T, N>`.
+    ///
+    /// The vector will not allocate until more than `N` elements are pushed
+    /// onto it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let vec: SmallVec<i32, 8> = SmallVec::new();
+    /// assert_eq!(vec.capacity(), 8);
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        SmallVec { len: 0, data: SmallVecData::Inline(MaybeUninit::uninit()) }
+    }
+
+    /// Constructs a new, empty `SmallVec<T, N>` with at least the specified
+    /// capacity.
+    ///
+    /// If `capacity` is not larger than `N`, the vector stays inline and does
+    /// not allocate. Otherwise it starts out spilled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let vec: SmallVec<i32, 4> = SmallVec::with_capacity(2);
+    /// assert!(!vec.spilled());
+    ///
+    /// let vec: SmallVec<i32, 4> = SmallVec::with_capacity(10);
+    /// assert!(vec.spilled());
+    /// assert_eq!(vec.capacity(), 10);
+    /// ```
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity <= Self::inline_capacity() {
+            SmallVec::new()
+        } else {
+            SmallVec { len: 0, data: SmallVecData::Heap(RawVec::with_capacity(capacity)) }
+        }
+    }
+
+    /// Converts a [`Vec`] into a `SmallVec`.
+    ///
+    /// If the elements fit inline they are moved there and the vector's
+    /// buffer is freed, otherwise the buffer is reused without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    /// use rusty_collections::vec;
+    ///
+    /// let small: SmallVec<i32, 4> = SmallVec::from_vec(vec![1, 2, 3]);
+    /// assert!(!small.spilled());
+    ///
+    /// let big: SmallVec<i32, 2> = SmallVec::from_vec(vec![1, 2, 3]);
+    /// assert!(big.spilled());
+    /// ```
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        let (ptr, len, capacity) = vec.into_raw_parts();
+        unsafe {
+            let buf = RawVec::from_raw_parts(ptr, capacity);
+            if len <= Self::inline_capacity() {
+                let mut inline = MaybeUninit::<[T; N]>::uninit();
+                ptr::copy_nonoverlapping(ptr, inline.as_mut_ptr() as *mut T, len);
+                // `buf` only frees the memory, the elements now live inline.
+                drop(buf);
+                SmallVec { len, data: SmallVecData::Inline(inline) }
+            } else {
+                SmallVec { len, data: SmallVecData::Heap(buf) }
+            }
+        }
+    }
+
+    /// Converts the `SmallVec` into a [`Vec`].
+    ///
+    /// A spilled vector hands over its heap buffer without copying; an inline
+    /// one allocates a buffer of exactly `len` elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    /// use rusty_collections::vec;
+    ///
+    /// let mut small: SmallVec<i32, 4> = SmallVec::new();
+    /// small.push(1);
+    /// assert_eq!(small.into_vec(), vec![1]);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        let mut me = ManuallyDrop::new(self);
+        let len = me.len;
+        unsafe {
+            match ptr::read(&me.data) {
+                SmallVecData::Heap(buf) => {
+                    let capacity = buf.capacity();
+                    let ptr = ManuallyDrop::new(buf).ptr();
+                    Vec::from_raw_parts_unchecked_in(ptr, len, capacity, Global)
+                }
+                SmallVecData::Inline(_) => {
+                    let mut vec = Vec::with_capacity(len);
+                    ptr::copy_nonoverlapping(me.as_mut_ptr(), vec.as_mut_ptr(), len);
+                    vec.set_len_unchecked(len);
+                    vec
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn inline_capacity() -> usize {
+        if mem::size_of::<T>() == 0 { usize::MAX } else { N }
+    }
+
+    /// Returns the number of elements the vector can hold without
+    /// reallocating.
+    ///
+    /// This is `N` as long as the vector has not spilled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let vec: SmallVec<i32, 4> = SmallVec::new();
+    /// assert_eq!(vec.capacity(), 4);
+    /// ```
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        match self.data {
+            SmallVecData::Inline(_) => Self::inline_capacity(),
+            SmallVecData::Heap(ref buf) => buf.capacity(),
+        }
+    }
+
+    /// Returns `true` if the elements have been moved to a heap buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let mut vec: SmallVec<i32, 1> = SmallVec::new();
+    /// vec.push(1);
+    /// assert!(!vec.spilled());
+    /// vec.push(2);
+    /// assert!(vec.spilled());
+    /// ```
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        match self.data {
+            SmallVecData::Inline(_) => false,
+            SmallVecData::Heap(_) => true,
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more elements to be inserted
+    /// in the given `SmallVec`.
+    ///
+    /// Spills the vector if the inline storage is not enough. Like
+    /// [`Vec::reserve`], the heap buffer may be bigger than requested to
+    /// avoid frequent reallocations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let mut vec: SmallVec<i32, 2> = SmallVec::new();
+    /// vec.push(1);
+    /// vec.reserve(10);
+    /// assert!(vec.capacity() >= 11);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let len = self.len;
+        match self.data {
+            SmallVecData::Heap(ref mut buf) => buf.reserve(len, additional),
+            SmallVecData::Inline(_) => {
+                if Self::inline_capacity() - len < additional {
+                    self.spill(additional, false);
+                }
+            }
+        }
+    }
+
+    /// Reserves the minimum capacity for exactly `additional` more elements to
+    /// be inserted in the given `SmallVec`.
+    ///
+    /// Does nothing if the capacity is already sufficient.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let len = self.len;
+        match self.data {
+            SmallVecData::Heap(ref mut buf) => buf.reserve_exact(len, additional),
+            SmallVecData::Inline(_) => {
+                if Self::inline_capacity() - len < additional {
+                    self.spill(additional, true);
+                }
+            }
+        }
+    }
+
+    // Moves the inline elements into a heap buffer with room for `additional`
+    // more. An amortized spill asks for at least `N` extra slots, so the
+    // vector doubles its capacity just like a `Vec` would.
+    //
+    // The new buffer is still empty, so it is grown from a used capacity of
+    // 0: `reserve(len, ..)` would see `len` elements in a buffer of capacity
+    // 0 and not allocate at all.
+    #[cold]
+    #[inline(never)]
+    fn spill(&mut self, additional: usize, exact: bool) {
+        let len = self.len;
+        let extra = if exact { additional } else { cmp::max(additional, N) };
+        let capacity = len.checked_add(extra).expect("capacity overflow");
+        let mut buf = RawVec::new();
+        if exact {
+            buf.reserve_exact(0, capacity);
+        } else {
+            buf.reserve(0, capacity);
+        }
+        unsafe {
+            ptr::copy_nonoverlapping(self.as_ptr(), buf.ptr(), len);
+        }
+        self.data = SmallVecData::Heap(buf);
+    }
+
+    /// Shrinks the capacity of the vector as much as possible.
+    ///
+    /// A spilled vector whose elements fit inline again moves them back and
+    /// frees its heap buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let mut vec: SmallVec<i32, 2> = SmallVec::with_capacity(10);
+    /// vec.push(1);
+    /// assert!(vec.spilled());
+    /// vec.shrink_to_fit();
+    /// assert!(!vec.spilled());
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        let len = self.len;
+        if let SmallVecData::Heap(ref mut buf) = self.data {
+            if len <= Self::inline_capacity() {
+                let mut inline = MaybeUninit::<[T; N]>::uninit();
+                unsafe {
+                    ptr::copy_nonoverlapping(buf.ptr(), inline.as_mut_ptr() as *mut T, len);
+                }
+                // Dropping the `RawVec` frees the buffer without touching the
+                // elements, which now live inline.
+                self.data = SmallVecData::Inline(inline);
+            } else if buf.capacity() > len {
+                buf.shrink_to_fit(len);
+            }
+        }
+    }
+
+    /// Shortens the vector, keeping the first `len` elements and dropping
+    /// the rest.
+    ///
+    /// If `len` is greater than the vector's current length, this has no
+    /// effect. The capacity is left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let mut vec: SmallVec<i32, 8> = [1, 2, 3, 4, 5].iter().copied().collect();
+    /// vec.truncate(2);
+    /// assert_eq!(vec, [1, 2]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        // This is safe because:
+        //
+        // * the slice passed to `drop_in_place` is valid; the `len > self.len`
+        //   case avoids creating an invalid slice, and
+        // * the `len` of the vector is shrunk before calling `drop_in_place`,
+        //   such that no value will be dropped twice in case `drop_in_place`
+        //   were to panic once (if it panics twice, the program aborts).
+        unsafe {
+            if len > self.len {
+                return;
+            }
+            let remaining_len = self.len - len;
+            let s = ptr::slice_from_raw_parts_mut(self.as_mut_ptr().add(len), remaining_len);
+            self.len = len;
+            ptr::drop_in_place(s);
+        }
+    }
+
+    /// Extracts a slice containing the entire vector.
+    ///
+    /// Equivalent to `&s[..]`.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    /// Extracts a mutable slice of the entire vector.
+    ///
+    /// Equivalent to `&mut s[..]`.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+
+    /// Returns a raw pointer to the vector's buffer.
+    ///
+    /// The pointer points into the `SmallVec` value itself while it has not
+    /// spilled, so it is invalidated by moving the vector as well as by
+    /// anything that would reallocate a [`Vec`].
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        match self.data {
+            SmallVecData::Inline(ref inline) => inline.as_ptr() as *const T,
+            SmallVecData::Heap(ref buf) => buf.ptr(),
+        }
+    }
+
+    /// Returns an unsafe mutable pointer to the vector's buffer.
+    ///
+    /// See [`as_ptr`] for how long the pointer stays valid.
+    ///
+    /// [`as_ptr`]: SmallVec::as_ptr
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        match self.data {
+            SmallVecData::Inline(ref mut inline) => inline.as_mut_ptr() as *mut T,
+            SmallVecData::Heap(ref buf) => buf.ptr(),
+        }
+    }
+
+    /// Forces the length of the vector to `new_len`.
+    ///
+    /// # Safety
+    ///
+    /// - `new_len` must be less than or equal to [`capacity()`].
+    /// - The elements at `old_len..new_len` must be initialized.
+    ///
+    /// [`capacity()`]: SmallVec::capacity
+    #[inline]
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.capacity());
+
+        self.len = new_len;
+    }
+
+    /// Removes an element from the vector and returns it, replacing it with
+    /// the last element.
+    ///
+    /// This does not preserve ordering, but is *O*(1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        #[cold]
+        #[inline(never)]
+        fn assert_failed(index: usize, len: usize) -> ! {
+            panic!("swap_remove index (is {}) should be < len (is {})", index, len);
+        }
+
+        let len = self.len();
+        if index >= len {
+            assert_failed(index, len);
+        }
+        unsafe {
+            // Replace self[index] with the last element. If the bounds check
+            // above succeeds there must be a last element (which can be
+            // self[index] itself).
+            let last = ptr::read(self.as_ptr().add(len - 1));
+            let hole = self.as_mut_ptr().add(index);
+            self.set_len(len - 1);
+            ptr::replace(hole, last)
+        }
+    }
+
+    /// Inserts an element at position `index` within the vector, shifting all
+    /// elements after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let mut vec: SmallVec<i32, 4> = [1, 2, 3].iter().copied().collect();
+    /// vec.insert(1, 4);
+    /// assert_eq!(vec, [1, 4, 2, 3]);
+    /// vec.insert(4, 5);
+    /// assert_eq!(vec, [1, 4, 2, 3, 5]);
+    /// ```
+    pub fn insert(&mut self, index: usize, element: T) {
+        #[cold]
+        #[inline(never)]
+        fn assert_failed(index: usize, len: usize) -> ! {
+            panic!("insertion index (is {}) should be <= len (is {})", index, len);
+        }
+
+        let len = self.len();
+        if index > len {
+            assert_failed(index, len);
+        }
+
+        // space for the new element
+        if len == self.capacity() {
+            self.reserve(1);
+        }
+
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            // Shift everything over to make space, then overwrite the first
+            // copy of the `index`th element.
+            ptr::copy(p, p.offset(1), len - index);
+            ptr::write(p, element);
+            self.set_len(len + 1);
+        }
+    }
+
+    /// Removes and returns the element at position `index` within the vector,
+    /// shifting all elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let mut v: SmallVec<i32, 4> = [1, 2, 3].iter().copied().collect();
+    /// assert_eq!(v.remove(1), 2);
+    /// assert_eq!(v, [1, 3]);
+    /// ```
+    pub fn remove(&mut self, index: usize) -> T {
+        #[cold]
+        #[inline(never)]
+        fn assert_failed(index: usize, len: usize) -> ! {
+            panic!("removal index (is {}) should be < len (is {})", index, len);
+        }
+
+        let len = self.len();
+        if index >= len {
+            assert_failed(index, len);
+        }
+        unsafe {
+            let ptr = self.as_mut_ptr().add(index);
+            // copy it out, unsafely having a copy of the value on
+            // the stack and in the vector at the same time.
+            let ret = ptr::read(ptr);
+            // Shift everything down to fill in that spot.
+            ptr::copy(ptr.offset(1), ptr, len - index - 1);
+            self.set_len(len - 1);
+            ret
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` such that `f(&e)` returns `false`.
+    /// This method operates in place, visiting each element exactly once in the
+    /// original order, and preserves the order of the retained elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let mut vec: SmallVec<i32, 4> = [1, 2, 3, 4].iter().copied().collect();
+    /// vec.retain(|&x| x % 2 == 0);
+    /// assert_eq!(vec, [2, 4]);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let len = self.len();
+        let mut del = 0;
+        {
+            let v = &mut **self;
+
+            for i in 0..len {
+                if !f(&v[i]) {
+                    del += 1;
+                } else if del > 0 {
+                    v.swap(i - del, i);
+                }
+            }
+        }
+        if del > 0 {
+            self.truncate(len - del);
+        }
+    }
+
+    /// Removes all but the first of consecutive elements in the vector that
+    /// resolve to the same key.
+    ///
+    /// If the vector is sorted, this removes all duplicates.
+    #[inline]
+    pub fn dedup_by_key<F, K>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Removes all but the first of consecutive elements in the vector
+    /// satisfying a given equality relation.
+    ///
+    /// The `same_bucket` function is passed references to two elements from
+    /// the vector and must determine if the elements compare equal. The
+    /// elements are passed in opposite order from their order in the slice,
+    /// so if `same_bucket(a, b)` returns `true`, `a` is removed.
+    ///
+    /// If the vector is sorted, this removes all duplicates.
+    pub fn dedup_by<F>(&mut self, same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let len = {
+            let (dedup, _) = partition_dedup_by(self.as_mut_slice(), same_bucket);
+            dedup.len()
+        };
+        self.truncate(len);
+    }
+
+    /// Appends an element to the back of the vector, spilling it to the heap
+    /// if the inline storage is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity exceeds `isize::MAX` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let mut vec: SmallVec<i32, 2> = SmallVec::new();
+    /// vec.push(1);
+    /// vec.push(2);
+    /// vec.push(3);
+    /// assert_eq!(vec, [1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+        unsafe {
+            let end = self.as_mut_ptr().add(self.len);
+            ptr::write(end, value);
+            self.len += 1;
+        }
+    }
+
+    /// Removes the last element from the vector and returns it, or [`None`]
+    /// if it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let mut vec: SmallVec<i32, 4> = [1, 2, 3].iter().copied().collect();
+    /// assert_eq!(vec.pop(), Some(3));
+    /// assert_eq!(vec, [1, 2]);
+    /// ```
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            None
+        } else {
+            unsafe {
+                self.len -= 1;
+                Some(ptr::read(self.as_ptr().add(self.len())))
+            }
+        }
+    }
+
+    /// Creates a draining iterator that removes the specified range in the
+    /// vector and yields the removed items.
+    ///
+    /// When the iterator **is** dropped, all elements in the range are removed
+    /// from the vector, even if the iterator was not fully consumed. If the
+    /// iterator **is not** dropped (with [`mem::forget`] for example), it is
+    /// unspecified how many elements are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let mut v: SmallVec<i32, 4> = [1, 2, 3].iter().copied().collect();
+    /// let u: Vec<_> = v.drain(1..).collect();
+    /// assert_eq!(v, [1]);
+    /// assert_eq!(u, [2, 3]);
+    /// ```
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, N>
+    where
+        R: RangeBounds<usize>,
+    {
+        // The length is shortened to `start` while the `Drain` is alive, so
+        // a leaked `Drain` only leaks elements and never exposes moved-from
+        // ones. See `Vec::drain` for the full story.
+        let len = self.len();
+        let Range { start, end } = slice_range(range, len);
+
+        unsafe {
+            self.set_len(start);
+            let range_slice = slice::from_raw_parts_mut(self.as_mut_ptr().add(start), end - start);
+            Drain {
+                tail_start: end,
+                tail_len: len - end,
+                iter: range_slice.iter(),
+                vec: NonNull::from(self),
+            }
+        }
+    }
+
+    /// Clears the vector, removing all values.
+    ///
+    /// Note that this method has no effect on the capacity, and does not move
+    /// a spilled vector back inline.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Returns the number of elements in the vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Clone, const N: usize> SmallVec<T, N> {
+    /// Resizes the `SmallVec` in-place so that `len` is equal to `new_len`.
+    ///
+    /// If `new_len` is greater than `len`, the vector is extended by the
+    /// difference, with each additional slot filled with `value`.
+    /// If `new_len` is less than `len`, the vector is simply truncated.
+    pub fn resize(&mut self, new_len: usize, value: T) {
+        let len = self.len();
+
+        if new_len > len {
+            self.reserve(new_len - len);
+            for _ in len..new_len {
+                self.push(value.clone());
+            }
+        } else {
+            self.truncate(new_len);
+        }
+    }
+
+    /// Clones and appends all elements in a slice to the `SmallVec`.
+    pub fn extend_from_slice(&mut self, other: &[T]) {
+        self.reserve(other.len());
+        for element in other {
+            self.push(element.clone());
+        }
+    }
+}
+
+impl<T: PartialEq, const N: usize> SmallVec<T, N> {
+    /// Removes consecutive repeated elements in the vector according to the
+    /// [`PartialEq`] trait implementation.
+    ///
+    /// If the vector is sorted, this removes all duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::small_vec::SmallVec;
+    ///
+    /// let mut vec: SmallVec<i32, 8> = [1, 2, 2, 3, 2].iter().copied().collect();
+    /// vec.dedup();
+    /// assert_eq!(vec, [1, 2, 3, 2]);
+    /// ```
+    #[inline]
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Common trait implementations for SmallVec
+////////////////////////////////////////////////////////////////////////////////
+
+impl<T, const N: usize> ops::Deref for SmallVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> ops::DerefMut for SmallVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for SmallVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut vec = SmallVec::with_capacity(self.len);
+        vec.extend_from_slice(self);
+        vec
+    }
+}
+
+impl<T: Hash, const N: usize> Hash for SmallVec<T, N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallVec<T, N> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = SmallVec::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T, const N: usize> IntoIterator for SmallVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    /// Creates a consuming iterator, that is, one that moves each value out of
+    /// the vector (from start to end). The vector cannot be used after calling
+    /// this.
+    #[inline]
+    fn into_iter(mut self) -> IntoIter<T, N> {
+        let end = self.len;
+        // The iterator owns the elements from here on; the vector only keeps
+        // the storage alive.
+        self.len = 0;
+        IntoIter { vec: self, current: 0, end }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut SmallVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SmallVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut iterator = iter.into_iter();
+        while let Some(element) = iterator.next() {
+            let len = self.len();
+            if len == self.capacity() {
+                let (lower, _) = iterator.size_hint();
+                self.reserve(lower.saturating_add(1));
+            }
+            unsafe {
+                ptr::write(self.as_mut_ptr().add(len), element);
+                self.set_len(len + 1);
+            }
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a, const N: usize> Extend<&'a T> for SmallVec<T, N> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+
+impl<T, U, const N: usize, const M: usize> PartialEq<SmallVec<U, M>> for SmallVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &SmallVec<U, M>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T, U, const N: usize> PartialEq<[U]> for SmallVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &[U]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T, U, const N: usize> PartialEq<&[U]> for SmallVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &&[U]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T, U, const N: usize, const M: usize> PartialEq<[U; M]> for SmallVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &[U; M]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T, U, const N: usize> PartialEq<Vec<U>> for SmallVec<T, N>
+where
+    T: PartialEq<U>,
+{
+    #[inline]
+    fn eq(&self, other: &Vec<U>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for SmallVec<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for SmallVec<T, N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for SmallVec<T, N> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<T, const N: usize> Drop for SmallVec<T, N> {
+    fn drop(&mut self) {
+        unsafe {
+            // use drop for [T]
+            // use a raw slice to refer to the elements of the vector as weakest necessary type;
+            // could avoid questions of validity in certain cases
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.as_mut_ptr(), self.len))
+        }
+        // a spilled `RawVec` handles deallocation
+    }
+}
+
+impl<T, const N: usize> Default for SmallVec<T, N> {
+    /// Creates an empty `SmallVec<T, N>`.
+    fn default() -> Self {
+        SmallVec::new()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for SmallVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for SmallVec<T, N> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for SmallVec<T, N> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T: Clone, const N: usize> From<&[T]> for SmallVec<T, N> {
+    fn from(s: &[T]) -> Self {
+        let mut vec = SmallVec::with_capacity(s.len());
+        vec.extend_from_slice(s);
+        vec
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for SmallVec<T, N> {
+    fn from(array: [T; N]) -> Self {
+        SmallVec { len: N, data: SmallVecData::Inline(MaybeUninit::new(array)) }
+    }
+}
+
+impl<T, const N: usize> From<Vec<T>> for SmallVec<T, N> {
+    fn from(vec: Vec<T>) -> Self {
+        SmallVec::from_vec(vec)
+    }
+}
+
+impl<T, const N: usize> From<SmallVec<T, N>> for Vec<T> {
+    fn from(vec: SmallVec<T, N>) -> Self {
+        vec.into_vec()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Iterators
+////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator that moves out of a `SmallVec`.
+///
+/// This `struct` is created by the `into_iter` method on [`SmallVec`]
+/// (provided by the [`IntoIterator`] trait).
+pub struct IntoIter<T, const N: usize> {
+    // Owns the storage, with its length set to zero. The live elements are
+    // the ones in `current..end`.
+    vec: SmallVec<T, N>,
+    current: usize,
+    end: usize,
+}
+
+impl<T, const N: usize> IntoIter<T, N> {
+    /// Returns the remaining items of this iterator as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        unsafe {
+            slice::from_raw_parts(self.vec.as_ptr().add(self.current), self.end - self.current)
+        }
+    }
+
+    /// Returns the remaining items of this iterator as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe {
+            slice::from_raw_parts_mut(
+                self.vec.as_mut_ptr().add(self.current),
+                self.end - self.current,
+            )
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for IntoIter<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.current == self.end {
+            None
+        } else {
+            unsafe {
+                let old = self.current;
+                self.current += 1;
+                Some(ptr::read(self.vec.as_ptr().add(old)))
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let exact = self.end - self.current;
+        (exact, Some(exact))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.current == self.end {
+            None
+        } else {
+            unsafe {
+                self.end -= 1;
+                Some(ptr::read(self.vec.as_ptr().add(self.end)))
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+impl<T: Clone, const N: usize> Clone for IntoIter<T, N> {
+    fn clone(&self) -> Self {
+        SmallVec::<T, N>::from(self.as_slice()).into_iter()
+    }
+}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        unsafe {
+            // drop the remaining elements; `vec` frees the storage afterwards
+            ptr::drop_in_place(self.as_mut_slice());
+        }
+    }
+}
+
+/// A draining iterator for `SmallVec<T, N>`.
+///
+/// This `struct` is created by [`SmallVec::drain`].
+/// See its documentation for more.
+pub struct Drain<'a, T: 'a, const N: usize> {
+    /// Index of tail to preserve
+    tail_start: usize,
+    /// Length of tail
+    tail_len: usize,
+    /// Current remaining range to remove
+    iter: slice::Iter<'a, T>,
+    vec: NonNull<SmallVec<T, N>>,
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for Drain<'_, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.iter.as_slice()).finish()
+    }
+}
+
+impl<'a, T, const N: usize> Drain<'a, T, N> {
+    /// Returns the remaining items of this iterator as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        self.iter.as_slice()
+    }
+}
+
+unsafe impl<T: Sync, const N: usize> Sync for Drain<'_, T, N> {}
+unsafe impl<T: Send, const N: usize> Send for Drain<'_, T, N> {}
+
+impl<T, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|elt| unsafe { ptr::read(elt as *const _) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back().map(|elt| unsafe { ptr::read(elt as *const _) })
+    }
+}
+
+impl<T, const N: usize> Drop for Drain<'_, T, N> {
+    fn drop(&mut self) {
+        /// Continues dropping the remaining elements in the `Drain`, then moves back the
+        /// un-`Drain`ed elements to restore the original `SmallVec`.
+        struct DropGuard<'r, 'a, T, const N: usize>(&'r mut Drain<'a, T, N>);
+
+        impl<'r, 'a, T, const N: usize> Drop for DropGuard<'r, 'a, T, N> {
+            fn drop(&mut self) {
+                // Continue the same loop we have below. If the loop already finished, this does
+                // nothing.
+                self.0.for_each(drop);
+
+                if self.0.tail_len > 0 {
+                    unsafe {
+                        let source_vec = self.0.vec.as_mut();
+                        // memmove back untouched tail, update to new length
+                        let start = source_vec.len();
+                        let tail = self.0.tail_start;
+                        if tail != start {
+                            let src = source_vec.as_ptr().add(tail);
+                            let dst = source_vec.as_mut_ptr().add(start);
+                            ptr::copy(src, dst, self.0.tail_len);
+                        }
+                        source_vec.set_len(start + self.0.tail_len);
+                    }
+                }
+            }
+        }
+
+        // exhaust self first
+        while let Some(item) = self.next() {
+            let guard = DropGuard(self);
+            drop(item);
+            mem::forget(guard);
+        }
+
+        // Drop a `DropGuard` to move back the non-drained tail of `self`.
+        DropGuard(self);
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for Drain<'_, T, N> {}
+
+impl<T, const N: usize> FusedIterator for Drain<'_, T, N> {}
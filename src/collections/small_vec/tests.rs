@@ -0,0 +1,184 @@
+use super::*;
+
+use std::vec::Vec as StdVec;
+
+use crate::test_util::{drops, Counted, Rng};
+
+// Applies one random operation to both vectors and checks that they agree
+// on what it returned. Values come from a small range, so `dedup` has
+// something to do.
+fn step(rng: &mut Rng, v: &mut SmallVec<u32, 4>, m: &mut StdVec<u32>) {
+    let x = (rng.next() % 8) as u32;
+    match rng.below(12) {
+        0..=2 => {
+            v.push(x);
+            m.push(x);
+        }
+        3 => assert_eq!(v.pop(), m.pop()),
+        4 => {
+            let i = rng.below(m.len() + 1);
+            v.insert(i, x);
+            m.insert(i, x);
+        }
+        5 if !m.is_empty() => {
+            let i = rng.below(m.len());
+            assert_eq!(v.remove(i), m.remove(i));
+        }
+        6 if !m.is_empty() => {
+            let i = rng.below(m.len());
+            assert_eq!(v.swap_remove(i), m.swap_remove(i));
+        }
+        7 => {
+            let start = rng.below(m.len() + 1);
+            let end = start + rng.below(m.len() - start + 1);
+            let drained: StdVec<u32> = v.drain(start..end).collect();
+            assert_eq!(drained, m.drain(start..end).collect::<StdVec<_>>());
+        }
+        8 => {
+            let extra = rng.below(10);
+            v.extend((0..extra).map(|i| i as u32));
+            m.extend((0..extra).map(|i| i as u32));
+        }
+        9 => {
+            v.retain(|&y| y != x);
+            m.retain(|&y| y != x);
+        }
+        10 => {
+            v.dedup();
+            m.dedup();
+        }
+        _ => {
+            v.shrink_to_fit();
+            assert_eq!(v.spilled(), m.len() > 4);
+            let len = rng.below(m.len() + 1);
+            v.truncate(len);
+            m.truncate(len);
+        }
+    }
+    assert_eq!(v[..], m[..]);
+    assert!(v.capacity() >= v.len());
+}
+
+#[test]
+fn random_operations_match_std() {
+    for seed in 0..200 {
+        let mut rng = Rng::new(seed);
+        let mut v = SmallVec::new();
+        let mut m = StdVec::new();
+        for _ in 0..100 {
+            step(&mut rng, &mut v, &mut m);
+        }
+    }
+}
+
+#[test]
+fn spill_copies_the_inline_elements() {
+    let mut v: SmallVec<i32, 2> = SmallVec::new();
+    v.push(1);
+    v.push(2);
+    assert!(!v.spilled());
+    v.push(3);
+    assert!(v.spilled());
+    assert_eq!(v, [1, 2, 3]);
+    assert!(v.capacity() >= 4);
+
+    let mut v: SmallVec<String, 2> = SmallVec::new();
+    v.push("a".to_string());
+    v.reserve_exact(5);
+    assert!(v.spilled());
+    assert_eq!(v.capacity(), 6);
+    v.insert(0, "b".to_string());
+    assert_eq!(v, ["b", "a"]);
+}
+
+#[test]
+fn shrink_to_fit_moves_the_elements_back_inline() {
+    let mut v: SmallVec<String, 3> = (0..10).map(|i| i.to_string()).collect();
+    assert!(v.spilled());
+    v.truncate(3);
+    v.shrink_to_fit();
+    assert!(!v.spilled());
+    assert_eq!(v.capacity(), 3);
+    assert_eq!(v, ["0", "1", "2"]);
+
+    // Too many to fit inline: the heap buffer only shrinks.
+    let mut v: SmallVec<u8, 2> = SmallVec::with_capacity(10);
+    v.extend_from_slice(&[1, 2, 3]);
+    v.shrink_to_fit();
+    assert!(v.spilled());
+    assert_eq!(v.capacity(), 3);
+}
+
+#[test]
+fn drain_keeps_the_tail_when_leaked_or_dropped_early() {
+    let mut v: SmallVec<String, 2> = (0..6).map(|i| i.to_string()).collect();
+    let mut drain = v.drain(1..4);
+    assert_eq!(drain.next().as_deref(), Some("1"));
+    drop(drain);
+    assert_eq!(v, ["0", "4", "5"]);
+
+    // A leaked `Drain` leaks the drained elements and the tail, never more.
+    let mut v: SmallVec<String, 2> = (0..6).map(|i| i.to_string()).collect();
+    mem::forget(v.drain(2..4));
+    assert_eq!(v, ["0", "1"]);
+}
+
+#[test]
+fn into_vec_and_from_vec() {
+    // Inline: the elements are copied into a new buffer.
+    let v: SmallVec<String, 4> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+    let vec = v.into_vec();
+    assert_eq!(vec, ["a", "b"]);
+    assert_eq!(vec.capacity(), 2);
+
+    // Spilled: the heap buffer is handed over.
+    let v: SmallVec<u32, 2> = (0..5).collect();
+    let ptr = v.as_ptr();
+    let vec = v.into_vec();
+    assert_eq!(vec, [0, 1, 2, 3, 4]);
+    assert_eq!(vec.as_ptr(), ptr);
+
+    let vec: Vec<u32> = (0..5).collect();
+    let ptr = vec.as_ptr();
+    let v: SmallVec<u32, 2> = SmallVec::from_vec(vec);
+    assert!(v.spilled());
+    assert_eq!(v.as_ptr(), ptr);
+
+    let vec: Vec<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+    let v: SmallVec<String, 2> = SmallVec::from_vec(vec);
+    assert!(!v.spilled());
+    assert_eq!(v, ["a", "b"]);
+}
+
+#[test]
+fn zero_sized_types_never_spill() {
+    let mut v: SmallVec<(), 2> = SmallVec::new();
+    for _ in 0..100 {
+        v.push(());
+    }
+    assert!(!v.spilled());
+    assert_eq!(v.len(), 100);
+    assert_eq!(v.drain(10..20).count(), 10);
+    assert_eq!(v.len(), 90);
+    assert_eq!(v.pop(), Some(()));
+    assert_eq!(v.clone().into_iter().count(), 89);
+
+    let vec = v.into_vec();
+    assert_eq!(vec.len(), 89);
+    let v: SmallVec<(), 2> = SmallVec::from_vec(vec);
+    assert!(!v.spilled());
+    assert_eq!(v.len(), 89);
+}
+
+#[test]
+fn each_element_is_dropped_once() {
+    for len in 0..8 {
+        let (_, n) = drops(|| {
+            let mut v: SmallVec<Counted, 3> = (0..len).map(|_| Counted(0)).collect();
+            v.shrink_to_fit();
+            let mut iter = v.into_iter();
+            drop(iter.next());
+        });
+        assert_eq!(n, len);
+    }
+}
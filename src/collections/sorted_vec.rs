@@ -0,0 +1,460 @@
+//! A set kept as a sorted vector, written `SortedVec<T>`.
+//!
+//! A [`SortedVec`] stores its elements in ascending order in a single
+//! [`Vec`], without duplicates. Lookups are binary searches over one
+//! contiguous buffer, so for small, read-heavy sets they beat a balanced
+//! tree, which follows a pointer per level. The price is that [`insert`]
+//! and [`remove`] shift the elements after the position they touch, making
+//! them *O*(*n*).
+//!
+//! Adding many elements at once is cheaper with [`extend`], which appends
+//! them all, sorts once and removes the duplicates, or with [`merge`] when
+//! they are already in a `SortedVec`.
+//!
+//! See [`flat_map`](crate::flat_map) for the map built the same way.
+//!
+//! # Examples
+//!
+//! ```
+//! use rusty_collections::sorted_vec::SortedVec;
+//!
+//! let mut set: SortedVec<i32> = [5, 1, 4, 1].into_iter().collect();
+//! assert_eq!(set, [1, 4, 5]);
+//!
+//! assert!(set.insert(3));
+//! assert!(!set.insert(3));
+//! assert!(set.contains(&4));
+//! assert_eq!(set.range(2..5), [3, 4]);
+//!
+//! set.extend([9, 0, 4]);
+//! assert_eq!(set, [0, 1, 3, 4, 5, 9]);
+//! ```
+//!
+//! [`insert`]: SortedVec::insert
+//! [`remove`]: SortedVec::remove
+//! [`extend`]: Extend::extend
+//! [`merge`]: SortedVec::merge
+
+use core::borrow::Borrow;
+use core::cmp::{self, Ordering};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::{Bound, Deref, Range, RangeBounds};
+use core::slice;
+
+use crate::algorithms::{partition_point, timsort_by};
+use crate::vec::{self, Vec};
+
+#[cfg(test)]
+mod tests;
+
+/// A set of ordered elements stored in a sorted vector.
+///
+/// `SortedVec<T>` derefs to the sorted `[T]` for reading. It can only be
+/// changed through methods that keep it sorted.
+///
+/// See the [module documentation](self) for an overview.
+pub struct SortedVec<T> {
+    vec: Vec<T>,
+}
+
+impl<T> SortedVec<T> {
+    /// Constructs a new, empty `SortedVec<T>`.
+    pub fn new() -> Self {
+        SortedVec { vec: Vec::new() }
+    }
+
+    /// Constructs a new, empty `SortedVec<T>` with room for `capacity`
+    /// elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        SortedVec { vec: Vec::with_capacity(capacity) }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns the number of elements the set can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Extracts a slice containing the elements in ascending order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.vec
+    }
+
+    /// Removes all elements.
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+
+    /// Removes and returns the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_index(&mut self, index: usize) -> T {
+        self.vec.remove(index)
+    }
+
+    /// Retains only the elements for which `f` returns `true`.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.vec.retain(f);
+    }
+
+    /// Converts the set into a `Vec` of its elements in ascending order,
+    /// without copying them.
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec
+    }
+}
+
+impl<T: Ord> SortedVec<T> {
+    /// Adds `value` to the set.
+    ///
+    /// Returns `false`, and leaves the set unchanged, if an equal element is
+    /// already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.vec.binary_search(&value) {
+            Ok(_) => false,
+            Err(index) => {
+                self.vec.insert(index, value);
+                true
+            }
+        }
+    }
+
+    /// Adds `value` to the set, replacing and returning the equal element
+    /// that was already present, if any.
+    pub fn replace(&mut self, value: T) -> Option<T> {
+        match self.vec.binary_search(&value) {
+            Ok(index) => Some(core::mem::replace(&mut self.vec[index], value)),
+            Err(index) => {
+                self.vec.insert(index, value);
+                None
+            }
+        }
+    }
+
+    /// Removes and returns the element equal to `value`, if any.
+    pub fn remove<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.index_of(value)?;
+        Some(self.vec.remove(index))
+    }
+
+    /// Returns a reference to the element equal to `value`, if any.
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.index_of(value).map(|index| &self.vec[index])
+    }
+
+    /// Returns `true` if the set contains an element equal to `value`.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.index_of(value).is_some()
+    }
+
+    /// Returns the index of the element equal to `value`, if any.
+    pub fn index_of<Q>(&self, value: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.lower_bound(value);
+        match self.vec.get(index) {
+            Some(elem) if elem.borrow() == value => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the first element that is not less than
+    /// `value`, or `len()` if there is none.
+    pub fn lower_bound<Q>(&self, value: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        partition_point(&self.vec, |elem| elem.borrow() < value)
+    }
+
+    /// Returns the index of the first element that is greater than
+    /// `value`, or `len()` if there is none.
+    pub fn upper_bound<Q>(&self, value: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        partition_point(&self.vec, |elem| elem.borrow() <= value)
+    }
+
+    /// Returns the elements within `range`, in ascending order.
+    ///
+    /// A range whose start lies after its end is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::sorted_vec::SortedVec;
+    ///
+    /// let set: SortedVec<i32> = (0..10).collect();
+    /// assert_eq!(set.range(3..=5), [3, 4, 5]);
+    /// assert_eq!(set.range(8..), [8, 9]);
+    /// assert!(set.range(5..3).is_empty());
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> &[T]
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        &self.vec[range_indices(&self.vec, &range, T::borrow)]
+    }
+
+    /// Moves every element of `other` into `self`, merging the two sorted
+    /// sequences in linear time.
+    ///
+    /// Of two equal elements, the one already in `self` is kept.
+    pub fn merge(&mut self, other: SortedVec<T>) {
+        let vec = core::mem::take(&mut self.vec);
+        self.vec = merge_by(vec, other.vec, T::cmp, |_, _| {});
+    }
+}
+
+/// Returns the indices of the elements of the sorted `v` whose keys lie in
+/// `range`.
+pub(crate) fn range_indices<T, Q, R, F>(v: &[T], range: &R, key: F) -> Range<usize>
+where
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+    F: Fn(&T) -> &Q,
+{
+    let start = match range.start_bound() {
+        Bound::Included(start) => partition_point(v, |elem| key(elem) < start),
+        Bound::Excluded(start) => partition_point(v, |elem| key(elem) <= start),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => partition_point(v, |elem| key(elem) <= end),
+        Bound::Excluded(end) => partition_point(v, |elem| key(elem) < end),
+        Bound::Unbounded => v.len(),
+    };
+    start..cmp::max(start, end)
+}
+
+/// Merges two vectors sorted by `compare` into one.
+///
+/// Both inputs must be free of duplicates. When an element of `a` and an
+/// element of `b` are equal, only the one from `a` is kept, after
+/// `on_equal` has been given the chance to take over parts of the other.
+pub(crate) fn merge_by<T, C, E>(a: Vec<T>, b: Vec<T>, mut compare: C, mut on_equal: E) -> Vec<T>
+where
+    C: FnMut(&T, &T) -> Ordering,
+    E: FnMut(&mut T, T),
+{
+    if b.is_empty() {
+        return a;
+    }
+    if a.is_empty() {
+        return b;
+    }
+
+    let mut out = Vec::with_capacity(a.len() + b.len());
+    let mut a = a.into_iter().peekable();
+    let mut b = b.into_iter().peekable();
+    while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+        match compare(x, y) {
+            Ordering::Less => out.push(a.next().unwrap()),
+            Ordering::Greater => out.push(b.next().unwrap()),
+            Ordering::Equal => {
+                let mut kept = a.next().unwrap();
+                on_equal(&mut kept, b.next().unwrap());
+                out.push(kept);
+            }
+        }
+    }
+    out.extend(a);
+    out.extend(b);
+    out
+}
+
+/// Appends `iter` to the vector sorted by `compare`, then sorts it again
+/// and removes the duplicates.
+///
+/// The sort is stable, so `same_bucket` sees every run of equal elements
+/// in the order they were added, as `(later, earlier)`, and the earliest
+/// one is kept.
+pub(crate) fn extend_sorted<T, I, C, S>(vec: &mut Vec<T>, iter: I, mut compare: C, same_bucket: S)
+where
+    I: IntoIterator<Item = T>,
+    C: FnMut(&T, &T) -> Ordering,
+    S: FnMut(&mut T, &mut T) -> bool,
+{
+    let sorted = vec.len();
+    vec.extend(iter);
+    if vec.len() == sorted {
+        return;
+    }
+    // The old elements form one run, so timsort only has to sort the new
+    // ones and merge the two.
+    timsort_by(vec, &mut compare);
+    vec.dedup_by(same_bucket);
+}
+
+impl<T: Clone> Clone for SortedVec<T> {
+    fn clone(&self) -> Self {
+        SortedVec { vec: self.vec.clone() }
+    }
+
+    fn clone_from(&mut self, other: &Self) {
+        self.vec.clone_from(&other.vec);
+    }
+}
+
+impl<T> Default for SortedVec<T> {
+    fn default() -> Self {
+        SortedVec::new()
+    }
+}
+
+impl<T> Deref for SortedVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+impl<T> AsRef<[T]> for SortedVec<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+impl<T> Borrow<[T]> for SortedVec<T> {
+    fn borrow(&self) -> &[T] {
+        &self.vec
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SortedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Hash> Hash for SortedVec<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<SortedVec<U>> for SortedVec<T> {
+    fn eq(&self, other: &SortedVec<U>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for SortedVec<T> {
+    fn eq(&self, other: &[U]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for SortedVec<T> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T: Eq> Eq for SortedVec<T> {}
+
+impl<T: PartialOrd> PartialOrd for SortedVec<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<T: Ord> Ord for SortedVec<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for SortedVec<T> {
+    /// Sorts the vector in place and removes its duplicates, keeping the
+    /// first of each run of equal elements.
+    fn from(mut vec: Vec<T>) -> Self {
+        timsort_by(&mut vec, T::cmp);
+        vec.dedup_by(|a, b| a == b);
+        SortedVec { vec }
+    }
+}
+
+impl<T> From<SortedVec<T>> for Vec<T> {
+    fn from(set: SortedVec<T>) -> Self {
+        set.vec
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SortedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        SortedVec::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
+impl<T: Ord> Extend<T> for SortedVec<T> {
+    /// Adds every element of `iter` to the set.
+    ///
+    /// The elements are appended and the vector is sorted once, instead of
+    /// inserting them one by one. Elements equal to one already in the set,
+    /// or to one earlier in `iter`, are dropped.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        extend_sorted(&mut self.vec, iter, T::cmp, |a, b| a == b);
+    }
+}
+
+impl<'a, T: Ord + Copy + 'a> Extend<&'a T> for SortedVec<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
+}
+
+impl<T> IntoIterator for SortedVec<T> {
+    type Item = T;
+    type IntoIter = vec::IntoIter<T>;
+
+    fn into_iter(self) -> vec::IntoIter<T> {
+        self.vec.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SortedVec<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
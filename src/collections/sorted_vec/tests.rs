@@ -0,0 +1,91 @@
+use super::*;
+
+use std::collections::BTreeSet;
+use std::vec::Vec as StdVec;
+
+use crate::test_util::Rng;
+
+// A value from a small range, so that values collide often.
+fn random_value(rng: &mut Rng) -> u32 {
+    (rng.next() % 32) as u32
+}
+
+fn random_values(rng: &mut Rng) -> StdVec<u32> {
+    let len = rng.next() % 12;
+    (0..len).map(|_| random_value(rng)).collect()
+}
+
+// Applies one random operation to both sets and checks that they agree on
+// what it returned.
+fn step(rng: &mut Rng, set: &mut SortedVec<u32>, model: &mut BTreeSet<u32>) {
+    let x = random_value(rng);
+    match rng.next() % 7 {
+        0 | 1 => assert_eq!(set.insert(x), model.insert(x)),
+        2 => assert_eq!(set.remove(&x), model.take(&x)),
+        3 => {
+            let values = random_values(rng);
+            set.extend(values.iter().copied());
+            model.extend(values);
+        }
+        4 => {
+            let values = random_values(rng);
+            set.merge(values.iter().copied().collect());
+            model.extend(values);
+        }
+        5 => {
+            let end = random_value(rng);
+            let expected: StdVec<_> =
+                if x <= end { model.range(x..end).copied().collect() } else { StdVec::new() };
+            assert_eq!(set.range(x..end), expected);
+        }
+        _ => {
+            assert_eq!(set.lower_bound(&x), model.range(..x).count());
+            assert_eq!(set.upper_bound(&x), model.range(..=x).count());
+            assert_eq!(set.get(&x), model.get(&x));
+        }
+    }
+}
+
+#[test]
+fn random_operations_match_btree_set() {
+    for seed in 0..200 {
+        let mut rng = Rng::new(seed);
+        let mut set = SortedVec::new();
+        let mut model = BTreeSet::new();
+        for _ in 0..200 {
+            step(&mut rng, &mut set, &mut model);
+            assert!(set.iter().eq(model.iter()), "seed {}", seed);
+        }
+    }
+}
+
+// Of equal elements, the one that was added first is the one kept.
+#[test]
+fn extend_and_merge_keep_the_existing_element() {
+    // Compares by the first field only.
+    #[derive(Debug)]
+    struct Tagged(u32, &'static str);
+    impl PartialEq for Tagged {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+    impl Eq for Tagged {}
+    impl PartialOrd for Tagged {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for Tagged {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+
+    let mut set: SortedVec<_> =
+        [Tagged(2, "a"), Tagged(1, "a"), Tagged(2, "b")].into_iter().collect();
+    set.extend([Tagged(1, "c"), Tagged(3, "c"), Tagged(3, "d")]);
+    set.merge([Tagged(3, "e"), Tagged(4, "e")].into_iter().collect());
+    let tags: StdVec<_> = set.iter().map(|t| (t.0, t.1)).collect();
+    assert_eq!(tags, [(1, "a"), (2, "a"), (3, "c"), (4, "e")]);
+}
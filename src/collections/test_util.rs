@@ -0,0 +1,74 @@
+//! Helpers shared by the tests of every container.
+
+use core::ops::Range;
+use std::cell::Cell;
+
+/// A small xorshift generator, so the tests are reproducible without
+/// pulling in a dependency.
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Rng {
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..n`.
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    /// A range inside `0..len`.
+    pub(crate) fn range(&mut self, len: usize) -> Range<usize> {
+        let start = self.below(len + 1);
+        let end = start + self.below(len - start + 1);
+        start..end
+    }
+}
+
+thread_local! {
+    static CLONES: Cell<usize> = const { Cell::new(0) };
+    static DROPS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// An element that counts how often it is cloned and dropped, so the tests
+/// can tell a copy from a move and catch double drops and leaks.
+///
+/// The counts are kept per thread; see [`clones`] and [`drops`].
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Counted(pub(crate) u32);
+
+impl Clone for Counted {
+    fn clone(&self) -> Counted {
+        CLONES.with(|c| c.set(c.get() + 1));
+        Counted(self.0)
+    }
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        DROPS.with(|d| d.set(d.get() + 1));
+    }
+}
+
+/// Runs `f`, and returns its result and the number of [`Counted`] values
+/// cloned on this thread meanwhile.
+pub(crate) fn clones<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = CLONES.with(Cell::get);
+    let result = f();
+    (result, CLONES.with(Cell::get) - before)
+}
+
+/// Runs `f`, and returns its result and the number of [`Counted`] values
+/// dropped on this thread meanwhile.
+pub(crate) fn drops<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = DROPS.with(Cell::get);
+    let result = f();
+    (result, DROPS.with(Cell::get) - before)
+}
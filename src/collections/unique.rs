@@ -0,0 +1,80 @@
+//! A stable stand-in for the unstable `core::ptr::Unique`.
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+/// A wrapper around a raw non-null `*mut T` that indicates that the
+/// possessor of this wrapper owns the referent.
+///
+/// Unlike `*mut T`, `Unique<T>` is covariant over `T`, and is `Send`/`Sync`
+/// if `T` is, like the owning containers built on it. The `PhantomData`
+/// tells the drop checker that a `T` may be dropped along with it.
+#[repr(transparent)]
+pub(crate) struct Unique<T: ?Sized> {
+    pointer: NonNull<T>,
+    _marker: PhantomData<T>,
+}
+
+/// `Unique` pointers are `Send` if `T` is `Send` because the data they
+/// reference is unaliased.
+unsafe impl<T: Send + ?Sized> Send for Unique<T> {}
+
+/// `Unique` pointers are `Sync` if `T` is `Sync` because the data they
+/// reference is unaliased.
+unsafe impl<T: Sync + ?Sized> Sync for Unique<T> {}
+
+impl<T> Unique<T> {
+    /// Creates a new `Unique` that is dangling, but well-aligned.
+    #[inline]
+    pub(crate) const fn dangling() -> Self {
+        Unique { pointer: NonNull::dangling(), _marker: PhantomData }
+    }
+}
+
+impl<T: ?Sized> Unique<T> {
+    /// Creates a new `Unique`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null.
+    #[inline]
+    pub(crate) const unsafe fn new_unchecked(ptr: *mut T) -> Self {
+        // SAFETY: the caller must guarantee that `ptr` is non-null.
+        unsafe { Unique { pointer: NonNull::new_unchecked(ptr), _marker: PhantomData } }
+    }
+
+    /// Acquires the underlying `*mut` pointer.
+    #[inline]
+    pub(crate) const fn as_ptr(self) -> *mut T {
+        self.pointer.as_ptr()
+    }
+
+    /// Casts to a pointer of another type.
+    #[inline]
+    pub(crate) const fn cast<U>(self) -> Unique<U> {
+        Unique { pointer: self.pointer.cast(), _marker: PhantomData }
+    }
+}
+
+impl<T: ?Sized> Clone for Unique<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for Unique<T> {}
+
+impl<T: ?Sized> fmt::Debug for Unique<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.pointer, f)
+    }
+}
+
+impl<T: ?Sized> From<Unique<T>> for NonNull<T> {
+    #[inline]
+    fn from(unique: Unique<T>) -> Self {
+        unique.pointer
+    }
+}
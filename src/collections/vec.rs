@@ -12,12 +12,14 @@
 //! You can explicitly create a [`Vec`] with [`Vec::new`]:
 //!
 //! ```
+//! # use rusty_collections::{vec, vec::Vec};
 //! let v: Vec<i32> = Vec::new();
 //! ```
 //!
 //! ...or by using the [`vec!`] macro:
 //!
 //! ```
+//! # use rusty_collections::{vec, vec::Vec};
 //! let v: Vec<i32> = vec![];
 //!
 //! let v = vec![1, 2, 3, 4, 5];
@@ -29,6 +31,7 @@
 //! as needed):
 //!
 //! ```
+//! # use rusty_collections::{vec, vec::Vec};
 //! let mut v = vec![1, 2];
 //!
 //! v.push(3);
@@ -37,6 +40,7 @@
 //! Popping values works in much the same way:
 //!
 //! ```
+//! # use rusty_collections::{vec, vec::Vec};
 //! let mut v = vec![1, 2];
 //!
 //! let two = v.pop();
@@ -45,6 +49,7 @@
 //! Vectors also support indexing (through the [`Index`] and [`IndexMut`] traits):
 //!
 //! ```
+//! # use rusty_collections::{vec, vec::Vec};
 //! let mut v = vec![1, 2, 3];
 //! let three = v[2];
 //! v[1] = v[1] + 5;
@@ -52,32 +57,39 @@
 //!
 //! [`push`]: Vec::push
 
-#![stable(feature = "rust1", since = "1.0.0")]
-
+use core::any::TypeId;
 use core::cmp::{self, Ordering};
 use core::convert::TryFrom;
 use core::fmt;
 use core::hash::{Hash, Hasher};
-use core::intrinsics::{arith_offset, assume};
-use core::iter::{
-    FromIterator, FusedIterator, InPlaceIterable, SourceIter, TrustedLen, TrustedRandomAccess,
-};
+use core::hint;
+use core::iter::{FromIterator, FusedIterator};
 use core::marker::PhantomData;
 use core::mem::{self, ManuallyDrop, MaybeUninit};
-use core::ops::{self, Index, IndexMut, Range, RangeBounds};
+use core::ops::{self, Bound, Index, IndexMut, Range, RangeBounds};
 use core::ptr::{self, NonNull};
 use core::slice::{self, SliceIndex};
 
-use crate::borrow::{Cow, ToOwned};
-use crate::boxed::Box;
-use crate::collections::TryReserveError;
-use crate::raw_vec::RawVec;
+use std::borrow::Cow;
+use std::io;
+
+use crate::alloc::{Allocator, Global, TryReserveError};
+use crate::pod::Zeroable;
+use crate::raw_vec::{debug_memory, RawVec};
+
+pub use crate::raw_vec::{Chunked, Doubling, GrowWith, GrowthPolicy, OneAndHalf, PageRounded};
+
+pub use crate::raw_vec::telemetry;
+
+#[cfg(test)]
+mod tests;
 
 /// A contiguous growable array type, written `Vec<T>` but pronounced 'vector'.
 ///
 /// # Examples
 ///
 /// ```
+/// # use rusty_collections::{vec, vec::Vec};
 /// let mut vec = Vec::new();
 /// vec.push(1);
 /// vec.push(2);
@@ -102,6 +114,7 @@ use crate::raw_vec::RawVec;
 /// The [`vec!`] macro is provided to make initialization more convenient:
 ///
 /// ```
+/// # use rusty_collections::{vec, vec::Vec};
 /// let mut vec = vec![1, 2, 3];
 /// vec.push(4);
 /// assert_eq!(vec, [1, 2, 3, 4]);
@@ -112,6 +125,7 @@ use crate::raw_vec::RawVec;
 /// in separate steps, especially when initializing a vector of zeros:
 ///
 /// ```
+/// # use rusty_collections::{vec, vec::Vec};
 /// let vec = vec![0; 5];
 /// assert_eq!(vec, [0, 0, 0, 0, 0]);
 ///
@@ -127,6 +141,7 @@ use crate::raw_vec::RawVec;
 /// Use a `Vec<T>` as an efficient stack:
 ///
 /// ```
+/// # use rusty_collections::{vec, vec::Vec};
 /// let mut stack = Vec::new();
 ///
 /// stack.push(1);
@@ -145,6 +160,7 @@ use crate::raw_vec::RawVec;
 /// [`Index`] trait. An example will be more explicit:
 ///
 /// ```
+/// # use rusty_collections::{vec, vec::Vec};
 /// let v = vec![0, 2, 4, 6];
 /// println!("{}", v[1]); // it will display '2'
 /// ```
@@ -153,6 +169,7 @@ use crate::raw_vec::RawVec;
 /// your software will panic! You cannot do this:
 ///
 /// ```should_panic
+/// # use rusty_collections::{vec, vec::Vec};
 /// let v = vec![0, 2, 4, 6];
 /// println!("{}", v[6]); // it will panic!
 /// ```
@@ -166,6 +183,7 @@ use crate::raw_vec::RawVec;
 /// To get a [slice], use [`&`]. Example:
 ///
 /// ```
+/// # use rusty_collections::{vec, vec::Vec};
 /// fn read_slice(slice: &[usize]) {
 ///     // ...
 /// }
@@ -260,6 +278,9 @@ use crate::raw_vec::RawVec;
 /// when full, nor when [`reserve`] is called. The current strategy is basic
 /// and it may prove desirable to use a non-constant growth factor. Whatever
 /// strategy is used will of course guarantee *O*(1) amortized [`push`].
+/// This only holds for the default [`Doubling`] policy; a vector with a
+/// different [`GrowthPolicy`] (see [`Vec::with_growth`]) grows however that
+/// policy says.
 ///
 /// `vec![x; n]`, `vec![a, b, c, d]`, and
 /// [`Vec::with_capacity(n)`][`Vec::with_capacity`], will all produce a `Vec`
@@ -295,10 +316,12 @@ use crate::raw_vec::RawVec;
 /// [owned slice]: Box
 /// [slice]: ../../std/primitive.slice.html
 /// [`&`]: ../../std/primitive.reference.html
-#[stable(feature = "rust1", since = "1.0.0")]
-#[cfg_attr(not(test), rustc_diagnostic_item = "vec_type")]
-pub struct Vec<T> {
-    buf: RawVec<T>,
+pub struct Vec<
+    T,
+    A: Allocator = Global,
+    G: GrowthPolicy = Doubling,
+> {
+    buf: RawVec<T, A, G>,
     len: usize,
 }
 
@@ -315,11 +338,10 @@ impl<T> Vec<T> {
     ///
     /// ```
     /// # #![allow(unused_mut)]
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec: Vec<i32> = Vec::new();
     /// ```
     #[inline]
-    #[rustc_const_stable(feature = "const_vec_new", since = "1.39.0")]
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub const fn new() -> Vec<T> {
         Vec { buf: RawVec::NEW, len: 0 }
     }
@@ -339,6 +361,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = Vec::with_capacity(10);
     ///
     /// // The vector contains no items, even though it has capacity for more
@@ -358,47 +381,64 @@ impl<T> Vec<T> {
     /// assert!(vec.capacity() >= 11);
     /// ```
     #[inline]
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn with_capacity(capacity: usize) -> Vec<T> {
         Vec { buf: RawVec::with_capacity(capacity), len: 0 }
     }
 
-    /// Decomposes a `Vec<T>` into its raw components.
+    /// Constructs a new, empty `Vec<T>` with the specified capacity, returning
+    /// an error instead of aborting if the allocation fails.
     ///
-    /// Returns the raw pointer to the underlying data, the length of
-    /// the vector (in elements), and the allocated capacity of the
-    /// data (in elements). These are the same arguments in the same
-    /// order as the arguments to [`from_raw_parts`].
+    /// See [`Vec::with_capacity`] for how the capacity is used.
     ///
-    /// After calling this function, the caller is responsible for the
-    /// memory previously managed by the `Vec`. The only way to do
-    /// this is to convert the raw pointer, length, and capacity back
-    /// into a `Vec` with the [`from_raw_parts`] function, allowing
-    /// the destructor to perform the cleanup.
+    /// # Errors
     ///
-    /// [`from_raw_parts`]: Vec::from_raw_parts
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
     ///
     /// # Examples
     ///
     /// ```
-    /// #![feature(vec_into_raw_parts)]
-    /// let v: Vec<i32> = vec![-1, 0, 1];
+    /// use rusty_collections::vec::Vec;
     ///
-    /// let (ptr, len, cap) = v.into_raw_parts();
+    /// let vec: Vec<u32> = Vec::try_with_capacity(10).unwrap();
+    /// assert_eq!(vec.capacity(), 10);
     ///
-    /// let rebuilt = unsafe {
-    ///     // We can now make changes to the components, such as
-    ///     // transmuting the raw pointer to a compatible type.
-    ///     let ptr = ptr as *mut u32;
+    /// assert!(Vec::<u32>::try_with_capacity(usize::MAX).is_err());
+    /// ```
+    #[inline]
+    pub fn try_with_capacity(capacity: usize) -> Result<Vec<T>, TryReserveError> {
+        Vec::try_with_capacity_in(capacity, Global)
+    }
+
+    /// Creates a vector from an iterator, returning an error instead of
+    /// aborting if an allocation fails.
+    ///
+    /// This is the fallible counterpart of [`Iterator::collect`]. Elements
+    /// already taken from the iterator are dropped on failure.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
     ///
-    ///     Vec::from_raw_parts(ptr, len, cap)
-    /// };
-    /// assert_eq!(rebuilt, [4294967295, 0, 1]);
     /// ```
-    #[unstable(feature = "vec_into_raw_parts", reason = "new API", issue = "65816")]
-    pub fn into_raw_parts(self) -> (*mut T, usize, usize) {
-        let mut me = ManuallyDrop::new(self);
-        (me.as_mut_ptr(), me.len(), me.capacity())
+    /// use rusty_collections::vec::Vec;
+    ///
+    /// let vec = Vec::try_collect((1..=3).map(|x| x * 10)).unwrap();
+    /// assert_eq!(vec, [10, 20, 30]);
+    /// ```
+    pub fn try_collect<I>(iter: I) -> Result<Vec<T>, TryReserveError>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let iterator = iter.into_iter();
+        let mut vec = Vec::new();
+        let (lower, _) = iterator.size_hint();
+        vec.try_reserve(lower)?;
+        vec.try_extend_desugared(iterator)?;
+        Ok(vec)
     }
 
     /// Creates a `Vec<T>` directly from the raw components of another vector.
@@ -437,6 +477,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// use std::ptr;
     /// use std::mem;
     ///
@@ -463,9 +504,271 @@ impl<T> Vec<T> {
     ///     assert_eq!(rebuilt, [4, 5, 6]);
     /// }
     /// ```
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub unsafe fn from_raw_parts(ptr: *mut T, length: usize, capacity: usize) -> Vec<T> {
-        unsafe { Vec { buf: RawVec::from_raw_parts(ptr, capacity), len: length } }
+        // Checked before the `Vec` exists, so that a panic leaves the buffer
+        // to the caller instead of freeing it.
+        unsafe {
+            debug_memory::check_initialized(ptr, 0..length, "from_raw_parts");
+            Vec { buf: RawVec::from_raw_parts(ptr, capacity), len: length }
+        }
+    }
+}
+
+impl<T, A: Allocator> Vec<T, A> {
+    /// Constructs a new, empty `Vec<T, A>`.
+    ///
+    /// The vector will not allocate until elements are pushed onto it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::alloc::Global;
+    /// use rusty_collections::vec::Vec;
+    ///
+    /// # #[allow(unused_mut)]
+    /// let mut vec: Vec<i32, _> = Vec::new_in(Global);
+    /// ```
+    #[inline]
+    pub const fn new_in(alloc: A) -> Self {
+        Vec { buf: RawVec::new_in(alloc), len: 0 }
+    }
+
+    /// Constructs a new, empty `Vec<T, A>` with the specified capacity with the provided
+    /// allocator.
+    ///
+    /// The vector will be able to hold exactly `capacity` elements without
+    /// reallocating. If `capacity` is 0, the vector will not allocate.
+    ///
+    /// It is important to note that although the returned vector has the
+    /// *capacity* specified, the vector will have a zero *length*. For an
+    /// explanation of the difference between length and capacity, see
+    /// *[Capacity and reallocation]*.
+    ///
+    /// [Capacity and reallocation]: #capacity-and-reallocation
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::alloc::Global;
+    /// use rusty_collections::vec::Vec;
+    ///
+    /// let mut vec = Vec::with_capacity_in(10, Global);
+    ///
+    /// // The vector contains no items, even though it has capacity for more
+    /// assert_eq!(vec.len(), 0);
+    /// assert_eq!(vec.capacity(), 10);
+    ///
+    /// // These are all done without reallocating...
+    /// for i in 0..10 {
+    ///     vec.push(i);
+    /// }
+    /// assert_eq!(vec.len(), 10);
+    /// assert_eq!(vec.capacity(), 10);
+    ///
+    /// // ...but this may make the vector reallocate
+    /// vec.push(11);
+    /// assert_eq!(vec.len(), 11);
+    /// assert!(vec.capacity() >= 11);
+    /// ```
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Vec { buf: RawVec::with_capacity_in(capacity, alloc), len: 0 }
+    }
+
+    /// Like [`try_with_capacity`], but parameterized over the choice of
+    /// allocator for the returned `Vec`.
+    ///
+    /// [`try_with_capacity`]: Vec::try_with_capacity
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    #[inline]
+    pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, TryReserveError> {
+        Ok(Vec { buf: RawVec::try_with_capacity_in(capacity, alloc)?, len: 0 })
+    }
+
+    /// Creates a `Vec<T, A>` directly from the raw components of another vector.
+    ///
+    /// # Safety
+    ///
+    /// This is highly unsafe, due to the number of invariants that aren't
+    /// checked:
+    ///
+    /// * `ptr` needs to have been previously allocated via [`String`]/`Vec<T>`
+    ///   (at least, it's highly likely to be incorrect if it wasn't).
+    /// * `T` needs to have the same size and alignment as what `ptr` was allocated with.
+    ///   (`T` having a less strict alignment is not sufficient, the alignment really
+    ///   needs to be equal to satisfy the [`dealloc`] requirement that memory must be
+    ///   allocated and deallocated with the same layout.)
+    /// * `length` needs to be less than or equal to `capacity`.
+    /// * `capacity` needs to be the capacity that the pointer was allocated with.
+    /// * `ptr` needs to have been allocated by `alloc`.
+    ///
+    /// Violating these may cause problems like corrupting the allocator's
+    /// internal data structures. For example it is **not** safe
+    /// to build a `Vec<u8>` from a pointer to a C `char` array with length `size_t`.
+    /// It's also not safe to build one from a `Vec<u16>` and its length, because
+    /// the allocator cares about the alignment, and these two types have different
+    /// alignments. The buffer was allocated with alignment 2 (for `u16`), but after
+    /// turning it into a `Vec<u8>` it'll be deallocated with alignment 1.
+    ///
+    /// The ownership of `ptr` is effectively transferred to the
+    /// `Vec<T>` which may then deallocate, reallocate or change the
+    /// contents of memory pointed to by the pointer at will. Ensure
+    /// that nothing else uses the pointer after calling this
+    /// function.
+    ///
+    /// [`String`]: crate::string::String
+    /// [`dealloc`]: crate::alloc::GlobalAlloc::dealloc
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::alloc::Global;
+    /// use rusty_collections::vec::Vec;
+    ///
+    /// use std::ptr;
+    /// use std::mem;
+    ///
+    /// let mut v = Vec::with_capacity_in(3, Global);
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    ///
+    // FIXME Update this when vec_into_raw_parts is stabilized
+    /// // Prevent running `v`'s destructor so we are in complete control
+    /// // of the allocation.
+    /// let mut v = mem::ManuallyDrop::new(v);
+    ///
+    /// // Pull out the various important pieces of information about `v`
+    /// let p = v.as_mut_ptr();
+    /// let len = v.len();
+    /// let cap = v.capacity();
+    /// let alloc = v.allocator();
+    ///
+    /// unsafe {
+    ///     // Overwrite memory with 4, 5, 6
+    ///     for i in 0..len as isize {
+    ///         ptr::write(p.offset(i), 4 + i);
+    ///     }
+    ///
+    ///     // Put everything back together into a Vec
+    ///     let rebuilt = Vec::from_raw_parts_in(p, len, cap, alloc.clone());
+    ///     assert_eq!(rebuilt, [4, 5, 6]);
+    /// }
+    /// ```
+    #[inline]
+    pub unsafe fn from_raw_parts_in(ptr: *mut T, length: usize, capacity: usize, alloc: A) -> Self {
+        // Checked before the `Vec` exists, so that a panic leaves the buffer
+        // to the caller instead of freeing it.
+        unsafe {
+            debug_memory::check_initialized(ptr, 0..length, "from_raw_parts_in");
+            Vec { buf: RawVec::from_raw_parts_in(ptr, capacity, alloc), len: length }
+        }
+    }
+
+    // `from_raw_parts_in` for buffers whose elements the crate initialized
+    // itself. Skips the `debug-memory` check, which would report real data
+    // that happens to look like poison.
+    #[inline]
+    pub(crate) unsafe fn from_raw_parts_unchecked_in(
+        ptr: *mut T,
+        length: usize,
+        capacity: usize,
+        alloc: A,
+    ) -> Self {
+        Vec { buf: unsafe { RawVec::from_raw_parts_in(ptr, capacity, alloc) }, len: length }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Decomposes a `Vec<T>` into its raw components.
+    ///
+    /// Returns the raw pointer to the underlying data, the length of
+    /// the vector (in elements), and the allocated capacity of the
+    /// data (in elements). These are the same arguments in the same
+    /// order as the arguments to [`from_raw_parts`].
+    ///
+    /// After calling this function, the caller is responsible for the
+    /// memory previously managed by the `Vec`. The only way to do
+    /// this is to convert the raw pointer, length, and capacity back
+    /// into a `Vec` with the [`from_raw_parts`] function, allowing
+    /// the destructor to perform the cleanup.
+    ///
+    /// [`from_raw_parts`]: Vec::from_raw_parts
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
+    /// let v: Vec<i32> = vec![-1, 0, 1];
+    ///
+    /// let (ptr, len, cap) = v.into_raw_parts();
+    ///
+    /// let rebuilt = unsafe {
+    ///     // We can now make changes to the components, such as
+    ///     // transmuting the raw pointer to a compatible type.
+    ///     let ptr = ptr as *mut u32;
+    ///
+    ///     Vec::from_raw_parts(ptr, len, cap)
+    /// };
+    /// assert_eq!(rebuilt, [4294967295, 0, 1]);
+    /// ```
+    pub fn into_raw_parts(self) -> (*mut T, usize, usize) {
+        let mut me = ManuallyDrop::new(self);
+        // The growth policy is not part of the raw parts.
+        unsafe { drop(ptr::read(me.growth())) };
+        (me.as_mut_ptr(), me.len(), me.capacity())
+    }
+
+    /// Decomposes a `Vec<T>` into its raw components.
+    ///
+    /// Returns the raw pointer to the underlying data, the length of the vector (in elements),
+    /// the allocated capacity of the data (in elements), and the allocator. These are the same
+    /// arguments in the same order as the arguments to [`from_raw_parts_in`].
+    ///
+    /// After calling this function, the caller is responsible for the
+    /// memory previously managed by the `Vec`. The only way to do
+    /// this is to convert the raw pointer, length, and capacity back
+    /// into a `Vec` with the [`from_raw_parts_in`] function, allowing
+    /// the destructor to perform the cleanup.
+    ///
+    /// [`from_raw_parts_in`]: Vec::from_raw_parts_in
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::alloc::Global;
+    /// use rusty_collections::vec::Vec;
+    ///
+    /// let mut v: Vec<i32, Global> = Vec::new_in(Global);
+    /// v.push(-1);
+    /// v.push(0);
+    /// v.push(1);
+    ///
+    /// let (ptr, len, cap, alloc) = v.into_raw_parts_with_alloc();
+    ///
+    /// let rebuilt = unsafe {
+    ///     // We can now make changes to the components, such as
+    ///     // transmuting the raw pointer to a compatible type.
+    ///     let ptr = ptr as *mut u32;
+    ///
+    ///     Vec::from_raw_parts_in(ptr, len, cap, alloc)
+    /// };
+    /// assert_eq!(rebuilt, [4294967295, 0, 1]);
+    /// ```
+    // #[unstable(feature = "vec_into_raw_parts", reason = "new API", issue = "65816")]
+    pub fn into_raw_parts_with_alloc(self) -> (*mut T, usize, usize, A) {
+        let mut me = ManuallyDrop::new(self);
+        let len = me.len();
+        let capacity = me.capacity();
+        let ptr = me.as_mut_ptr();
+        let alloc = unsafe { ptr::read(me.allocator()) };
+        // The growth policy is not part of the raw parts.
+        unsafe { drop(ptr::read(me.growth())) };
+        (ptr, len, capacity, alloc)
     }
 
     /// Returns the number of elements the vector can hold without
@@ -474,11 +777,11 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let vec: Vec<i32> = Vec::with_capacity(10);
     /// assert_eq!(vec.capacity(), 10);
     /// ```
     #[inline]
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn capacity(&self) -> usize {
         self.buf.capacity()
     }
@@ -496,11 +799,11 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1];
     /// vec.reserve(10);
     /// assert!(vec.capacity() >= 11);
     /// ```
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn reserve(&mut self, additional: usize) {
         self.buf.reserve(self.len, additional);
     }
@@ -521,11 +824,11 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1];
     /// vec.reserve_exact(10);
     /// assert!(vec.capacity() >= 11);
     /// ```
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn reserve_exact(&mut self, additional: usize) {
         self.buf.reserve_exact(self.len, additional);
     }
@@ -544,8 +847,8 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
-    /// #![feature(try_reserve)]
-    /// use std::collections::TryReserveError;
+    /// use rusty_collections::alloc::TryReserveError;
+    /// use rusty_collections::vec::Vec;
     ///
     /// fn process_data(data: &[u32]) -> Result<Vec<u32>, TryReserveError> {
     ///     let mut output = Vec::new();
@@ -562,7 +865,6 @@ impl<T> Vec<T> {
     /// }
     /// # process_data(&[1, 2, 3]).expect("why is the test harness OOMing on 12 bytes?");
     /// ```
-    #[unstable(feature = "try_reserve", reason = "new API", issue = "48043")]
     pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
         self.buf.try_reserve(self.len, additional)
     }
@@ -585,8 +887,8 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
-    /// #![feature(try_reserve)]
-    /// use std::collections::TryReserveError;
+    /// use rusty_collections::alloc::TryReserveError;
+    /// use rusty_collections::vec::Vec;
     ///
     /// fn process_data(data: &[u32]) -> Result<Vec<u32>, TryReserveError> {
     ///     let mut output = Vec::new();
@@ -603,7 +905,6 @@ impl<T> Vec<T> {
     /// }
     /// # process_data(&[1, 2, 3]).expect("why is the test harness OOMing on 12 bytes?");
     /// ```
-    #[unstable(feature = "try_reserve", reason = "new API", issue = "48043")]
     pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
         self.buf.try_reserve_exact(self.len, additional)
     }
@@ -616,19 +917,20 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = Vec::with_capacity(10);
     /// vec.extend([1, 2, 3].iter().cloned());
     /// assert_eq!(vec.capacity(), 10);
     /// vec.shrink_to_fit();
     /// assert!(vec.capacity() >= 3);
     /// ```
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn shrink_to_fit(&mut self) {
         // The capacity is never less than the length, and there's nothing to do when
         // they are equal, so we can avoid the panic case in `RawVec::shrink_to_fit`
         // by only calling it with a greater capacity.
         if self.capacity() > self.len {
             self.buf.shrink_to_fit(self.len);
+            self.poison_spare();
         }
     }
 
@@ -645,7 +947,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
-    /// #![feature(shrink_to)]
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = Vec::with_capacity(10);
     /// vec.extend([1, 2, 3].iter().cloned());
     /// assert_eq!(vec.capacity(), 10);
@@ -654,63 +956,29 @@ impl<T> Vec<T> {
     /// vec.shrink_to(0);
     /// assert!(vec.capacity() >= 3);
     /// ```
-    #[unstable(feature = "shrink_to", reason = "new API", issue = "56431")]
     pub fn shrink_to(&mut self, min_capacity: usize) {
         self.buf.shrink_to_fit(cmp::max(self.len, min_capacity));
+        self.poison_spare();
     }
 
-    /// Converts the vector into [`Box<[T]>`][owned slice].
+    /// Shortens the vector, keeping the first `len` elements and dropping
+    /// the rest.
     ///
-    /// Note that this will drop any excess capacity.
+    /// If `len` is greater than the vector's current length, this has no
+    /// effect.
     ///
-    /// [owned slice]: Box
+    /// The [`drain`] method can emulate `truncate`, but causes the excess
+    /// elements to be returned instead of dropped.
+    ///
+    /// Note that this method has no effect on the allocated capacity
+    /// of the vector.
     ///
     /// # Examples
     ///
-    /// ```
-    /// let v = vec![1, 2, 3];
-    ///
-    /// let slice = v.into_boxed_slice();
-    /// ```
-    ///
-    /// Any excess capacity is removed:
-    ///
-    /// ```
-    /// let mut vec = Vec::with_capacity(10);
-    /// vec.extend([1, 2, 3].iter().cloned());
-    ///
-    /// assert_eq!(vec.capacity(), 10);
-    /// let slice = vec.into_boxed_slice();
-    /// assert_eq!(slice.into_vec().capacity(), 3);
-    /// ```
-    #[stable(feature = "rust1", since = "1.0.0")]
-    pub fn into_boxed_slice(mut self) -> Box<[T]> {
-        unsafe {
-            self.shrink_to_fit();
-            let me = ManuallyDrop::new(self);
-            let buf = ptr::read(&me.buf);
-            let len = me.len();
-            buf.into_box(len).assume_init()
-        }
-    }
-
-    /// Shortens the vector, keeping the first `len` elements and dropping
-    /// the rest.
-    ///
-    /// If `len` is greater than the vector's current length, this has no
-    /// effect.
-    ///
-    /// The [`drain`] method can emulate `truncate`, but causes the excess
-    /// elements to be returned instead of dropped.
-    ///
-    /// Note that this method has no effect on the allocated capacity
-    /// of the vector.
-    ///
-    /// # Examples
-    ///
-    /// Truncating a five element vector to two elements:
+    /// Truncating a five element vector to two elements:
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1, 2, 3, 4, 5];
     /// vec.truncate(2);
     /// assert_eq!(vec, [1, 2]);
@@ -720,6 +988,7 @@ impl<T> Vec<T> {
     /// length:
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1, 2, 3];
     /// vec.truncate(8);
     /// assert_eq!(vec, [1, 2, 3]);
@@ -729,6 +998,7 @@ impl<T> Vec<T> {
     /// method.
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1, 2, 3];
     /// vec.truncate(0);
     /// assert_eq!(vec, []);
@@ -736,7 +1006,6 @@ impl<T> Vec<T> {
     ///
     /// [`clear`]: Vec::clear
     /// [`drain`]: Vec::drain
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn truncate(&mut self, len: usize) {
         // This is safe because:
         //
@@ -763,12 +1032,12 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// use std::io::{self, Write};
     /// let buffer = vec![1, 2, 3, 5, 8];
     /// io::sink().write(buffer.as_slice()).unwrap();
     /// ```
     #[inline]
-    #[stable(feature = "vec_as_slice", since = "1.7.0")]
     pub fn as_slice(&self) -> &[T] {
         self
     }
@@ -780,12 +1049,12 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// use std::io::{self, Read};
     /// let mut buffer = vec![0; 3];
     /// io::repeat(0b101).read_exact(buffer.as_mut_slice()).unwrap();
     /// ```
     #[inline]
-    #[stable(feature = "vec_as_slice", since = "1.7.0")]
     pub fn as_mut_slice(&mut self) -> &mut [T] {
         self
     }
@@ -804,6 +1073,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let x = vec![1, 2, 4];
     /// let x_ptr = x.as_ptr();
     ///
@@ -815,14 +1085,13 @@ impl<T> Vec<T> {
     /// ```
     ///
     /// [`as_mut_ptr`]: Vec::as_mut_ptr
-    #[stable(feature = "vec_as_ptr", since = "1.37.0")]
     #[inline]
     pub fn as_ptr(&self) -> *const T {
         // We shadow the slice method of the same name to avoid going through
         // `deref`, which creates an intermediate reference.
         let ptr = self.buf.ptr();
         unsafe {
-            assume(!ptr.is_null());
+            hint::assert_unchecked(!ptr.is_null());
         }
         ptr
     }
@@ -837,6 +1106,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// // Allocate vector big enough for 4 elements.
     /// let size = 4;
     /// let mut x: Vec<i32> = Vec::with_capacity(size);
@@ -851,14 +1121,13 @@ impl<T> Vec<T> {
     /// }
     /// assert_eq!(&*x, &[0,1,2,3]);
     /// ```
-    #[stable(feature = "vec_as_ptr", since = "1.37.0")]
     #[inline]
     pub fn as_mut_ptr(&mut self) -> *mut T {
         // We shadow the slice method of the same name to avoid going through
         // `deref_mut`, which creates an intermediate reference.
         let ptr = self.buf.ptr();
         unsafe {
-            assume(!ptr.is_null());
+            hint::assert_unchecked(!ptr.is_null());
         }
         ptr
     }
@@ -889,6 +1158,7 @@ impl<T> Vec<T> {
     ///
     /// ```no_run
     /// # #![allow(dead_code)]
+    /// # use rusty_collections::{vec, vec::Vec};
     /// # // This is just a minimal skeleton for the doc example;
     /// # // don't use this as a starting point for a real library.
     /// # pub struct StreamWrapper { strm: *mut std::ffi::c_void }
@@ -928,6 +1198,7 @@ impl<T> Vec<T> {
     /// the inner vectors were not freed prior to the `set_len` call:
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![vec![1, 0, 0],
     ///                    vec![0, 1, 0],
     ///                    vec![0, 0, 1]];
@@ -942,13 +1213,51 @@ impl<T> Vec<T> {
     /// Normally, here, one would use [`clear`] instead to correctly drop
     /// the contents and thus not leak memory.
     #[inline]
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub unsafe fn set_len(&mut self, new_len: usize) {
         debug_assert!(new_len <= self.capacity());
 
+        let old_len = mem::replace(&mut self.len, new_len);
+        if new_len > old_len {
+            self.check_initialized(old_len, new_len, "set_len");
+        } else {
+            self.poison(new_len, old_len);
+        }
+    }
+
+    // `set_len` for the crate's own code, which only exposes elements it
+    // wrote itself: skips the `debug-memory` checks and poisoning.
+    #[inline]
+    pub(crate) unsafe fn set_len_unchecked(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.capacity());
         self.len = new_len;
     }
 
+    // With the `debug-memory` feature, panics if one of the elements in
+    // `start..end` was never written. Does nothing otherwise.
+    #[inline]
+    fn check_initialized(&self, start: usize, end: usize, what: &str) {
+        if debug_memory::ENABLED {
+            unsafe { debug_memory::check_initialized(self.as_ptr(), start..end, what) }
+        }
+    }
+
+    // With the `debug-memory` feature, fills the slots `start..end` with
+    // poison. Does nothing otherwise.
+    #[inline]
+    fn poison(&mut self, start: usize, end: usize) {
+        if debug_memory::ENABLED && mem::size_of::<T>() != 0 {
+            unsafe {
+                let ptr = self.as_mut_ptr().add(start) as *mut u8;
+                debug_memory::poison(ptr, (end - start) * mem::size_of::<T>());
+            }
+        }
+    }
+
+    #[inline]
+    fn poison_spare(&mut self) {
+        self.poison(self.len, self.capacity());
+    }
+
     /// Removes an element from the vector and returns it.
     ///
     /// The removed element is replaced by the last element of the vector.
@@ -962,6 +1271,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut v = vec!["foo", "bar", "baz", "qux"];
     ///
     /// assert_eq!(v.swap_remove(1), "bar");
@@ -971,7 +1281,6 @@ impl<T> Vec<T> {
     /// assert_eq!(v, ["baz", "qux"]);
     /// ```
     #[inline]
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn swap_remove(&mut self, index: usize) -> T {
         #[cold]
         #[inline(never)]
@@ -989,8 +1298,11 @@ impl<T> Vec<T> {
             // can be self[index] itself).
             let last = ptr::read(self.as_ptr().add(len - 1));
             let hole = self.as_mut_ptr().add(index);
+            // Replace before `set_len`, which poisons the last slot: it can
+            // be the hole itself.
+            let removed = ptr::replace(hole, last);
             self.set_len(len - 1);
-            ptr::replace(hole, last)
+            removed
         }
     }
 
@@ -1004,13 +1316,13 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1, 2, 3];
     /// vec.insert(1, 4);
     /// assert_eq!(vec, [1, 4, 2, 3]);
     /// vec.insert(4, 5);
     /// assert_eq!(vec, [1, 4, 2, 3, 5]);
     /// ```
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn insert(&mut self, index: usize, element: T) {
         #[cold]
         #[inline(never)]
@@ -1040,10 +1352,53 @@ impl<T> Vec<T> {
                 // element.
                 ptr::write(p, element);
             }
-            self.set_len(len + 1);
+            self.len = len + 1;
         }
     }
 
+    /// Inserts an element at position `index` within the vector, shifting all
+    /// elements after it to the right, or returns an error if the vector could
+    /// not grow.
+    ///
+    /// On error the vector is left unchanged and `element` is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec;
+    ///
+    /// let mut vec = vec![1, 2, 3];
+    /// vec.try_insert(1, 4).unwrap();
+    /// assert_eq!(vec, [1, 4, 2, 3]);
+    /// ```
+    pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), TryReserveError> {
+        #[cold]
+        #[inline(never)]
+        fn assert_failed(index: usize, len: usize) -> ! {
+            panic!("insertion index (is {}) should be <= len (is {})", index, len);
+        }
+
+        let len = self.len();
+        if index > len {
+            assert_failed(index, len);
+        }
+        if len == self.buf.capacity() {
+            self.try_reserve(1)?;
+        }
+        // There is room now, so `insert` does not allocate.
+        self.insert(index, element);
+        Ok(())
+    }
+
     /// Removes and returns the element at position `index` within the vector,
     /// shifting all elements after it to the left.
     ///
@@ -1054,11 +1409,11 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut v = vec![1, 2, 3];
     /// assert_eq!(v.remove(1), 2);
     /// assert_eq!(v, [1, 3]);
     /// ```
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn remove(&mut self, index: usize) -> T {
         #[cold]
         #[inline(never)]
@@ -1097,6 +1452,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1, 2, 3, 4];
     /// vec.retain(|&x| x % 2 == 0);
     /// assert_eq!(vec, [2, 4]);
@@ -1105,13 +1461,13 @@ impl<T> Vec<T> {
     /// The exact order may be useful for tracking external state, like an index.
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1, 2, 3, 4, 5];
     /// let keep = [false, true, true, false, true];
     /// let mut i = 0;
     /// vec.retain(|_| (keep[i], i += 1).0);
     /// assert_eq!(vec, [2, 3, 5]);
     /// ```
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn retain<F>(&mut self, mut f: F)
     where
         F: FnMut(&T) -> bool,
@@ -1142,13 +1498,13 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![10, 20, 21, 30, 20];
     ///
     /// vec.dedup_by_key(|i| *i / 10);
     ///
     /// assert_eq!(vec, [10, 20, 30, 20]);
     /// ```
-    #[stable(feature = "dedup_by", since = "1.16.0")]
     #[inline]
     pub fn dedup_by_key<F, K>(&mut self, mut key: F)
     where
@@ -1170,19 +1526,19 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec!["foo", "bar", "Bar", "baz", "bar"];
     ///
     /// vec.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
     ///
     /// assert_eq!(vec, ["foo", "bar", "baz", "bar"]);
     /// ```
-    #[stable(feature = "dedup_by", since = "1.16.0")]
     pub fn dedup_by<F>(&mut self, same_bucket: F)
     where
         F: FnMut(&mut T, &mut T) -> bool,
     {
         let len = {
-            let (dedup, _) = self.as_mut_slice().partition_dedup_by(same_bucket);
+            let (dedup, _) = partition_dedup_by(self.as_mut_slice(), same_bucket);
             dedup.len()
         };
         self.truncate(len);
@@ -1197,12 +1553,12 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1, 2];
     /// vec.push(3);
     /// assert_eq!(vec, [1, 2, 3]);
     /// ```
     #[inline]
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn push(&mut self, value: T) {
         // This will panic or abort if we would allocate > isize::MAX bytes
         // or if the length increment would overflow for zero-sized types.
@@ -1216,18 +1572,47 @@ impl<T> Vec<T> {
         }
     }
 
+    /// Appends an element to the back of a collection, or returns an error if
+    /// the vector could not grow.
+    ///
+    /// On error the vector is left unchanged and `value` is dropped.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec;
+    ///
+    /// let mut vec = vec![1, 2];
+    /// vec.try_push(3).unwrap();
+    /// assert_eq!(vec, [1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn try_push(&mut self, value: T) -> Result<(), TryReserveError> {
+        if self.len == self.buf.capacity() {
+            self.try_reserve(1)?;
+        }
+        // There is room now, so `push` does not allocate.
+        self.push(value);
+        Ok(())
+    }
+
     /// Removes the last element from a vector and returns it, or [`None`] if it
     /// is empty.
     ///
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1, 2, 3];
     /// assert_eq!(vec.pop(), Some(3));
     /// assert_eq!(vec, [1, 2]);
     /// ```
     #[inline]
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn pop(&mut self) -> Option<T> {
         if self.len == 0 {
             None
@@ -1248,6 +1633,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1, 2, 3];
     /// let mut vec2 = vec![4, 5, 6];
     /// vec.append(&mut vec2);
@@ -1255,7 +1641,6 @@ impl<T> Vec<T> {
     /// assert_eq!(vec2, []);
     /// ```
     #[inline]
-    #[stable(feature = "append", since = "1.4.0")]
     pub fn append(&mut self, other: &mut Self) {
         unsafe {
             self.append_elements(other.as_slice() as _);
@@ -1263,10 +1648,38 @@ impl<T> Vec<T> {
         }
     }
 
+    /// Moves all the elements of `other` into `Self`, leaving `other` empty,
+    /// or returns an error if `Self` could not grow.
+    ///
+    /// On error both vectors are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec;
+    ///
+    /// let mut vec = vec![1, 2, 3];
+    /// let mut vec2 = vec![4, 5, 6];
+    /// vec.try_append(&mut vec2).unwrap();
+    /// assert_eq!(vec, [1, 2, 3, 4, 5, 6]);
+    /// assert_eq!(vec2, []);
+    /// ```
+    #[inline]
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), TryReserveError> {
+        self.try_reserve(other.len())?;
+        self.append(other);
+        Ok(())
+    }
+
     /// Appends elements to `Self` from other buffer.
     #[inline]
     unsafe fn append_elements(&mut self, other: *const [T]) {
-        let count = unsafe { (*other).len() };
+        let count = other.len();
         self.reserve(count);
         let len = self.len();
         unsafe { ptr::copy_nonoverlapping(other as *const T, self.as_mut_ptr().add(len), count) };
@@ -1289,6 +1702,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut v = vec![1, 2, 3];
     /// let u: Vec<_> = v.drain(1..).collect();
     /// assert_eq!(v, &[1]);
@@ -1298,8 +1712,7 @@ impl<T> Vec<T> {
     /// v.drain(..);
     /// assert_eq!(v, &[]);
     /// ```
-    #[stable(feature = "drain", since = "1.6.0")]
-    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T>
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, A, G>
     where
         R: RangeBounds<usize>,
     {
@@ -1314,11 +1727,12 @@ impl<T> Vec<T> {
         // the hole, and the vector length is restored to the new length.
         //
         let len = self.len();
-        let Range { start, end } = range.assert_len(len);
+        let Range { start, end } = slice_range(range, len);
 
         unsafe {
-            // set self.vec length's to start, to be safe in case Drain is leaked
-            self.set_len(start);
+            // set self.vec length's to start, to be safe in case Drain is leaked.
+            // Not through `set_len`, which would poison the drained range.
+            self.len = start;
             // Use the borrow in the IterMut to indicate borrowing behavior of the
             // whole Drain iterator (like &mut T).
             let range_slice = slice::from_raw_parts_mut(self.as_mut_ptr().add(start), end - start);
@@ -1339,6 +1753,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut v = vec![1, 2, 3];
     ///
     /// v.clear();
@@ -1346,7 +1761,6 @@ impl<T> Vec<T> {
     /// assert!(v.is_empty());
     /// ```
     #[inline]
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn clear(&mut self) {
         self.truncate(0)
     }
@@ -1357,11 +1771,11 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let a = vec![1, 2, 3];
     /// assert_eq!(a.len(), 3);
     /// ```
     #[inline]
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn len(&self) -> usize {
         self.len
     }
@@ -1371,13 +1785,13 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut v = Vec::new();
     /// assert!(v.is_empty());
     ///
     /// v.push(1);
     /// assert!(!v.is_empty());
     /// ```
-    #[stable(feature = "rust1", since = "1.0.0")]
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
@@ -1395,6 +1809,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1,2,3];
     /// let vec2 = vec.split_off(1);
     /// assert_eq!(vec, [1]);
@@ -1402,8 +1817,11 @@ impl<T> Vec<T> {
     /// ```
     #[inline]
     #[must_use = "use `.truncate()` if you don't need the other half"]
-    #[stable(feature = "split_off", since = "1.4.0")]
-    pub fn split_off(&mut self, at: usize) -> Self {
+    pub fn split_off(&mut self, at: usize) -> Self
+    where
+        A: Clone,
+        G: Clone,
+    {
         #[cold]
         #[inline(never)]
         fn assert_failed(at: usize, len: usize) -> ! {
@@ -1416,19 +1834,22 @@ impl<T> Vec<T> {
 
         if at == 0 {
             // the new vector can take over the original buffer and avoid the copy
-            return mem::replace(self, Vec::with_capacity(self.capacity()));
+            let empty = Vec::with_capacity_in(self.capacity(), self.allocator().clone());
+            return mem::replace(self, empty.with_growth(self.growth().clone()));
         }
 
         let other_len = self.len - at;
-        let mut other = Vec::with_capacity(other_len);
+        let other = Vec::with_capacity_in(other_len, self.allocator().clone());
+        let mut other = other.with_growth(self.growth().clone());
 
-        // Unsafely `set_len` and copy items to `other`.
+        // Copy items to `other`, then unsafely `set_len`: under `debug-memory`
+        // it poisons the tail of `self`.
         unsafe {
-            self.set_len(at);
-            other.set_len(other_len);
+            ptr::copy_nonoverlapping(self.as_ptr().add(at), other.as_mut_ptr(), other_len);
 
-            ptr::copy_nonoverlapping(self.as_ptr().add(at), other.as_mut_ptr(), other.len());
+            self.set_len(at);
         }
+        other.len = other_len;
         other
     }
 
@@ -1449,6 +1870,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1, 2, 3];
     /// vec.resize_with(5, Default::default);
     /// assert_eq!(vec, [1, 2, 3, 0, 0]);
@@ -1458,7 +1880,6 @@ impl<T> Vec<T> {
     /// vec.resize_with(4, || { p *= 2; p });
     /// assert_eq!(vec, [2, 4, 8, 16]);
     /// ```
-    #[stable(feature = "vec_resize_with", since = "1.33.0")]
     pub fn resize_with<F>(&mut self, new_len: usize, f: F)
     where
         F: FnMut() -> T,
@@ -1488,18 +1909,19 @@ impl<T> Vec<T> {
     /// Simple usage:
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let x = vec![1, 2, 3];
     /// let static_ref: &'static mut [usize] = x.leak();
     /// static_ref[0] += 1;
     /// assert_eq!(static_ref, &[2, 2, 3]);
     /// ```
-    #[stable(feature = "vec_leak", since = "1.47.0")]
     #[inline]
     pub fn leak<'a>(self) -> &'a mut [T]
     where
-        T: 'a, // Technically not needed, but kept to be explicit.
+        A: 'a,
     {
-        Box::leak(self.into_boxed_slice())
+        let mut me = ManuallyDrop::new(self);
+        unsafe { slice::from_raw_parts_mut(me.as_mut_ptr(), me.len) }
     }
 
     /// Returns the remaining spare capacity of the vector as a slice of
@@ -1514,8 +1936,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
-    /// #![feature(vec_spare_capacity, maybe_uninit_extra)]
-    ///
+    /// # use rusty_collections::{vec, vec::Vec};
     /// // Allocate vector big enough for 10 elements.
     /// let mut v = Vec::with_capacity(10);
     ///
@@ -1532,7 +1953,6 @@ impl<T> Vec<T> {
     ///
     /// assert_eq!(&v, &[0, 1, 2]);
     /// ```
-    #[unstable(feature = "vec_spare_capacity", issue = "75017")]
     #[inline]
     pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
         unsafe {
@@ -1542,9 +1962,88 @@ impl<T> Vec<T> {
             )
         }
     }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.buf.allocator()
+    }
+
+    /// Returns a reference to the growth policy of the vector.
+    #[inline]
+    pub fn growth(&self) -> &G {
+        self.buf.growth()
+    }
+
+    /// Replaces the growth policy of the vector, keeping its elements and allocation.
+    ///
+    /// The policy decides how much [`reserve`] and [`reserve_exact`] (and
+    /// everything that grows the vector through them, such as [`push`])
+    /// allocate when the vector runs out of capacity. The current capacity
+    /// is left untouched.
+    ///
+    /// [`reserve`]: Vec::reserve
+    /// [`reserve_exact`]: Vec::reserve_exact
+    /// [`push`]: Vec::push
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec::{Chunked, Vec};
+    ///
+    /// let mut vec = Vec::new().with_growth(Chunked::<100>);
+    /// vec.push(1);
+    /// assert_eq!(vec.capacity(), 100);
+    ///
+    /// vec.extend(0..100);
+    /// assert_eq!(vec.capacity(), 200);
+    /// ```
+    pub fn with_growth<H: GrowthPolicy>(self, growth: H) -> Vec<T, A, H> {
+        let me = ManuallyDrop::new(self);
+        let buf = unsafe { ptr::read(&me.buf) };
+        Vec { buf: buf.with_growth(growth), len: me.len }
+    }
 }
 
-impl<T: Clone> Vec<T> {
+impl<T, G: GrowthPolicy> Vec<T, Global, G> {
+    /// Converts the vector into [`Box<[T]>`][owned slice].
+    ///
+    /// Note that this will drop any excess capacity.
+    ///
+    /// [owned slice]: Box
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
+    /// let v = vec![1, 2, 3];
+    ///
+    /// let slice = v.into_boxed_slice();
+    /// ```
+    ///
+    /// Any excess capacity is removed:
+    ///
+    /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
+    /// let mut vec = Vec::with_capacity(10);
+    /// vec.extend([1, 2, 3].iter().cloned());
+    ///
+    /// assert_eq!(vec.capacity(), 10);
+    /// let slice = vec.into_boxed_slice();
+    /// assert_eq!(slice.into_vec().capacity(), 3);
+    /// ```
+    pub fn into_boxed_slice(mut self) -> Box<[T]> {
+        unsafe {
+            self.shrink_to_fit();
+            let me = ManuallyDrop::new(self);
+            let buf = ptr::read(&me.buf);
+            let len = me.len();
+            buf.into_box(len).assume_init()
+        }
+    }
+}
+
+impl<T: Clone, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
     /// Resizes the `Vec` in-place so that `len` is equal to `new_len`.
     ///
     /// If `new_len` is greater than `len`, the `Vec` is extended by the
@@ -1559,6 +2058,7 @@ impl<T: Clone> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec!["hello"];
     /// vec.resize(3, "world");
     /// assert_eq!(vec, ["hello", "world", "world"]);
@@ -1567,7 +2067,6 @@ impl<T: Clone> Vec<T> {
     /// vec.resize(2, 0);
     /// assert_eq!(vec, [1, 2]);
     /// ```
-    #[stable(feature = "vec_resize", since = "1.5.0")]
     pub fn resize(&mut self, new_len: usize, value: T) {
         let len = self.len();
 
@@ -1578,6 +2077,43 @@ impl<T: Clone> Vec<T> {
         }
     }
 
+    /// Resizes the `Vec` in-place so that `len` is equal to `new_len`, or
+    /// returns an error if the vector could not grow.
+    ///
+    /// Behaves like [`Vec::resize`] otherwise. On error the vector is left
+    /// unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec;
+    ///
+    /// let mut vec = vec!["hello"];
+    /// vec.try_resize(3, "world").unwrap();
+    /// assert_eq!(vec, ["hello", "world", "world"]);
+    ///
+    /// let mut vec = vec![1, 2, 3];
+    /// assert!(vec.try_resize(usize::MAX, 0).is_err());
+    /// assert_eq!(vec, [1, 2, 3]);
+    /// ```
+    pub fn try_resize(&mut self, new_len: usize, value: T) -> Result<(), TryReserveError> {
+        let len = self.len();
+
+        if new_len > len {
+            self.try_reserve(new_len - len)?;
+            // There is room now, so `extend_with` does not allocate.
+            self.extend_with(new_len - len, ExtendElement(value))
+        } else {
+            self.truncate(new_len);
+        }
+        Ok(())
+    }
+
     /// Clones and appends all elements in a slice to the `Vec`.
     ///
     /// Iterates over the slice `other`, clones each element, and then appends
@@ -1591,15 +2127,41 @@ impl<T: Clone> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1];
     /// vec.extend_from_slice(&[2, 3, 4]);
     /// assert_eq!(vec, [1, 2, 3, 4]);
     /// ```
     ///
     /// [`extend`]: Vec::extend
-    #[stable(feature = "vec_extend_from_slice", since = "1.6.0")]
     pub fn extend_from_slice(&mut self, other: &[T]) {
-        self.spec_extend(other.iter())
+        self.spec_extend(other.iter().cloned())
+    }
+
+    /// Clones and appends all elements in a slice to the `Vec`, or returns an
+    /// error if the vector could not grow.
+    ///
+    /// On error the vector is left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// If the capacity overflows, or the allocator reports a failure, then an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec;
+    ///
+    /// let mut vec = vec![1];
+    /// vec.try_extend_from_slice(&[2, 3, 4]).unwrap();
+    /// assert_eq!(vec, [1, 2, 3, 4]);
+    /// ```
+    pub fn try_extend_from_slice(&mut self, other: &[T]) -> Result<(), TryReserveError> {
+        self.try_reserve(other.len())?;
+        // There is room now, so `extend_from_slice` does not allocate.
+        self.extend_from_slice(other);
+        Ok(())
     }
 }
 
@@ -1619,16 +2181,6 @@ impl<T: Clone> ExtendWith<T> for ExtendElement<T> {
     }
 }
 
-struct ExtendDefault;
-impl<T: Default> ExtendWith<T> for ExtendDefault {
-    fn next(&mut self) -> T {
-        Default::default()
-    }
-    fn last(self) -> T {
-        Default::default()
-    }
-}
-
 struct ExtendFunc<F>(F);
 impl<T, F: FnMut() -> T> ExtendWith<T> for ExtendFunc<F> {
     fn next(&mut self) -> T {
@@ -1639,7 +2191,7 @@ impl<T, F: FnMut() -> T> ExtendWith<T> for ExtendFunc<F> {
     }
 }
 
-impl<T> Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
     /// Extend the vector by `n` values, using the given generator.
     fn extend_with<E: ExtendWith<T>>(&mut self, n: usize, mut value: E) {
         self.reserve(n);
@@ -1699,7 +2251,7 @@ impl Drop for SetLenOnDrop<'_> {
     }
 }
 
-impl<T: PartialEq> Vec<T> {
+impl<T: PartialEq, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
     /// Removes consecutive repeated elements in the vector according to the
     /// [`PartialEq`] trait implementation.
     ///
@@ -1708,30 +2260,29 @@ impl<T: PartialEq> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec![1, 2, 2, 3, 2];
     ///
     /// vec.dedup();
     ///
     /// assert_eq!(vec, [1, 2, 3, 2]);
     /// ```
-    #[stable(feature = "rust1", since = "1.0.0")]
     #[inline]
     pub fn dedup(&mut self) {
         self.dedup_by(|a, b| a == b)
     }
 }
 
-impl<T> Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
     /// Removes the first instance of `item` from the vector if the item exists.
     ///
     /// This method will be removed soon.
-    #[unstable(feature = "vec_remove_item", reason = "recently added", issue = "40062")]
-    #[rustc_deprecated(
-        reason = "Removing the first item equal to a needle is already easily possible \
+    #[deprecated(
+        since = "1.46.0",
+        note = "Removing the first item equal to a needle is already easily possible \
             with iterators and the current Vec methods. Furthermore, having a method for \
             one particular case of removal (linear search, only the first item, no swap remove) \
-            but not for others is inconsistent. This method will be removed soon.",
-        since = "1.46.0"
+            but not for others is inconsistent. This method will be removed soon."
     )]
     pub fn remove_item<V>(&mut self, item: &V) -> Option<T>
     where
@@ -1747,142 +2298,237 @@ impl<T> Vec<T> {
 ////////////////////////////////////////////////////////////////////////////////
 
 #[doc(hidden)]
-#[stable(feature = "rust1", since = "1.0.0")]
 pub fn from_elem<T: Clone>(elem: T, n: usize) -> Vec<T> {
-    <T as SpecFromElem>::from_elem(elem, n)
+    from_elem_in(elem, n, Global)
 }
 
-// Specialization trait used for Vec::from_elem
-trait SpecFromElem: Sized {
-    fn from_elem(elem: Self, n: usize) -> Vec<Self>;
+#[doc(hidden)]
+pub fn from_elem_in<T: Clone, A: Allocator>(elem: T, n: usize, alloc: A) -> Vec<T, A> {
+    let mut v = Vec::with_capacity_in(n, alloc);
+    v.extend_with(n, ExtendElement(elem));
+    v
 }
 
-impl<T: Clone> SpecFromElem for T {
-    default fn from_elem(elem: Self, n: usize) -> Vec<Self> {
-        let mut v = Vec::with_capacity(n);
-        v.extend_with(n, ExtendElement(elem));
-        v
-    }
+// `vec![elem; n]` picks between `from_zeroable_elem` and `from_elem` with
+// autoref-based specialization, since stable Rust has no specialization:
+//
+//     (&__elem_probe(&elem)).__elem_kind().from_elem(elem, n)
+//
+// `ZeroableKind` is implemented for `ElemProbe<T>` itself, so method lookup
+// finds it before it tries `CloneKind`, which needs another autoref. Where the
+// element type is not known to be `Zeroable`, as in code generic over
+// `T: Clone`, only `CloneKind` applies.
+
+#[doc(hidden)]
+pub struct ElemProbe<T>(PhantomData<T>);
+
+#[doc(hidden)]
+#[inline(always)]
+pub fn __elem_probe<T>(_: &T) -> ElemProbe<T> {
+    ElemProbe(PhantomData)
 }
 
-impl SpecFromElem for i8 {
-    #[inline]
-    fn from_elem(elem: i8, n: usize) -> Vec<i8> {
-        if elem == 0 {
-            return Vec { buf: RawVec::with_capacity_zeroed(n), len: n };
-        }
-        unsafe {
-            let mut v = Vec::with_capacity(n);
-            ptr::write_bytes(v.as_mut_ptr(), elem as u8, n);
-            v.set_len(n);
-            v
-        }
+#[doc(hidden)]
+pub struct Zeroed;
+
+#[doc(hidden)]
+pub struct Cloned;
+
+#[doc(hidden)]
+pub trait ZeroableKind {
+    fn __elem_kind(&self) -> Zeroed;
+}
+
+impl<T: Clone + Zeroable> ZeroableKind for ElemProbe<T> {
+    #[inline(always)]
+    fn __elem_kind(&self) -> Zeroed {
+        Zeroed
     }
 }
 
-impl SpecFromElem for u8 {
-    #[inline]
-    fn from_elem(elem: u8, n: usize) -> Vec<u8> {
-        if elem == 0 {
-            return Vec { buf: RawVec::with_capacity_zeroed(n), len: n };
-        }
-        unsafe {
-            let mut v = Vec::with_capacity(n);
-            ptr::write_bytes(v.as_mut_ptr(), elem, n);
-            v.set_len(n);
-            v
-        }
+#[doc(hidden)]
+pub trait CloneKind {
+    fn __elem_kind(&self) -> Cloned;
+}
+
+impl<T: Clone> CloneKind for &ElemProbe<T> {
+    #[inline(always)]
+    fn __elem_kind(&self) -> Cloned {
+        Cloned
     }
 }
 
-impl<T: Clone + IsZero> SpecFromElem for T {
-    #[inline]
-    fn from_elem(elem: T, n: usize) -> Vec<T> {
-        if elem.is_zero() {
-            return Vec { buf: RawVec::with_capacity_zeroed(n), len: n };
-        }
-        let mut v = Vec::with_capacity(n);
-        v.extend_with(n, ExtendElement(elem));
-        v
+impl Zeroed {
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn from_elem<T: Clone + Zeroable>(self, elem: T, n: usize) -> Vec<T> {
+        from_zeroable_elem(elem, n)
     }
 }
 
-#[rustc_specialization_trait]
-unsafe trait IsZero {
-    /// Whether this value is zero
-    fn is_zero(&self) -> bool;
+impl Cloned {
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn from_elem<T: Clone>(self, elem: T, n: usize) -> Vec<T> {
+        from_elem(elem, n)
+    }
 }
 
-macro_rules! impl_is_zero {
-    ($t:ty, $is_zero:expr) => {
-        unsafe impl IsZero for $t {
-            #[inline]
-            fn is_zero(&self) -> bool {
-                $is_zero(*self)
-            }
+/// Converts `range` into a `Range` within `..len`, as the unstable
+/// `RangeBounds::assert_len` does.
+///
+/// # Panics
+///
+/// Panics if the start of `range` is greater than its end, or if its end is
+/// greater than `len`.
+pub(crate) fn slice_range<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(start) => {
+            start.checked_add(1).unwrap_or_else(|| slice_start_index_overflow_fail())
+        }
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => {
+            end.checked_add(1).unwrap_or_else(|| slice_end_index_overflow_fail())
         }
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
     };
+    if start > end {
+        slice_index_order_fail(start, end);
+    }
+    if end > len {
+        slice_end_index_len_fail(end, len);
+    }
+    start..end
 }
 
-impl_is_zero!(i16, |x| x == 0);
-impl_is_zero!(i32, |x| x == 0);
-impl_is_zero!(i64, |x| x == 0);
-impl_is_zero!(i128, |x| x == 0);
-impl_is_zero!(isize, |x| x == 0);
-
-impl_is_zero!(u16, |x| x == 0);
-impl_is_zero!(u32, |x| x == 0);
-impl_is_zero!(u64, |x| x == 0);
-impl_is_zero!(u128, |x| x == 0);
-impl_is_zero!(usize, |x| x == 0);
+#[inline(never)]
+#[cold]
+#[track_caller]
+fn slice_end_index_len_fail(index: usize, len: usize) -> ! {
+    panic!("range end index {} out of range for slice of length {}", index, len);
+}
 
-impl_is_zero!(bool, |x| x == false);
-impl_is_zero!(char, |x| x == '\0');
+#[inline(never)]
+#[cold]
+#[track_caller]
+fn slice_index_order_fail(index: usize, end: usize) -> ! {
+    panic!("slice index starts at {} but ends at {}", index, end);
+}
 
-impl_is_zero!(f32, |x: f32| x.to_bits() == 0);
-impl_is_zero!(f64, |x: f64| x.to_bits() == 0);
+#[inline(never)]
+#[cold]
+#[track_caller]
+fn slice_start_index_overflow_fail() -> ! {
+    panic!("attempted to index slice from after maximum usize");
+}
 
-unsafe impl<T> IsZero for *const T {
-    #[inline]
-    fn is_zero(&self) -> bool {
-        (*self).is_null()
-    }
+#[inline(never)]
+#[cold]
+#[track_caller]
+fn slice_end_index_overflow_fail() -> ! {
+    panic!("attempted to index slice up to maximum usize");
 }
 
-unsafe impl<T> IsZero for *mut T {
-    #[inline]
-    fn is_zero(&self) -> bool {
-        (*self).is_null()
+/// Moves all but the first of consecutive elements satisfying `same_bucket`
+/// to the end of `v`, as the unstable `<[T]>::partition_dedup_by` does.
+///
+/// Returns two slices: the first contains no consecutive repeated elements,
+/// the second contains all the duplicates in no specified order.
+pub(crate) fn partition_dedup_by<T, F>(v: &mut [T], mut same_bucket: F) -> (&mut [T], &mut [T])
+where
+    F: FnMut(&mut T, &mut T) -> bool,
+{
+    // Although we have a mutable reference to `v`, we cannot make
+    // *arbitrary* changes. The `same_bucket` calls could panic, so we
+    // must ensure that the slice is in a valid state at all times.
+    //
+    // The way that we handle this is by using swaps; we iterate
+    // over all the elements, swapping as we go so that at the end
+    // the elements we wish to keep are in the front, and those we
+    // wish to reject are at the back. We can then split the slice.
+    let len = v.len();
+    if len <= 1 {
+        return (v, &mut []);
+    }
+
+    let ptr = v.as_mut_ptr();
+    let mut next_read: usize = 1;
+    let mut next_write: usize = 1;
+
+    // SAFETY: the `while` condition guarantees `next_read` and `next_write`
+    // are less than `len`, thus are inside `v`. `prev_ptr_write` points to
+    // one element before `ptr_write`, but `next_write` starts at 1, so
+    // `prev_ptr_write` is never less than 0 and is inside the slice.
+    unsafe {
+        // Avoid bounds checks by using raw pointers.
+        while next_read < len {
+            let ptr_read = ptr.add(next_read);
+            let prev_ptr_write = ptr.add(next_write - 1);
+            if !same_bucket(&mut *ptr_read, &mut *prev_ptr_write) {
+                if next_read != next_write {
+                    let ptr_write = prev_ptr_write.add(1);
+                    ptr::swap(ptr_read, ptr_write);
+                }
+                next_write += 1;
+            }
+            next_read += 1;
+        }
     }
+
+    v.split_at_mut(next_write)
 }
 
-// `Option<&T>` and `Option<Box<T>>` are guaranteed to represent `None` as null.
-// For fat pointers, the bytes that would be the pointer metadata in the `Some`
-// variant are padding in the `None` variant, so ignoring them and
-// zero-initializing instead is ok.
-// `Option<&mut T>` never implements `Clone`, so there's no need for an impl of
-// `SpecFromElem`.
+// `<[T]>::to_vec_in` makes a `Vec` of std, not one of ours.
+fn to_vec_in<T: Clone, A: Allocator>(s: &[T], alloc: A) -> Vec<T, A> {
+    let mut vec = Vec::with_capacity_in(s.len(), alloc);
+    vec.extend_from_slice(s);
+    vec
+}
 
-unsafe impl<T: ?Sized> IsZero for Option<&T> {
-    #[inline]
-    fn is_zero(&self) -> bool {
-        self.is_none()
-    }
+/// Creates a `Vec` holding `n` clones of `elem`, like `vec![elem; n]`.
+///
+/// If `elem` [is zero](Zeroable::is_zero), the memory is zeroed by the
+/// allocator instead of cloning `elem` into every slot. For large vectors
+/// this can skip touching the memory entirely, since fresh pages from the
+/// operating system are already zero.
+///
+/// `vec![elem; n]` takes this path by itself wherever the element type is
+/// known to implement [`Zeroable`]. Code that is generic over `T: Clone`
+/// cannot know that on stable Rust, and calls this function to get it.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_collections::vec;
+///
+/// let table: vec::Vec<u64> = vec::from_zeroable_elem(0, 1 << 20);
+/// assert!(table.iter().all(|&x| x == 0));
+/// ```
+pub fn from_zeroable_elem<T: Clone + Zeroable>(elem: T, n: usize) -> Vec<T> {
+    from_zeroable_elem_in(elem, n, Global)
 }
 
-unsafe impl<T: ?Sized> IsZero for Option<Box<T>> {
-    #[inline]
-    fn is_zero(&self) -> bool {
-        self.is_none()
+/// Like [`from_zeroable_elem`], but with the given allocator.
+pub fn from_zeroable_elem_in<T: Clone + Zeroable, A: Allocator>(
+    elem: T,
+    n: usize,
+    alloc: A,
+) -> Vec<T, A> {
+    if elem.is_zero() {
+        return Vec { buf: RawVec::with_capacity_zeroed_in(n, alloc), len: n };
     }
+    from_elem_in(elem, n, alloc)
 }
 
 ////////////////////////////////////////////////////////////////////////////////
 // Common trait implementations for Vec
 ////////////////////////////////////////////////////////////////////////////////
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T> ops::Deref for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> ops::Deref for Vec<T, A, G> {
     type Target = [T];
 
     fn deref(&self) -> &[T] {
@@ -1890,48 +2536,40 @@ impl<T> ops::Deref for Vec<T> {
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T> ops::DerefMut for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> ops::DerefMut for Vec<T, A, G> {
     fn deref_mut(&mut self) -> &mut [T] {
         unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T: Clone> Clone for Vec<T> {
-    #[cfg(not(test))]
-    fn clone(&self) -> Vec<T> {
-        <[T]>::to_vec(&**self)
+impl<T: Clone, A: Allocator + Clone, G: GrowthPolicy + Clone> Clone for Vec<T, A, G> {
+    fn clone(&self) -> Self {
+        let alloc = self.allocator().clone();
+        to_vec_in(self, alloc).with_growth(self.growth().clone())
     }
 
-    // HACK(japaric): with cfg(test) the inherent `[T]::to_vec` method, which is
-    // required for this method definition, is not available. Instead use the
-    // `slice::to_vec`  function which is only available with cfg(test)
-    // NB see the slice::hack module in slice.rs for more information
-    #[cfg(test)]
-    fn clone(&self) -> Vec<T> {
-        crate::slice::to_vec(&**self)
-    }
+    fn clone_from(&mut self, other: &Self) {
+        // drop anything that will not be overwritten
+        self.truncate(other.len());
 
-    fn clone_from(&mut self, other: &Vec<T>) {
-        other.as_slice().clone_into(self);
+        // self.len <= other.len due to the truncate above, so the
+        // slices here are always in-bounds.
+        let (init, tail) = other.split_at(self.len());
+
+        // reuse the contained values' allocations/resources.
+        self.clone_from_slice(init);
+        self.extend_from_slice(tail);
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T: Hash> Hash for Vec<T> {
+impl<T: Hash, A: Allocator, G: GrowthPolicy> Hash for Vec<T, A, G> {
     #[inline]
     fn hash<H: Hasher>(&self, state: &mut H) {
         Hash::hash(&**self, state)
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-#[rustc_on_unimplemented(
-    message = "vector indices are of type `usize` or ranges of `usize`",
-    label = "vector indices are of type `usize` or ranges of `usize`"
-)]
-impl<T, I: SliceIndex<[T]>> Index<I> for Vec<T> {
+impl<T, I: SliceIndex<[T]>, A: Allocator, G: GrowthPolicy> Index<I> for Vec<T, A, G> {
     type Output = I::Output;
 
     #[inline]
@@ -1940,19 +2578,13 @@ impl<T, I: SliceIndex<[T]>> Index<I> for Vec<T> {
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-#[rustc_on_unimplemented(
-    message = "vector indices are of type `usize` or ranges of `usize`",
-    label = "vector indices are of type `usize` or ranges of `usize`"
-)]
-impl<T, I: SliceIndex<[T]>> IndexMut<I> for Vec<T> {
+impl<T, I: SliceIndex<[T]>, A: Allocator, G: GrowthPolicy> IndexMut<I> for Vec<T, A, G> {
     #[inline]
     fn index_mut(&mut self, index: I) -> &mut Self::Output {
         IndexMut::index_mut(&mut **self, index)
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
 impl<T> FromIterator<T> for Vec<T> {
     #[inline]
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Vec<T> {
@@ -1960,10 +2592,90 @@ impl<T> FromIterator<T> for Vec<T> {
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T> IntoIterator for Vec<T> {
+// Specialization trait used for `Vec::from_iter`.
+//
+// liballoc specializes it for `IntoIter`, so that collecting a vector's own
+// consuming iterator can reuse its buffer. Stable Rust cannot specialize,
+// so the one impl asks `into_iter_of` whether the iterator is an `IntoIter`
+// and hands it to `Vec::from` if it is.
+trait SpecFromIter<T, I> {
+    fn from_iter(iter: I) -> Self;
+}
+
+impl<T, I: Iterator<Item = T>> SpecFromIter<T, I> for Vec<T> {
+    fn from_iter(iterator: I) -> Self {
+        match into_iter_of(iterator) {
+            Ok(iterator) => Vec::from(iterator),
+            Err(iterator) => from_iter_nested(iterator),
+        }
+    }
+}
+
+// Returns `iterator` as an `IntoIter<T>` if that is its type.
+fn into_iter_of<T, I: Iterator<Item = T>>(iterator: I) -> Result<IntoIter<T>, I> {
+    if type_id_of::<I>() != type_id_of::<IntoIter<T>>() {
+        return Err(iterator);
+    }
+    // Type ids ignore lifetimes, but those are the same too: an iterator
+    // of type `IntoIter<U>` yields `U`, and `I` yields `T`, so `U` is `T`.
+    let iterator = ManuallyDrop::new(iterator);
+    Ok(unsafe { ptr::read(&*iterator as *const I as *const IntoIter<T>) })
+}
+
+// Returns the `TypeId` of a type that need not be `'static`, with its
+// lifetimes erased.
+//
+// `TypeId::of` only takes `'static` types, since it cannot tell lifetimes
+// apart. They are erased before code generation anyway, so it is called
+// through a trait object whose lifetime is extended to `'static`.
+fn type_id_of<U: ?Sized>() -> TypeId {
+    trait NonStaticAny {
+        fn get_type_id(&self) -> TypeId
+        where
+            Self: 'static;
+    }
+
+    impl<U: ?Sized> NonStaticAny for PhantomData<U> {
+        fn get_type_id(&self) -> TypeId
+        where
+            Self: 'static,
+        {
+            TypeId::of::<U>()
+        }
+    }
+
+    let phantom = PhantomData::<U>;
+    let any: &dyn NonStaticAny = &phantom;
+    let any: &(dyn NonStaticAny + 'static) = unsafe { mem::transmute(any) };
+    any.get_type_id()
+}
+
+// The generic path of `SpecFromIter`.
+fn from_iter_nested<T, I: Iterator<Item = T>>(mut iterator: I) -> Vec<T> {
+    // Unroll the first iteration, as the vector is going to be
+    // expanded on this iteration in every case when the iterable is not
+    // empty, but the loop in extend_desugared() is not going to see the
+    // vector being full in the few subsequent loop iterations.
+    // So we get better branch prediction.
+    let mut vector = match iterator.next() {
+        None => return Vec::new(),
+        Some(element) => {
+            let (lower, _) = iterator.size_hint();
+            let mut vector = Vec::with_capacity(lower.saturating_add(1));
+            unsafe {
+                ptr::write(vector.as_mut_ptr(), element);
+                vector.len = 1;
+            }
+            vector
+        }
+    };
+    vector.spec_extend(iterator);
+    vector
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> IntoIterator for Vec<T, A, G> {
     type Item = T;
-    type IntoIter = IntoIter<T>;
+    type IntoIter = IntoIter<T, A>;
 
     /// Creates a consuming iterator, that is, one that moves each value out of
     /// the vector (from start to end). The vector cannot be used after calling
@@ -1972,6 +2684,7 @@ impl<T> IntoIterator for Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let v = vec!["a".to_string(), "b".to_string()];
     /// for s in v.into_iter() {
     ///     // s has type String, not &String
@@ -1979,12 +2692,15 @@ impl<T> IntoIterator for Vec<T> {
     /// }
     /// ```
     #[inline]
-    fn into_iter(self) -> IntoIter<T> {
+    fn into_iter(self) -> IntoIter<T, A> {
         unsafe {
             let mut me = ManuallyDrop::new(self);
+            let alloc = ptr::read(me.allocator());
+            // `IntoIter` never grows, so it has no use for the growth policy.
+            drop(ptr::read(me.growth()));
             let begin = me.as_mut_ptr();
             let end = if mem::size_of::<T>() == 0 {
-                arith_offset(begin as *const i8, me.len() as isize) as *const T
+                (begin as *const u8).wrapping_add(me.len()) as *const T
             } else {
                 begin.add(me.len()) as *const T
             };
@@ -1993,6 +2709,7 @@ impl<T> IntoIterator for Vec<T> {
                 buf: NonNull::new_unchecked(begin),
                 phantom: PhantomData,
                 cap,
+                alloc,
                 ptr: begin,
                 end,
             }
@@ -2000,8 +2717,7 @@ impl<T> IntoIterator for Vec<T> {
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, T> IntoIterator for &'a Vec<T> {
+impl<'a, T, A: Allocator, G: GrowthPolicy> IntoIterator for &'a Vec<T, A, G> {
     type Item = &'a T;
     type IntoIter = slice::Iter<'a, T>;
 
@@ -2010,8 +2726,7 @@ impl<'a, T> IntoIterator for &'a Vec<T> {
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, T> IntoIterator for &'a mut Vec<T> {
+impl<'a, T, A: Allocator, G: GrowthPolicy> IntoIterator for &'a mut Vec<T, A, G> {
     type Item = &'a mut T;
     type IntoIter = slice::IterMut<'a, T>;
 
@@ -2020,130 +2735,34 @@ impl<'a, T> IntoIterator for &'a mut Vec<T> {
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T> Extend<T> for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> Extend<T> for Vec<T, A, G> {
     #[inline]
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        <Self as SpecExtend<T, I::IntoIter>>::spec_extend(self, iter.into_iter())
-    }
-
-    #[inline]
-    fn extend_one(&mut self, item: T) {
-        self.push(item);
-    }
-
-    #[inline]
-    fn extend_reserve(&mut self, additional: usize) {
-        self.reserve(additional);
-    }
-}
-
-/// Specialization trait used for Vec::from_iter
-///
-/// ## The delegation graph:
-///
-/// ```text
-/// +-------------+
-/// |FromIterator |
-/// +-+-----------+
-///   |
-///   v
-/// +-+-------------------------------+  +---------------------+
-/// |SpecFromIter                  +---->+SpecFromIterNested   |
-/// |where I:                      |  |  |where I:             |
-/// |  Iterator (default)----------+  |  |  Iterator (default) |
-/// |  vec::IntoIter               |  |  |  TrustedLen         |
-/// |  SourceIterMarker---fallback-+  |  |                     |
-/// |  slice::Iter                    |  |                     |
-/// |  Iterator<Item = &Clone>        |  +---------------------+
-/// +---------------------------------+
-/// ```
-trait SpecFromIter<T, I> {
-    fn from_iter(iter: I) -> Self;
-}
-
-/// Another specialization trait for Vec::from_iter
-/// necessary to manually prioritize overlapping specializations
-/// see [`SpecFromIter`] for details.
-trait SpecFromIterNested<T, I> {
-    fn from_iter(iter: I) -> Self;
-}
-
-impl<T, I> SpecFromIterNested<T, I> for Vec<T>
-where
-    I: Iterator<Item = T>,
-{
-    default fn from_iter(mut iterator: I) -> Self {
-        // Unroll the first iteration, as the vector is going to be
-        // expanded on this iteration in every case when the iterable is not
-        // empty, but the loop in extend_desugared() is not going to see the
-        // vector being full in the few subsequent loop iterations.
-        // So we get better branch prediction.
-        let mut vector = match iterator.next() {
-            None => return Vec::new(),
-            Some(element) => {
-                let (lower, _) = iterator.size_hint();
-                let mut vector = Vec::with_capacity(lower.saturating_add(1));
-                unsafe {
-                    ptr::write(vector.as_mut_ptr(), element);
-                    vector.set_len(1);
-                }
-                vector
-            }
-        };
-        // must delegate to spec_extend() since extend() itself delegates
-        // to spec_from for empty Vecs
-        <Vec<T> as SpecExtend<T, I>>::spec_extend(&mut vector, iterator);
-        vector
-    }
-}
-
-impl<T, I> SpecFromIterNested<T, I> for Vec<T>
-where
-    I: TrustedLen<Item = T>,
-{
-    fn from_iter(iterator: I) -> Self {
-        let mut vector = Vec::new();
-        // must delegate to spec_extend() since extend() itself delegates
-        // to spec_from for empty Vecs
-        vector.spec_extend(iterator);
-        vector
-    }
-}
-
-impl<T, I> SpecFromIter<T, I> for Vec<T>
-where
-    I: Iterator<Item = T>,
-{
-    default fn from_iter(iterator: I) -> Self {
-        SpecFromIterNested::from_iter(iterator)
-    }
-}
-
-// A helper struct for in-place iteration that drops the destination slice of iteration,
-// i.e. the head. The source slice (the tail) is dropped by IntoIter.
-struct InPlaceDrop<T> {
-    inner: *mut T,
-    dst: *mut T,
-}
-
-impl<T> InPlaceDrop<T> {
-    fn len(&self) -> usize {
-        unsafe { self.dst.offset_from(self.inner) as usize }
-    }
-}
-
-impl<T> Drop for InPlaceDrop<T> {
-    #[inline]
-    fn drop(&mut self) {
-        unsafe {
-            ptr::drop_in_place(slice::from_raw_parts_mut(self.inner, self.len()));
-        }
+        self.spec_extend(iter.into_iter())
     }
 }
 
-impl<T> SpecFromIter<T, IntoIter<T>> for Vec<T> {
-    fn from_iter(iterator: IntoIter<T>) -> Self {
+impl<T> From<IntoIter<T>> for Vec<T> {
+    /// Collects the remaining elements of a consuming iterator.
+    ///
+    /// This reuses the buffer of `iterator`, unless more than half of it
+    /// would be left unused. Collecting an `IntoIter` with `collect` does
+    /// the same.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec::Vec;
+    ///
+    /// let v: Vec<i32> = (0..10).collect();
+    /// let ptr = v.as_ptr();
+    /// let mut iter = v.into_iter();
+    /// iter.next();
+    /// let v = Vec::from(iter);
+    /// assert_eq!(v, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    /// assert_eq!(v.as_ptr(), ptr);
+    /// ```
+    fn from(iterator: IntoIter<T>) -> Self {
         // A common case is passing a vector into a function which immediately
         // re-collects into a vector. We can short circuit this if the IntoIter
         // has not been advanced at all.
@@ -2152,229 +2771,52 @@ impl<T> SpecFromIter<T, IntoIter<T>> for Vec<T> {
         // than creating it through the generic FromIterator implementation would. That limitation
         // is not strictly necessary as Vec's allocation behavior is intentionally unspecified.
         // But it is a conservative choice.
-        let has_advanced = iterator.buf.as_ptr() as *const _ != iterator.ptr;
+        let has_advanced = !ptr::eq(iterator.buf.as_ptr(), iterator.ptr);
         if !has_advanced || iterator.len() >= iterator.cap / 2 {
             unsafe {
                 let it = ManuallyDrop::new(iterator);
                 if has_advanced {
                     ptr::copy(it.ptr, it.buf.as_ptr(), it.len());
                 }
-                return Vec::from_raw_parts(it.buf.as_ptr(), it.len(), it.cap);
+                let buf = RawVec::from_raw_parts(it.buf.as_ptr(), it.cap);
+                return Vec { buf, len: it.len() };
             }
         }
 
         let mut vec = Vec::new();
-        // must delegate to spec_extend() since extend() itself delegates
-        // to spec_from for empty Vecs
         vec.spec_extend(iterator);
         vec
     }
 }
 
-fn write_in_place_with_drop<T>(
-    src_end: *const T,
-) -> impl FnMut(InPlaceDrop<T>, T) -> Result<InPlaceDrop<T>, !> {
-    move |mut sink, item| {
-        unsafe {
-            // the InPlaceIterable contract cannot be verified precisely here since
-            // try_fold has an exclusive reference to the source pointer
-            // all we can do is check if it's still in range
-            debug_assert!(sink.dst as *const _ <= src_end, "InPlaceIterable contract violation");
-            ptr::write(sink.dst, item);
-            sink.dst = sink.dst.add(1);
-        }
-        Ok(sink)
-    }
-}
-
-/// Specialization marker for collecting an iterator pipeline into a Vec while reusing the
-/// source allocation, i.e. executing the pipeline in place.
-///
-/// The SourceIter parent trait is necessary for the specializing function to access the allocation
-/// which is to be reused. But it is not sufficient for the specialization to be valid. See
-/// additional bounds on the impl.
-#[rustc_unsafe_specialization_marker]
-trait SourceIterMarker: SourceIter<Source: AsIntoIter> {}
-
-// The std-internal SourceIter/InPlaceIterable traits are only implemented by chains of
-// Adapter<Adapter<Adapter<IntoIter>>> (all owned by core/std). Additional bounds
-// on the adapter implementations (beyond `impl<I: Trait> Trait for Adapter<I>`) only depend on other
-// traits already marked as specialization traits (Copy, TrustedRandomAccess, FusedIterator).
-// I.e. the marker does not depend on lifetimes of user-supplied types. Modulo the Copy hole, which
-// several other specializations already depend on.
-impl<T> SourceIterMarker for T where T: SourceIter<Source: AsIntoIter> + InPlaceIterable {}
-
-impl<T, I> SpecFromIter<T, I> for Vec<T>
-where
-    I: Iterator<Item = T> + SourceIterMarker,
-{
-    default fn from_iter(mut iterator: I) -> Self {
-        // Additional requirements which cannot expressed via trait bounds. We rely on const eval
-        // instead:
-        // a) no ZSTs as there would be no allocation to reuse and pointer arithmetic would panic
-        // b) size match as required by Alloc contract
-        // c) alignments match as required by Alloc contract
-        if mem::size_of::<T>() == 0
-            || mem::size_of::<T>()
-                != mem::size_of::<<<I as SourceIter>::Source as AsIntoIter>::Item>()
-            || mem::align_of::<T>()
-                != mem::align_of::<<<I as SourceIter>::Source as AsIntoIter>::Item>()
-        {
-            // fallback to more generic implementations
-            return SpecFromIterNested::from_iter(iterator);
-        }
-
-        let (src_buf, src_ptr, dst_buf, dst_end, cap) = unsafe {
-            let inner = iterator.as_inner().as_into_iter();
-            (
-                inner.buf.as_ptr(),
-                inner.ptr,
-                inner.buf.as_ptr() as *mut T,
-                inner.end as *const T,
-                inner.cap,
-            )
-        };
-
-        // use try-fold since
-        // - it vectorizes better for some iterator adapters
-        // - unlike most internal iteration methods, it only takes a &mut self
-        // - it lets us thread the write pointer through its innards and get it back in the end
-        let sink = InPlaceDrop { inner: dst_buf, dst: dst_buf };
-        let sink = iterator
-            .try_fold::<_, _, Result<_, !>>(sink, write_in_place_with_drop(dst_end))
-            .unwrap();
-        // iteration succeeded, don't drop head
-        let dst = ManuallyDrop::new(sink).dst;
-
-        let src = unsafe { iterator.as_inner().as_into_iter() };
-        // check if SourceIter contract was upheld
-        // caveat: if they weren't we may not even make it to this point
-        debug_assert_eq!(src_buf, src.buf.as_ptr());
-        // check InPlaceIterable contract. This is only possible if the iterator advanced the
-        // source pointer at all. If it uses unchecked access via TrustedRandomAccess
-        // then the source pointer will stay in its initial position and we can't use it as reference
-        if src.ptr != src_ptr {
-            debug_assert!(
-                dst as *const _ <= src.ptr,
-                "InPlaceIterable contract violation, write pointer advanced beyond read pointer"
-            );
-        }
-
-        // drop any remaining values at the tail of the source
-        src.drop_remaining();
-        // but prevent drop of the allocation itself once IntoIter goes out of scope
-        src.forget_allocation();
-
-        let vec = unsafe {
-            let len = dst.offset_from(dst_buf) as usize;
-            Vec::from_raw_parts(dst_buf, len, cap)
-        };
-
-        vec
-    }
-}
-
-impl<'a, T: 'a, I> SpecFromIter<&'a T, I> for Vec<T>
-where
-    I: Iterator<Item = &'a T>,
-    T: Clone,
-{
-    default fn from_iter(iterator: I) -> Self {
-        SpecFromIter::from_iter(iterator.cloned())
-    }
-}
-
-impl<'a, T: 'a> SpecFromIter<&'a T, slice::Iter<'a, T>> for Vec<T>
-where
-    T: Copy,
-{
-    // reuses the extend specialization for T: Copy
-    fn from_iter(iterator: slice::Iter<'a, T>) -> Self {
-        let mut vec = Vec::new();
-        // must delegate to spec_extend() since extend() itself delegates
-        // to spec_from for empty Vecs
-        vec.spec_extend(iterator);
-        vec
-    }
-}
-
-// Specialization trait used for Vec::extend
-trait SpecExtend<T, I> {
-    fn spec_extend(&mut self, iter: I);
-}
-
-impl<T, I> SpecExtend<T, I> for Vec<T>
-where
-    I: Iterator<Item = T>,
-{
-    default fn spec_extend(&mut self, iter: I) {
-        self.extend_desugared(iter)
-    }
-}
-
-impl<T, I> SpecExtend<T, I> for Vec<T>
-where
-    I: TrustedLen<Item = T>,
-{
-    default fn spec_extend(&mut self, iterator: I) {
-        // This is the case for a TrustedLen iterator.
+// `extend` used to pick among specializations of `SpecExtend`. Stable Rust
+// cannot dispatch on the iterator type, so the `TrustedLen` fast path is
+// taken for every iterator whose size hint is exact.
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    // The size hint of an iterator is not a promise on stable, so at most
+    // the reserved number of elements is written without checking the
+    // capacity, and anything beyond goes through `extend_desugared`.
+    fn spec_extend<I: Iterator<Item = T>>(&mut self, mut iterator: I) {
         let (low, high) = iterator.size_hint();
-        if let Some(high_value) = high {
-            debug_assert_eq!(
-                low,
-                high_value,
-                "TrustedLen iterator's size hint is not exact: {:?}",
-                (low, high)
-            );
-        }
-        if let Some(additional) = high {
-            self.reserve(additional);
-            unsafe {
-                let mut ptr = self.as_mut_ptr().add(self.len());
-                let mut local_len = SetLenOnDrop::new(&mut self.len);
-                iterator.for_each(move |element| {
-                    ptr::write(ptr, element);
-                    ptr = ptr.offset(1);
-                    // NB can't overflow since we would have had to alloc the address space
-                    local_len.increment_len(1);
-                });
-            }
-        } else {
-            self.extend_desugared(iterator)
+        if high != Some(low) {
+            return self.extend_desugared(iterator);
         }
-    }
-}
-
-impl<T> SpecExtend<T, IntoIter<T>> for Vec<T> {
-    fn spec_extend(&mut self, mut iterator: IntoIter<T>) {
+        self.reserve(low);
         unsafe {
-            self.append_elements(iterator.as_slice() as _);
+            let mut ptr = self.as_mut_ptr().add(self.len());
+            let mut local_len = SetLenOnDrop::new(&mut self.len);
+            iterator.by_ref().take(low).for_each(move |element| {
+                ptr::write(ptr, element);
+                ptr = ptr.offset(1);
+                // NB can't overflow since we would have had to alloc the address space
+                local_len.increment_len(1);
+            });
         }
-        iterator.ptr = iterator.end;
-    }
-}
-
-impl<'a, T: 'a, I> SpecExtend<&'a T, I> for Vec<T>
-where
-    I: Iterator<Item = &'a T>,
-    T: Clone,
-{
-    default fn spec_extend(&mut self, iterator: I) {
-        self.spec_extend(iterator.cloned())
-    }
-}
-
-impl<'a, T: 'a> SpecExtend<&'a T, slice::Iter<'a, T>> for Vec<T>
-where
-    T: Copy,
-{
-    fn spec_extend(&mut self, iterator: slice::Iter<'a, T>) {
-        let slice = iterator.as_slice();
-        unsafe { self.append_elements(slice) };
+        self.extend_desugared(iterator)
     }
 }
 
-impl<T> Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
     // leaf method to which various SpecFrom/SpecExtend implementations delegate when
     // they have no further optimizations to apply
     fn extend_desugared<I: Iterator<Item = T>>(&mut self, mut iterator: I) {
@@ -2394,9 +2836,29 @@ impl<T> Vec<T> {
             unsafe {
                 ptr::write(self.as_mut_ptr().add(len), element);
                 // NB can't overflow since we would have had to alloc the address space
-                self.set_len(len + 1);
+                self.len = len + 1;
+            }
+        }
+    }
+
+    // fallible version of `extend_desugared`, used by `try_collect`
+    fn try_extend_desugared<I: Iterator<Item = T>>(
+        &mut self,
+        mut iterator: I,
+    ) -> Result<(), TryReserveError> {
+        while let Some(element) = iterator.next() {
+            let len = self.len();
+            if len == self.capacity() {
+                let (lower, _) = iterator.size_hint();
+                self.try_reserve(lower.saturating_add(1))?;
+            }
+            unsafe {
+                ptr::write(self.as_mut_ptr().add(len), element);
+                // NB can't overflow since we would have had to alloc the address space
+                self.len = len + 1;
             }
         }
+        Ok(())
     }
 
     /// Creates a splicing iterator that replaces the specified range in the vector
@@ -2426,6 +2888,7 @@ impl<T> Vec<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut v = vec![1, 2, 3];
     /// let new = [7, 8];
     /// let u: Vec<_> = v.splice(..2, new.iter().cloned()).collect();
@@ -2433,8 +2896,7 @@ impl<T> Vec<T> {
     /// assert_eq!(u, &[1, 2]);
     /// ```
     #[inline]
-    #[stable(feature = "vec_splice", since = "1.21.0")]
-    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter>
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter, A, G>
     where
         R: RangeBounds<usize>,
         I: IntoIterator<Item = T>,
@@ -2451,6 +2913,7 @@ impl<T> Vec<T> {
     /// Using this method is equivalent to the following code:
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// # let some_predicate = |x: &mut i32| { *x == 2 || *x == 3 || *x == 6 };
     /// # let mut vec = vec![1, 2, 3, 4, 5, 6];
     /// let mut i = 0;
@@ -2463,96 +2926,279 @@ impl<T> Vec<T> {
     ///     }
     /// }
     ///
-    /// # assert_eq!(vec, vec![1, 4, 5]);
+    /// # assert_eq!(vec, vec![1, 4, 5]);
+    /// ```
+    ///
+    /// But `drain_filter` is easier to use. `drain_filter` is also more efficient,
+    /// because it can backshift the elements of the array in bulk.
+    ///
+    /// Note that `drain_filter` also lets you mutate every element in the filter closure,
+    /// regardless of whether you choose to keep or remove it.
+    ///
+    /// # Examples
+    ///
+    /// Splitting an array into evens and odds, reusing the original allocation:
+    ///
+    /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
+    /// let mut numbers = vec![1, 2, 3, 4, 5, 6, 8, 9, 11, 13, 14, 15];
+    ///
+    /// let evens = numbers.drain_filter(|x| *x % 2 == 0).collect::<Vec<_>>();
+    /// let odds = numbers;
+    ///
+    /// assert_eq!(evens, vec![2, 4, 6, 8, 14]);
+    /// assert_eq!(odds, vec![1, 3, 5, 9, 11, 13, 15]);
+    /// ```
+    pub fn drain_filter<F>(&mut self, filter: F) -> DrainFilter<'_, T, F, A, G>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let old_len = self.len();
+
+        // Guard against us getting leaked (leak amplification). Not through
+        // `set_len`, which would poison the elements still to be filtered.
+        self.len = 0;
+
+        DrainFilter { vec: self, idx: 0, del: 0, old_len, pred: filter, panic_flag: false }
+    }
+
+    /// Creates an iterator which uses a closure to determine if an element in
+    /// `range` should be removed.
+    ///
+    /// If the closure returns true, then the element is removed and yielded.
+    /// If the closure returns false, the element will remain in the vector and
+    /// will not be yielded by the iterator. Elements outside of `range` are
+    /// never passed to the closure.
+    ///
+    /// Unlike [`drain_filter`], dropping the iterator before it is exhausted
+    /// does not remove any more elements: the ones it has not looked at yet
+    /// are kept. The kept elements are shifted into place once, when the
+    /// iterator is dropped, so removing `k` elements costs *O*(*n*) rather
+    /// than the *O*(*n* · *k*) of calling [`remove`] `k` times.
+    ///
+    /// If the closure or the drop of a yielded element panics, the elements
+    /// that were already removed stay removed and all others are kept, in
+    /// order.
+    ///
+    /// [`drain_filter`]: Vec::drain_filter
+    /// [`remove`]: Vec::remove
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the vector.
+    ///
+    /// # Examples
+    ///
     /// ```
+    /// use rusty_collections::vec;
     ///
-    /// But `drain_filter` is easier to use. `drain_filter` is also more efficient,
-    /// because it can backshift the elements of the array in bulk.
+    /// let mut v = vec![1, 2, 3, 4, 5, 6, 7, 8];
+    /// let evens: Vec<_> = v.extract_if(2.., |x| *x % 2 == 0).collect();
+    /// assert_eq!(evens, [4, 6, 8]);
+    /// assert_eq!(v, [1, 2, 3, 5, 7]);
     ///
-    /// Note that `drain_filter` also lets you mutate every element in the filter closure,
-    /// regardless of whether you choose to keep or remove it.
+    /// // Stops after the first match, keeping the rest.
+    /// let mut v = vec![1, 2, 3, 4];
+    /// assert_eq!(v.extract_if(.., |x| *x > 1).next(), Some(2));
+    /// assert_eq!(v, [1, 3, 4]);
+    /// ```
+    pub fn extract_if<R, F>(&mut self, range: R, filter: F) -> ExtractIf<'_, T, F, A, G>
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&mut T) -> bool,
+    {
+        let old_len = self.len();
+        let Range { start, end } = slice_range(range, old_len);
+
+        // Guard against us getting leaked (leak amplification). Not through
+        // `set_len`, which would poison the elements still to be filtered.
+        self.len = 0;
+
+        ExtractIf { vec: self, idx: start, end, del: 0, old_len, pred: filter }
+    }
+
+    /// Applies a batch of insertions and removals in one pass over the
+    /// buffer, and returns the removed elements in order.
     ///
-    /// # Examples
+    /// The indices of all edits refer to the vector as it was before the
+    /// call, and the edits must be sorted by them. An [`Edit::Insert`]
+    /// places its value before the element at its index, or at the end if
+    /// the index is the length; several insertions at the same index keep
+    /// their order. An [`Edit::Remove`] removes the element at its index.
     ///
-    /// Splitting an array into evens and odds, reusing the original allocation:
+    /// Calling [`insert`] or [`remove`] once per edit shifts the tail every
+    /// time, which costs *O*(*n* · *k*) for `k` edits. This moves every
+    /// element at most once, so it costs *O*(*n* + *k*).
     ///
-    /// ```
-    /// #![feature(drain_filter)]
-    /// let mut numbers = vec![1, 2, 3, 4, 5, 6, 8, 9, 11, 13, 14, 15];
+    /// [`insert`]: Vec::insert
+    /// [`remove`]: Vec::remove
     ///
-    /// let evens = numbers.drain_filter(|x| *x % 2 == 0).collect::<Vec<_>>();
-    /// let odds = numbers;
+    /// # Panics
+    ///
+    /// Panics if the edits are not sorted by index, if an index is out of
+    /// bounds, if the same element is removed twice, or if the new capacity
+    /// exceeds `isize::MAX` bytes. The edits are checked before the vector
+    /// is touched, and no user code runs while the elements are moved, so a
+    /// panic always leaves the vector unchanged.
+    ///
+    /// # Examples
     ///
-    /// assert_eq!(evens, vec![2, 4, 6, 8, 14]);
-    /// assert_eq!(odds, vec![1, 3, 5, 9, 11, 13, 15]);
     /// ```
-    #[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
-    pub fn drain_filter<F>(&mut self, filter: F) -> DrainFilter<'_, T, F>
+    /// use rusty_collections::vec;
+    /// use rusty_collections::vec::Edit;
+    ///
+    /// let mut v = vec!['a', 'b', 'c', 'd'];
+    /// let removed = v.apply_edits([
+    ///     Edit::Insert(0, 'x'),
+    ///     Edit::Remove(1),
+    ///     Edit::Insert(3, 'y'),
+    ///     Edit::Remove(3),
+    ///     Edit::Insert(4, 'z'),
+    /// ]);
+    /// assert_eq!(v, ['x', 'a', 'c', 'y', 'z']);
+    /// assert_eq!(removed, ['b', 'd']);
+    /// ```
+    pub fn apply_edits<I>(&mut self, edits: I) -> Vec<T>
     where
-        F: FnMut(&mut T) -> bool,
+        I: IntoIterator<Item = Edit<T>>,
     {
         let old_len = self.len();
+        let edits: Vec<Edit<T>> = edits.into_iter().collect();
+
+        let (mut inserted, mut removed) = (0, 0);
+        let mut last_index = 0;
+        let mut last_removed = None;
+        for edit in edits.iter() {
+            let index = edit.index();
+            assert!(
+                index >= last_index,
+                "edits are not sorted by index ({} after {})",
+                index,
+                last_index
+            );
+            last_index = index;
+            match edit {
+                Edit::Insert(..) => {
+                    assert!(
+                        index <= old_len,
+                        "insertion index (is {}) should be <= len (is {})",
+                        index,
+                        old_len
+                    );
+                    inserted += 1;
+                }
+                Edit::Remove(_) => {
+                    assert!(
+                        index < old_len,
+                        "removal index (is {}) should be < len (is {})",
+                        index,
+                        old_len
+                    );
+                    assert!(last_removed != Some(index), "element {} is removed twice", index);
+                    last_removed = Some(index);
+                    removed += 1;
+                }
+            }
+        }
+        let new_len = old_len + inserted - removed;
+        if new_len > old_len {
+            self.reserve(new_len - old_len);
+        }
 
-        // Guard against us getting leaked (leak amplification)
+        // The runs of elements that are kept, as `(src, dst, len)`. Each run
+        // moves by the number of insertions minus the number of removals
+        // before it.
+        let mut runs: Vec<(usize, usize, usize)> = Vec::with_capacity(edits.len() + 1);
+        let mut out = Vec::with_capacity(removed);
+        let (mut src, mut inserted, mut removed) = (0, 0, 0);
         unsafe {
-            self.set_len(0);
-        }
+            let ptr = self.as_mut_ptr();
+            for edit in edits.iter() {
+                let index = edit.index();
+                if index > src {
+                    runs.push((src, src + inserted - removed, index - src));
+                    src = index;
+                }
+                match *edit {
+                    Edit::Insert(..) => inserted += 1,
+                    Edit::Remove(index) => {
+                        // Read out before any run is moved over it.
+                        out.push(ptr::read(ptr.add(index)));
+                        removed += 1;
+                        src = index + 1;
+                    }
+                }
+            }
+            if old_len > src {
+                runs.push((src, src + inserted - removed, old_len - src));
+            }
 
-        DrainFilter { vec: self, idx: 0, del: 0, old_len, pred: filter, panic_flag: false }
+            // A run moving left only overlaps runs before it, and one moving
+            // right only runs after it, so the ones moving left go front to
+            // back and the ones moving right back to front.
+            for &(src, dst, len) in runs.iter().filter(|&&(src, dst, _)| dst < src) {
+                ptr::copy(ptr.add(src), ptr.add(dst), len);
+            }
+            for &(src, dst, len) in runs.iter().rev().filter(|&&(src, dst, _)| dst > src) {
+                ptr::copy(ptr.add(src), ptr.add(dst), len);
+            }
+
+            // The inserted values fill the gaps between the runs.
+            let (mut inserted, mut removed_before) = (0, 0);
+            let mut last_removed = None;
+            for edit in edits {
+                match edit {
+                    Edit::Insert(index, value) => {
+                        // An insertion goes before the element at its index,
+                        // so a removal of that element does not count.
+                        let removed = removed_before - (last_removed == Some(index)) as usize;
+                        ptr::write(ptr.add(index + inserted - removed), value);
+                        inserted += 1;
+                    }
+                    Edit::Remove(index) => {
+                        removed_before += 1;
+                        last_removed = Some(index);
+                    }
+                }
+            }
+
+            self.len = new_len;
+        }
+        out
     }
 }
 
 /// Extend implementation that copies elements out of references before pushing them onto the Vec.
-///
-/// This implementation is specialized for slice iterators, where it uses [`copy_from_slice`] to
-/// append the entire slice at once.
-///
-/// [`copy_from_slice`]: ../../std/primitive.slice.html#method.copy_from_slice
-#[stable(feature = "extend_ref", since = "1.2.0")]
-impl<'a, T: 'a + Copy> Extend<&'a T> for Vec<T> {
+impl<'a, T: Copy + 'a, A: Allocator + 'a, G: GrowthPolicy + 'a> Extend<&'a T> for Vec<T, A, G> {
     fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
-        self.spec_extend(iter.into_iter())
-    }
-
-    #[inline]
-    fn extend_one(&mut self, &item: &'a T) {
-        self.push(item);
-    }
-
-    #[inline]
-    fn extend_reserve(&mut self, additional: usize) {
-        self.reserve(additional);
+        self.spec_extend(iter.into_iter().copied())
     }
 }
 
 macro_rules! __impl_slice_eq1 {
-    ([$($vars:tt)*] $lhs:ty, $rhs:ty $(where $ty:ty: $bound:ident)?, #[$stability:meta]) => {
-        #[$stability]
-        impl<A, B, $($vars)*> PartialEq<$rhs> for $lhs
+    ([$($vars:tt)*] $lhs:ty, $rhs:ty $(where $ty:ty: $bound:ident)?) => {
+        impl<T, U, $($vars)*> PartialEq<$rhs> for $lhs
         where
-            A: PartialEq<B>,
+            T: PartialEq<U>,
             $($ty: $bound)?
         {
             #[inline]
             fn eq(&self, other: &$rhs) -> bool { self[..] == other[..] }
-            #[inline]
-            fn ne(&self, other: &$rhs) -> bool { self[..] != other[..] }
         }
     }
 }
 
-__impl_slice_eq1! { [] Vec<A>, Vec<B>, #[stable(feature = "rust1", since = "1.0.0")] }
-__impl_slice_eq1! { [] Vec<A>, &[B], #[stable(feature = "rust1", since = "1.0.0")] }
-__impl_slice_eq1! { [] Vec<A>, &mut [B], #[stable(feature = "rust1", since = "1.0.0")] }
-__impl_slice_eq1! { [] &[A], Vec<B>, #[stable(feature = "partialeq_vec_for_ref_slice", since = "1.46.0")] }
-__impl_slice_eq1! { [] &mut [A], Vec<B>, #[stable(feature = "partialeq_vec_for_ref_slice", since = "1.46.0")] }
-__impl_slice_eq1! { [] Vec<A>, [B], #[stable(feature = "partialeq_vec_for_slice", since = "1.48.0")]  }
-__impl_slice_eq1! { [] [A], Vec<B>, #[stable(feature = "partialeq_vec_for_slice", since = "1.48.0")]  }
-__impl_slice_eq1! { [] Cow<'_, [A]>, Vec<B> where A: Clone, #[stable(feature = "rust1", since = "1.0.0")] }
-__impl_slice_eq1! { [] Cow<'_, [A]>, &[B] where A: Clone, #[stable(feature = "rust1", since = "1.0.0")] }
-__impl_slice_eq1! { [] Cow<'_, [A]>, &mut [B] where A: Clone, #[stable(feature = "rust1", since = "1.0.0")] }
-__impl_slice_eq1! { [const N: usize] Vec<A>, [B; N], #[stable(feature = "rust1", since = "1.0.0")] }
-__impl_slice_eq1! { [const N: usize] Vec<A>, &[B; N], #[stable(feature = "rust1", since = "1.0.0")] }
+__impl_slice_eq1! { [A1: Allocator, G1: GrowthPolicy, A2: Allocator, G2: GrowthPolicy] Vec<T, A1, G1>, Vec<U, A2, G2> }
+__impl_slice_eq1! { [A: Allocator, G: GrowthPolicy] Vec<T, A, G>, &[U] }
+__impl_slice_eq1! { [A: Allocator, G: GrowthPolicy] Vec<T, A, G>, &mut [U] }
+__impl_slice_eq1! { [A: Allocator, G: GrowthPolicy] &[T], Vec<U, A, G> }
+__impl_slice_eq1! { [A: Allocator, G: GrowthPolicy] &mut [T], Vec<U, A, G> }
+__impl_slice_eq1! { [A: Allocator, G: GrowthPolicy] Vec<T, A, G>, [U] }
+__impl_slice_eq1! { [A: Allocator, G: GrowthPolicy] [T], Vec<U, A, G> }
+__impl_slice_eq1! { [A: Allocator, G: GrowthPolicy] Cow<'_, [T]>, Vec<U, A, G> where T: Clone }
+__impl_slice_eq1! { [A: Allocator, G: GrowthPolicy, const N: usize] Vec<T, A, G>, [U; N] }
+__impl_slice_eq1! { [A: Allocator, G: GrowthPolicy, const N: usize] Vec<T, A, G>, &[U; N] }
 
 // NOTE: some less important impls are omitted to reduce code bloat
 // FIXME(Centril): Reconsider this?
@@ -2565,28 +3211,24 @@ __impl_slice_eq1! { [const N: usize] Vec<A>, &[B; N], #[stable(feature = "rust1"
 //__impl_slice_eq1! { [const N: usize] Cow<'a, [A]>, &mut [B; N], }
 
 /// Implements comparison of vectors, [lexicographically](core::cmp::Ord#lexicographical-comparison).
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T: PartialOrd> PartialOrd for Vec<T> {
+impl<T: PartialOrd, A: Allocator, G: GrowthPolicy> PartialOrd for Vec<T, A, G> {
     #[inline]
-    fn partial_cmp(&self, other: &Vec<T>) -> Option<Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         PartialOrd::partial_cmp(&**self, &**other)
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T: Eq> Eq for Vec<T> {}
+impl<T: Eq, A: Allocator, G: GrowthPolicy> Eq for Vec<T, A, G> {}
 
 /// Implements ordering of vectors, [lexicographically](core::cmp::Ord#lexicographical-comparison).
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T: Ord> Ord for Vec<T> {
+impl<T: Ord, A: Allocator, G: GrowthPolicy> Ord for Vec<T, A, G> {
     #[inline]
-    fn cmp(&self, other: &Vec<T>) -> Ordering {
+    fn cmp(&self, other: &Self) -> Ordering {
         Ord::cmp(&**self, &**other)
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-unsafe impl<#[may_dangle] T> Drop for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> Drop for Vec<T, A, G> {
     fn drop(&mut self) {
         unsafe {
             // use drop for [T]
@@ -2598,7 +3240,6 @@ unsafe impl<#[may_dangle] T> Drop for Vec<T> {
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
 impl<T> Default for Vec<T> {
     /// Creates an empty `Vec<T>`.
     fn default() -> Vec<T> {
@@ -2606,115 +3247,86 @@ impl<T> Default for Vec<T> {
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T: fmt::Debug> fmt::Debug for Vec<T> {
+impl<T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for Vec<T, A, G> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Debug::fmt(&**self, f)
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T> AsRef<Vec<T>> for Vec<T> {
-    fn as_ref(&self) -> &Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> AsRef<Vec<T, A, G>> for Vec<T, A, G> {
+    fn as_ref(&self) -> &Vec<T, A, G> {
         self
     }
 }
 
-#[stable(feature = "vec_as_mut", since = "1.5.0")]
-impl<T> AsMut<Vec<T>> for Vec<T> {
-    fn as_mut(&mut self) -> &mut Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> AsMut<Vec<T, A, G>> for Vec<T, A, G> {
+    fn as_mut(&mut self) -> &mut Vec<T, A, G> {
         self
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T> AsRef<[T]> for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> AsRef<[T]> for Vec<T, A, G> {
     fn as_ref(&self) -> &[T] {
         self
     }
 }
 
-#[stable(feature = "vec_as_mut", since = "1.5.0")]
-impl<T> AsMut<[T]> for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> AsMut<[T]> for Vec<T, A, G> {
     fn as_mut(&mut self) -> &mut [T] {
         self
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
 impl<T: Clone> From<&[T]> for Vec<T> {
-    #[cfg(not(test))]
     fn from(s: &[T]) -> Vec<T> {
-        s.to_vec()
-    }
-    #[cfg(test)]
-    fn from(s: &[T]) -> Vec<T> {
-        crate::slice::to_vec(s)
+        to_vec_in(s, Global)
     }
 }
 
-#[stable(feature = "vec_from_mut", since = "1.19.0")]
 impl<T: Clone> From<&mut [T]> for Vec<T> {
-    #[cfg(not(test))]
-    fn from(s: &mut [T]) -> Vec<T> {
-        s.to_vec()
-    }
-    #[cfg(test)]
     fn from(s: &mut [T]) -> Vec<T> {
-        crate::slice::to_vec(s)
+        to_vec_in(s, Global)
     }
 }
 
-#[stable(feature = "vec_from_array", since = "1.44.0")]
 impl<T, const N: usize> From<[T; N]> for Vec<T> {
-    #[cfg(not(test))]
     fn from(s: [T; N]) -> Vec<T> {
-        <[T]>::into_vec(box s)
-    }
-    #[cfg(test)]
-    fn from(s: [T; N]) -> Vec<T> {
-        crate::slice::into_vec(box s)
+        Vec::from(Box::new(s) as Box<[T]>)
     }
 }
 
-#[stable(feature = "vec_from_cow_slice", since = "1.14.0")]
-impl<'a, T> From<Cow<'a, [T]>> for Vec<T>
-where
-    [T]: ToOwned<Owned = Vec<T>>,
-{
+impl<'a, T: Clone> From<Cow<'a, [T]>> for Vec<T> {
     fn from(s: Cow<'a, [T]>) -> Vec<T> {
-        s.into_owned()
+        match s {
+            Cow::Borrowed(s) => Vec::from(s),
+            Cow::Owned(v) => Vec::from(v.into_boxed_slice()),
+        }
     }
 }
 
-// note: test pulls in libstd, which causes errors here
-#[cfg(not(test))]
-#[stable(feature = "vec_from_box", since = "1.18.0")]
 impl<T> From<Box<[T]>> for Vec<T> {
-    fn from(s: Box<[T]>) -> Vec<T> {
-        s.into_vec()
+    fn from(s: Box<[T]>) -> Self {
+        // Through `RawVec::from_box` rather than `into_vec`, so the buffer
+        // gets a canary under `debug-memory`.
+        let len = s.len();
+        Vec { buf: RawVec::from_box(s), len }
     }
 }
 
-// note: test pulls in libstd, which causes errors here
-#[cfg(not(test))]
-#[stable(feature = "box_from_vec", since = "1.20.0")]
-impl<T> From<Vec<T>> for Box<[T]> {
-    fn from(v: Vec<T>) -> Box<[T]> {
+impl<T, G: GrowthPolicy> From<Vec<T, Global, G>> for Box<[T]> {
+    fn from(v: Vec<T, Global, G>) -> Self {
         v.into_boxed_slice()
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
 impl From<&str> for Vec<u8> {
     fn from(s: &str) -> Vec<u8> {
         From::from(s.as_bytes())
     }
 }
 
-#[stable(feature = "array_try_from_vec", since = "1.48.0")]
-impl<T, const N: usize> TryFrom<Vec<T>> for [T; N] {
-    type Error = Vec<T>;
+impl<T, A: Allocator, G: GrowthPolicy, const N: usize> TryFrom<Vec<T, A, G>> for [T; N] {
+    type Error = Vec<T, A, G>;
 
     /// Gets the entire contents of the `Vec<T>` as an array,
     /// if its size exactly matches that of the requested array.
@@ -2722,6 +3334,7 @@ impl<T, const N: usize> TryFrom<Vec<T>> for [T; N] {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// use std::convert::TryInto;
     /// assert_eq!(vec![1, 2, 3].try_into(), Ok([1, 2, 3]));
     /// assert_eq!(<Vec<i32>>::new().try_into(), Ok([]));
@@ -2729,6 +3342,7 @@ impl<T, const N: usize> TryFrom<Vec<T>> for [T; N] {
     ///
     /// If the length doesn't match, the input comes back in `Err`:
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// use std::convert::TryInto;
     /// let r: Result<[i32; 4], _> = (0..10).collect::<Vec<_>>().try_into();
     /// assert_eq!(r, Err(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]));
@@ -2745,13 +3359,14 @@ impl<T, const N: usize> TryFrom<Vec<T>> for [T; N] {
     /// assert_eq!(a, b' ');
     /// assert_eq!(b, b'd');
     /// ```
-    fn try_from(mut vec: Vec<T>) -> Result<[T; N], Vec<T>> {
+    fn try_from(mut vec: Vec<T, A, G>) -> Result<[T; N], Vec<T, A, G>> {
         if vec.len() != N {
             return Err(vec);
         }
 
-        // SAFETY: `.set_len(0)` is always sound.
-        unsafe { vec.set_len(0) };
+        // Setting the length to 0 is always sound. Not through `set_len`,
+        // which would poison the elements about to be read.
+        vec.len = 0;
 
         // SAFETY: A `Vec`'s pointer is always aligned properly, and
         // the alignment the array needs is the same as the items.
@@ -2767,34 +3382,196 @@ impl<T, const N: usize> TryFrom<Vec<T>> for [T; N] {
 // Clone-on-write
 ////////////////////////////////////////////////////////////////////////////////
 
-#[stable(feature = "cow_from_vec", since = "1.8.0")]
-impl<'a, T: Clone> From<&'a [T]> for Cow<'a, [T]> {
-    fn from(s: &'a [T]) -> Cow<'a, [T]> {
-        Cow::Borrowed(s)
-    }
-}
-
-#[stable(feature = "cow_from_vec", since = "1.8.0")]
 impl<'a, T: Clone> From<Vec<T>> for Cow<'a, [T]> {
     fn from(v: Vec<T>) -> Cow<'a, [T]> {
-        Cow::Owned(v)
+        // The owned side of a `Cow<[T]>` is std's `Vec`.
+        Cow::Owned(Box::<[T]>::from(v).into_vec())
     }
 }
 
-#[stable(feature = "cow_from_vec_ref", since = "1.28.0")]
 impl<'a, T: Clone> From<&'a Vec<T>> for Cow<'a, [T]> {
     fn from(v: &'a Vec<T>) -> Cow<'a, [T]> {
         Cow::Borrowed(v.as_slice())
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<'a, T> FromIterator<T> for Cow<'a, [T]>
+////////////////////////////////////////////////////////////////////////////////
+// I/O
+////////////////////////////////////////////////////////////////////////////////
+
+/// Write is implemented for `Vec<u8>` by appending to the vector.
+/// The vector will grow as needed.
+impl<A: Allocator, G: GrowthPolicy> io::Write for Vec<u8, A, G> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        let len = bufs.iter().map(|b| b.len()).sum();
+        self.reserve(len);
+        for buf in bufs {
+            self.extend_from_slice(buf);
+        }
+        Ok(len)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.extend_from_slice(buf);
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Read is implemented for `IntoIter<u8>` by taking bytes from the front
+/// of the ones not yet yielded.
+impl<A: Allocator> io::Read for IntoIter<u8, A> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = cmp::min(self.len(), buf.len());
+        buf[..n].copy_from_slice(&self.as_slice()[..n]);
+        // SAFETY: At least `n` bytes are left, and bytes need no dropping.
+        self.ptr = unsafe { self.ptr.add(n) };
+        Ok(n)
+    }
+}
+
+/// Read is implemented for `Drain<'_, u8>` by taking bytes from the front
+/// of the ones not yet yielded. The vector is closed up when the `Drain`
+/// is dropped, as usual.
+impl<A: Allocator, G: GrowthPolicy> io::Read for Drain<'_, u8, A, G> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.iter.as_slice();
+        let n = cmp::min(remaining.len(), buf.len());
+        buf[..n].copy_from_slice(&remaining[..n]);
+        self.iter = remaining[n..].iter();
+        Ok(n)
+    }
+}
+
+/// The size of the stack buffer `read_to_end_into` probes a full vector
+/// with, and the least it grows the vector by.
+const READ_PROBE_SIZE: usize = 32;
+
+/// The most bytes `read_to_end_into` offers the reader at once, until a
+/// read fills them.
+const READ_MIN_BUF_SIZE: usize = 8 * 1024;
+
+/// Reads all bytes from `reader` until EOF and appends them to `vec`,
+/// returning how many were read.
+///
+/// This is [`Read::read_to_end`](io::Read::read_to_end) for our `Vec`: the
+/// reader fills the spare capacity directly, and the vector grows through
+/// [`Vec::reserve`], so its [`GrowthPolicy`] decides by how much. A vector
+/// that is already full is first probed with a small read on the stack, so
+/// reading into a vector sized exactly for the input does not grow it.
+///
+/// `Read::read` takes an initialized `&mut [u8]`, and stable Rust cannot
+/// hand it uninitialized memory, so spare capacity is zeroed before the
+/// reader first sees it. Each byte is zeroed at most once, however short
+/// the reads, and the buffer offered to the reader starts at 8 KiB and
+/// only doubles while reads fill it, so a large `with_capacity` is not
+/// zeroed up front.
+///
+/// Reads failing with [`ErrorKind::Interrupted`](io::ErrorKind::Interrupted)
+/// are retried. Any other error is returned, and the bytes read before it
+/// stay in `vec`.
+///
+/// # Panics
+///
+/// Panics if `reader` claims to have read more bytes than it was offered.
+///
+/// # Examples
+///
+/// ```
+/// use rusty_collections::vec::{self, Vec};
+///
+/// let mut bytes: Vec<u8> = Vec::with_capacity(5);
+/// let n = vec::read_to_end_into(&mut &b"hello"[..], &mut bytes)?;
+/// assert_eq!(n, 5);
+/// assert_eq!(bytes, *b"hello");
+/// assert_eq!(bytes.capacity(), 5);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn read_to_end_into<R, A, G>(reader: &mut R, vec: &mut Vec<u8, A, G>) -> io::Result<usize>
 where
-    T: Clone,
+    R: io::Read + ?Sized,
+    A: Allocator,
+    G: GrowthPolicy,
 {
-    fn from_iter<I: IntoIterator<Item = T>>(it: I) -> Cow<'a, [T]> {
-        Cow::Owned(FromIterator::from_iter(it))
+    let start_len = vec.len();
+    let start_cap = vec.capacity();
+    // How many bytes at the front of the spare capacity are initialized,
+    // either zeroed or written by an earlier read.
+    let mut initialized = 0;
+    let mut max_read = READ_MIN_BUF_SIZE;
+
+    loop {
+        if vec.len() == vec.capacity() {
+            // `initialized` is 0 here, and stays correct for the new spare
+            // capacity the vector grows below.
+            if vec.capacity() == start_cap && !probe_read(reader, vec)? {
+                return Ok(vec.len() - start_len);
+            }
+            vec.reserve(READ_PROBE_SIZE);
+        }
+
+        let spare = vec.spare_capacity_mut();
+        let buf_len = cmp::min(spare.len(), max_read);
+        if initialized < buf_len {
+            // SAFETY: `initialized..buf_len` is within the spare capacity.
+            unsafe {
+                ptr::write_bytes(spare.as_mut_ptr().add(initialized), 0, buf_len - initialized);
+            }
+            initialized = buf_len;
+        }
+        // SAFETY: The first `initialized >= buf_len` spare bytes are
+        // initialized.
+        let buf = unsafe { slice::from_raw_parts_mut(spare.as_mut_ptr() as *mut u8, buf_len) };
+
+        match reader.read(buf) {
+            Ok(0) => return Ok(vec.len() - start_len),
+            Ok(n) => {
+                assert!(n <= buf_len, "reader read more bytes than it was offered");
+                // The reader initialized, or left zeroed, the first `n`
+                // spare bytes.
+                vec.len += n;
+                initialized -= n;
+                if n == buf_len {
+                    max_read = max_read.saturating_mul(2);
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Reads into a small buffer on the stack, to check for EOF before growing
+/// a full vector. Returns `false` at EOF.
+fn probe_read<R, A, G>(reader: &mut R, vec: &mut Vec<u8, A, G>) -> io::Result<bool>
+where
+    R: io::Read + ?Sized,
+    A: Allocator,
+    G: GrowthPolicy,
+{
+    let mut probe = [0u8; READ_PROBE_SIZE];
+    loop {
+        match reader.read(&mut probe) {
+            Ok(0) => return Ok(false),
+            Ok(n) => {
+                vec.extend_from_slice(&probe[..n]);
+                return Ok(true);
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
     }
 }
 
@@ -2810,38 +3587,41 @@ where
 /// # Example
 ///
 /// ```
+/// # use rusty_collections::{vec, vec::Vec};
 /// let v = vec![0, 1, 2];
-/// let iter: std::vec::IntoIter<_> = v.into_iter();
+/// let iter: rusty_collections::vec::IntoIter<_> = v.into_iter();
 /// ```
-#[stable(feature = "rust1", since = "1.0.0")]
-pub struct IntoIter<T> {
+pub struct IntoIter<
+    T,
+    A: Allocator = Global,
+> {
     buf: NonNull<T>,
     phantom: PhantomData<T>,
     cap: usize,
+    alloc: A,
     ptr: *const T,
     end: *const T,
 }
 
-#[stable(feature = "vec_intoiter_debug", since = "1.13.0")]
-impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
     }
 }
 
-impl<T> IntoIter<T> {
+impl<T, A: Allocator> IntoIter<T, A> {
     /// Returns the remaining items of this iterator as a slice.
     ///
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let vec = vec!['a', 'b', 'c'];
     /// let mut into_iter = vec.into_iter();
     /// assert_eq!(into_iter.as_slice(), &['a', 'b', 'c']);
     /// let _ = into_iter.next().unwrap();
     /// assert_eq!(into_iter.as_slice(), &['b', 'c']);
     /// ```
-    #[stable(feature = "vec_into_iter_as_slice", since = "1.15.0")]
     pub fn as_slice(&self) -> &[T] {
         unsafe { slice::from_raw_parts(self.ptr, self.len()) }
     }
@@ -2851,6 +3631,7 @@ impl<T> IntoIter<T> {
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let vec = vec!['a', 'b', 'c'];
     /// let mut into_iter = vec.into_iter();
     /// assert_eq!(into_iter.as_slice(), &['a', 'b', 'c']);
@@ -2859,57 +3640,42 @@ impl<T> IntoIter<T> {
     /// assert_eq!(into_iter.next().unwrap(), 'b');
     /// assert_eq!(into_iter.next().unwrap(), 'z');
     /// ```
-    #[stable(feature = "vec_into_iter_as_slice", since = "1.15.0")]
     pub fn as_mut_slice(&mut self) -> &mut [T] {
         unsafe { &mut *self.as_raw_mut_slice() }
     }
 
-    fn as_raw_mut_slice(&mut self) -> *mut [T] {
-        ptr::slice_from_raw_parts_mut(self.ptr as *mut T, self.len())
-    }
-
-    fn drop_remaining(&mut self) {
-        unsafe {
-            ptr::drop_in_place(self.as_mut_slice());
-        }
-        self.ptr = self.end;
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
     }
 
-    /// Relinquishes the backing allocation, equivalent to
-    /// `ptr::write(&mut self, Vec::new().into_iter())`
-    fn forget_allocation(&mut self) {
-        self.cap = 0;
-        self.buf = unsafe { NonNull::new_unchecked(RawVec::NEW.ptr()) };
-        self.ptr = self.buf.as_ptr();
-        self.end = self.buf.as_ptr();
+    fn as_raw_mut_slice(&mut self) -> *mut [T] {
+        ptr::slice_from_raw_parts_mut(self.ptr as *mut T, self.len())
     }
 }
 
-#[stable(feature = "vec_intoiter_as_ref", since = "1.46.0")]
-impl<T> AsRef<[T]> for IntoIter<T> {
+impl<T, A: Allocator> AsRef<[T]> for IntoIter<T, A> {
     fn as_ref(&self) -> &[T] {
         self.as_slice()
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-unsafe impl<T: Send> Send for IntoIter<T> {}
-#[stable(feature = "rust1", since = "1.0.0")]
-unsafe impl<T: Sync> Sync for IntoIter<T> {}
+unsafe impl<T: Send, A: Allocator + Send> Send for IntoIter<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for IntoIter<T, A> {}
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T> Iterator for IntoIter<T> {
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
     type Item = T;
 
     #[inline]
     fn next(&mut self) -> Option<T> {
-        if self.ptr as *const _ == self.end {
+        if ptr::eq(self.ptr, self.end) {
             None
         } else if mem::size_of::<T>() == 0 {
             // purposefully don't use 'ptr.offset' because for
             // vectors with 0-size elements this would return the
             // same pointer.
-            self.ptr = unsafe { arith_offset(self.ptr as *const i8, 1) as *mut T };
+            self.ptr = (self.ptr as *mut u8).wrapping_add(1) as *mut T;
 
             // Make up a value of this ZST.
             Some(unsafe { mem::zeroed() })
@@ -2935,34 +3701,16 @@ impl<T> Iterator for IntoIter<T> {
     fn count(self) -> usize {
         self.len()
     }
-
-    unsafe fn __iterator_get_unchecked(&mut self, i: usize) -> Self::Item
-    where
-        Self: TrustedRandomAccess,
-    {
-        // SAFETY: the caller must guarantee that `i` is in bounds of the
-        // `Vec<T>`, so `i` cannot overflow an `isize`, and the `self.ptr.add(i)`
-        // is guaranteed to pointer to an element of the `Vec<T>` and
-        // thus guaranteed to be valid to dereference.
-        //
-        // Also note the implementation of `Self: TrustedRandomAccess` requires
-        // that `T: Copy` so reading elements from the buffer doesn't invalidate
-        // them for `Drop`.
-        unsafe {
-            if mem::size_of::<T>() == 0 { mem::zeroed() } else { ptr::read(self.ptr.add(i)) }
-        }
-    }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T> DoubleEndedIterator for IntoIter<T> {
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
     #[inline]
     fn next_back(&mut self) -> Option<T> {
         if self.end == self.ptr {
             None
         } else if mem::size_of::<T>() == 0 {
             // See above for why 'ptr.offset' isn't used
-            self.end = unsafe { arith_offset(self.end as *const i8, -1) as *mut T };
+            self.end = (self.end as *const u8).wrapping_sub(1) as *mut T;
 
             // Make up a value of this ZST.
             Some(unsafe { mem::zeroed() })
@@ -2974,48 +3722,28 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-impl<T> ExactSizeIterator for IntoIter<T> {
-    fn is_empty(&self) -> bool {
-        self.ptr == self.end
-    }
-}
-
-#[stable(feature = "fused", since = "1.26.0")]
-impl<T> FusedIterator for IntoIter<T> {}
-
-#[unstable(feature = "trusted_len", issue = "37572")]
-unsafe impl<T> TrustedLen for IntoIter<T> {}
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
 
-#[doc(hidden)]
-#[unstable(issue = "none", feature = "std_internals")]
-// T: Copy as approximation for !Drop since get_unchecked does not advance self.ptr
-// and thus we can't implement drop-handling
-unsafe impl<T> TrustedRandomAccess for IntoIter<T>
-where
-    T: Copy,
-{
-    fn may_have_side_effect() -> bool {
-        false
-    }
-}
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
 
-#[stable(feature = "vec_into_iter_clone", since = "1.8.0")]
-impl<T: Clone> Clone for IntoIter<T> {
-    fn clone(&self) -> IntoIter<T> {
-        self.as_slice().to_owned().into_iter()
+impl<T: Clone, A: Allocator + Clone> Clone for IntoIter<T, A> {
+    fn clone(&self) -> Self {
+        to_vec_in(self.as_slice(), self.alloc.clone()).into_iter()
     }
 }
 
-#[stable(feature = "rust1", since = "1.0.0")]
-unsafe impl<#[may_dangle] T> Drop for IntoIter<T> {
+impl<T, A: Allocator> Drop for IntoIter<T, A> {
     fn drop(&mut self) {
-        struct DropGuard<'a, T>(&'a mut IntoIter<T>);
+        struct DropGuard<'a, T, A: Allocator>(&'a mut IntoIter<T, A>);
 
-        impl<T> Drop for DropGuard<'_, T> {
+        impl<T, A: Allocator> Drop for DropGuard<'_, T, A> {
             fn drop(&mut self) {
-                // RawVec handles deallocation
-                let _ = unsafe { RawVec::from_raw_parts(self.0.buf.as_ptr(), self.0.cap) };
+                unsafe {
+                    // `IntoIter::alloc` is not used anymore after this
+                    let alloc = ptr::read(&self.0.alloc);
+                    // RawVec handles deallocation
+                    let _ = RawVec::from_raw_parts_in(self.0.buf.as_ptr(), self.0.cap, alloc);
+                }
             }
         }
 
@@ -3028,34 +3756,6 @@ unsafe impl<#[may_dangle] T> Drop for IntoIter<T> {
     }
 }
 
-#[unstable(issue = "none", feature = "inplace_iteration")]
-unsafe impl<T> InPlaceIterable for IntoIter<T> {}
-
-#[unstable(issue = "none", feature = "inplace_iteration")]
-unsafe impl<T> SourceIter for IntoIter<T> {
-    type Source = IntoIter<T>;
-
-    #[inline]
-    unsafe fn as_inner(&mut self) -> &mut Self::Source {
-        self
-    }
-}
-
-// internal helper trait for in-place iteration specialization.
-#[rustc_specialization_trait]
-pub(crate) trait AsIntoIter {
-    type Item;
-    fn as_into_iter(&mut self) -> &mut IntoIter<Self::Item>;
-}
-
-impl<T> AsIntoIter for IntoIter<T> {
-    type Item = T;
-
-    fn as_into_iter(&mut self) -> &mut IntoIter<Self::Item> {
-        self
-    }
-}
-
 /// A draining iterator for `Vec<T>`.
 ///
 /// This `struct` is created by [`Vec::drain`].
@@ -3064,59 +3764,65 @@ impl<T> AsIntoIter for IntoIter<T> {
 /// # Example
 ///
 /// ```
+/// # use rusty_collections::{vec, vec::Vec};
 /// let mut v = vec![0, 1, 2];
-/// let iter: std::vec::Drain<_> = v.drain(..);
+/// let iter: rusty_collections::vec::Drain<_> = v.drain(..);
 /// ```
-#[stable(feature = "drain", since = "1.6.0")]
-pub struct Drain<'a, T: 'a> {
+pub struct Drain<
+    'a,
+    T: 'a,
+    A: Allocator + 'a = Global,
+    G: GrowthPolicy + 'a = Doubling,
+> {
     /// Index of tail to preserve
     tail_start: usize,
     /// Length of tail
     tail_len: usize,
     /// Current remaining range to remove
     iter: slice::Iter<'a, T>,
-    vec: NonNull<Vec<T>>,
+    vec: NonNull<Vec<T, A, G>>,
 }
 
-#[stable(feature = "collection_debug", since = "1.17.0")]
-impl<T: fmt::Debug> fmt::Debug for Drain<'_, T> {
+impl<T: fmt::Debug, A: Allocator, G: GrowthPolicy> fmt::Debug for Drain<'_, T, A, G> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("Drain").field(&self.iter.as_slice()).finish()
     }
 }
 
-impl<'a, T> Drain<'a, T> {
+impl<'a, T, A: Allocator, G: GrowthPolicy> Drain<'a, T, A, G> {
     /// Returns the remaining items of this iterator as a slice.
     ///
     /// # Examples
     ///
     /// ```
+    /// # use rusty_collections::{vec, vec::Vec};
     /// let mut vec = vec!['a', 'b', 'c'];
     /// let mut drain = vec.drain(..);
     /// assert_eq!(drain.as_slice(), &['a', 'b', 'c']);
     /// let _ = drain.next().unwrap();
     /// assert_eq!(drain.as_slice(), &['b', 'c']);
     /// ```
-    #[stable(feature = "vec_drain_as_slice", since = "1.46.0")]
     pub fn as_slice(&self) -> &[T] {
         self.iter.as_slice()
     }
+
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        unsafe { self.vec.as_ref().allocator() }
+    }
 }
 
-#[stable(feature = "vec_drain_as_slice", since = "1.46.0")]
-impl<'a, T> AsRef<[T]> for Drain<'a, T> {
+impl<'a, T, A: Allocator, G: GrowthPolicy> AsRef<[T]> for Drain<'a, T, A, G> {
     fn as_ref(&self) -> &[T] {
         self.as_slice()
     }
 }
 
-#[stable(feature = "drain", since = "1.6.0")]
-unsafe impl<T: Sync> Sync for Drain<'_, T> {}
-#[stable(feature = "drain", since = "1.6.0")]
-unsafe impl<T: Send> Send for Drain<'_, T> {}
+unsafe impl<T: Sync, A: Sync + Allocator, G: Sync + GrowthPolicy> Sync for Drain<'_, T, A, G> {}
+unsafe impl<T: Send, A: Send + Allocator, G: Send + GrowthPolicy> Send for Drain<'_, T, A, G> {}
 
-#[stable(feature = "drain", since = "1.6.0")]
-impl<T> Iterator for Drain<'_, T> {
+impl<T, A: Allocator, G: GrowthPolicy> Iterator for Drain<'_, T, A, G> {
     type Item = T;
 
     #[inline]
@@ -3129,22 +3835,20 @@ impl<T> Iterator for Drain<'_, T> {
     }
 }
 
-#[stable(feature = "drain", since = "1.6.0")]
-impl<T> DoubleEndedIterator for Drain<'_, T> {
+impl<T, A: Allocator, G: GrowthPolicy> DoubleEndedIterator for Drain<'_, T, A, G> {
     #[inline]
     fn next_back(&mut self) -> Option<T> {
         self.iter.next_back().map(|elt| unsafe { ptr::read(elt as *const _) })
     }
 }
 
-#[stable(feature = "drain", since = "1.6.0")]
-impl<T> Drop for Drain<'_, T> {
+impl<T, A: Allocator, G: GrowthPolicy> Drop for Drain<'_, T, A, G> {
     fn drop(&mut self) {
         /// Continues dropping the remaining elements in the `Drain`, then moves back the
         /// un-`Drain`ed elements to restore the original `Vec`.
-        struct DropGuard<'r, 'a, T>(&'r mut Drain<'a, T>);
+        struct DropGuard<'r, 'a, T, A: Allocator, G: GrowthPolicy>(&'r mut Drain<'a, T, A, G>);
 
-        impl<'r, 'a, T> Drop for DropGuard<'r, 'a, T> {
+        impl<'r, 'a, T, A: Allocator, G: GrowthPolicy> Drop for DropGuard<'r, 'a, T, A, G> {
             fn drop(&mut self) {
                 // Continue the same loop we have below. If the loop already finished, this does
                 // nothing.
@@ -3161,7 +3865,7 @@ impl<T> Drop for Drain<'_, T> {
                             let dst = source_vec.as_mut_ptr().add(start);
                             ptr::copy(src, dst, self.0.tail_len);
                         }
-                        source_vec.set_len(start + self.0.tail_len);
+                        source_vec.len = start + self.0.tail_len;
                     }
                 }
             }
@@ -3179,18 +3883,9 @@ impl<T> Drop for Drain<'_, T> {
     }
 }
 
-#[stable(feature = "drain", since = "1.6.0")]
-impl<T> ExactSizeIterator for Drain<'_, T> {
-    fn is_empty(&self) -> bool {
-        self.iter.is_empty()
-    }
-}
-
-#[unstable(feature = "trusted_len", issue = "37572")]
-unsafe impl<T> TrustedLen for Drain<'_, T> {}
+impl<T, A: Allocator, G: GrowthPolicy> ExactSizeIterator for Drain<'_, T, A, G> {}
 
-#[stable(feature = "fused", since = "1.26.0")]
-impl<T> FusedIterator for Drain<'_, T> {}
+impl<T, A: Allocator, G: GrowthPolicy> FusedIterator for Drain<'_, T, A, G> {}
 
 /// A splicing iterator for `Vec`.
 ///
@@ -3200,19 +3895,23 @@ impl<T> FusedIterator for Drain<'_, T> {}
 /// # Example
 ///
 /// ```
+/// # use rusty_collections::{vec, vec::Vec};
 /// let mut v = vec![0, 1, 2];
 /// let new = [7, 8];
-/// let iter: std::vec::Splice<_> = v.splice(1.., new.iter().cloned());
+/// let iter: rusty_collections::vec::Splice<_> = v.splice(1.., new.iter().cloned());
 /// ```
 #[derive(Debug)]
-#[stable(feature = "vec_splice", since = "1.21.0")]
-pub struct Splice<'a, I: Iterator + 'a> {
-    drain: Drain<'a, I::Item>,
+pub struct Splice<
+    'a,
+    I: Iterator + 'a,
+    A: Allocator + 'a = Global,
+    G: GrowthPolicy + 'a = Doubling,
+> {
+    drain: Drain<'a, I::Item, A, G>,
     replace_with: I,
 }
 
-#[stable(feature = "vec_splice", since = "1.21.0")]
-impl<I: Iterator> Iterator for Splice<'_, I> {
+impl<I: Iterator, A: Allocator, G: GrowthPolicy> Iterator for Splice<'_, I, A, G> {
     type Item = I::Item;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -3224,18 +3923,15 @@ impl<I: Iterator> Iterator for Splice<'_, I> {
     }
 }
 
-#[stable(feature = "vec_splice", since = "1.21.0")]
-impl<I: Iterator> DoubleEndedIterator for Splice<'_, I> {
+impl<I: Iterator, A: Allocator, G: GrowthPolicy> DoubleEndedIterator for Splice<'_, I, A, G> {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.drain.next_back()
     }
 }
 
-#[stable(feature = "vec_splice", since = "1.21.0")]
-impl<I: Iterator> ExactSizeIterator for Splice<'_, I> {}
+impl<I: Iterator, A: Allocator, G: GrowthPolicy> ExactSizeIterator for Splice<'_, I, A, G> {}
 
-#[stable(feature = "vec_splice", since = "1.21.0")]
-impl<I: Iterator> Drop for Splice<'_, I> {
+impl<I: Iterator, A: Allocator, G: GrowthPolicy> Drop for Splice<'_, I, A, G> {
     fn drop(&mut self) {
         self.drain.by_ref().for_each(drop);
 
@@ -3276,7 +3972,7 @@ impl<I: Iterator> Drop for Splice<'_, I> {
 }
 
 /// Private helper methods for `Splice::drop`
-impl<T> Drain<'_, T> {
+impl<T, A: Allocator, G: GrowthPolicy> Drain<'_, T, A, G> {
     /// The range from `self.vec.len` to `self.tail_start` contains elements
     /// that have been moved out.
     /// Fill that range as much as possible with new elements from the `replace_with` iterator.
@@ -3324,18 +4020,21 @@ impl<T> Drain<'_, T> {
 /// # Example
 ///
 /// ```
-/// #![feature(drain_filter)]
-///
+/// # use rusty_collections::{vec, vec::Vec};
 /// let mut v = vec![0, 1, 2];
-/// let iter: std::vec::DrainFilter<_, _> = v.drain_filter(|x| *x % 2 == 0);
+/// let iter: rusty_collections::vec::DrainFilter<_, _> = v.drain_filter(|x| *x % 2 == 0);
 /// ```
-#[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
 #[derive(Debug)]
-pub struct DrainFilter<'a, T, F>
-where
+pub struct DrainFilter<
+    'a,
+    T,
+    F,
+    A: Allocator = Global,
+    G: GrowthPolicy = Doubling,
+> where
     F: FnMut(&mut T) -> bool,
 {
-    vec: &'a mut Vec<T>,
+    vec: &'a mut Vec<T, A, G>,
     /// The index of the item that will be inspected by the next call to `next`.
     idx: usize,
     /// The number of items that have been drained (removed) thus far.
@@ -3352,8 +4051,18 @@ where
     panic_flag: bool,
 }
 
-#[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
-impl<T, F> Iterator for DrainFilter<'_, T, F>
+impl<T, F, A: Allocator, G: GrowthPolicy> DrainFilter<'_, T, F, A, G>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.vec.allocator()
+    }
+}
+
+impl<T, F, A: Allocator, G: GrowthPolicy> Iterator for DrainFilter<'_, T, F, A, G>
 where
     F: FnMut(&mut T) -> bool,
 {
@@ -3390,20 +4099,20 @@ where
     }
 }
 
-#[unstable(feature = "drain_filter", reason = "recently added", issue = "43244")]
-impl<T, F> Drop for DrainFilter<'_, T, F>
+impl<T, F, A: Allocator, G: GrowthPolicy> Drop for DrainFilter<'_, T, F, A, G>
 where
     F: FnMut(&mut T) -> bool,
 {
     fn drop(&mut self) {
-        struct BackshiftOnDrop<'a, 'b, T, F>
+        struct BackshiftOnDrop<'a, 'b, T, F, A: Allocator, G: GrowthPolicy>
         where
             F: FnMut(&mut T) -> bool,
         {
-            drain: &'b mut DrainFilter<'a, T, F>,
+            drain: &'b mut DrainFilter<'a, T, F, A, G>,
         }
 
-        impl<'a, 'b, T, F> Drop for BackshiftOnDrop<'a, 'b, T, F>
+        impl<'a, 'b, T, F, A: Allocator, G: GrowthPolicy> Drop
+            for BackshiftOnDrop<'a, 'b, T, F, A, G>
         where
             F: FnMut(&mut T) -> bool,
         {
@@ -3422,7 +4131,7 @@ where
                         let tail_len = self.drain.old_len - self.drain.idx;
                         src.copy_to(dst, tail_len);
                     }
-                    self.drain.vec.set_len(self.drain.old_len - self.drain.del);
+                    self.drain.vec.len = self.drain.old_len - self.drain.del;
                 }
             }
         }
@@ -3436,4 +4145,125 @@ where
             backshift.drain.for_each(drop);
         }
     }
-}
\ No newline at end of file
+}
+
+/// An iterator which uses a closure to determine if an element in a range
+/// should be removed.
+///
+/// This struct is created by [`Vec::extract_if`].
+/// See its documentation for more.
+#[derive(Debug)]
+pub struct ExtractIf<
+    'a,
+    T,
+    F,
+    A: Allocator = Global,
+    G: GrowthPolicy = Doubling,
+> where
+    F: FnMut(&mut T) -> bool,
+{
+    vec: &'a mut Vec<T, A, G>,
+    /// The index of the item that will be inspected by the next call to `next`.
+    idx: usize,
+    /// The end of the range that is filtered.
+    end: usize,
+    /// The number of items that have been extracted (removed) thus far.
+    del: usize,
+    /// The original length of `vec` prior to extracting.
+    old_len: usize,
+    /// The filter test predicate.
+    pred: F,
+}
+
+impl<T, F, A: Allocator, G: GrowthPolicy> ExtractIf<'_, T, F, A, G>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    /// Returns a reference to the underlying allocator.
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.vec.allocator()
+    }
+}
+
+impl<T, F, A: Allocator, G: GrowthPolicy> Iterator for ExtractIf<'_, T, F, A, G>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        unsafe {
+            while self.idx < self.end {
+                let i = self.idx;
+                let v = slice::from_raw_parts_mut(self.vec.as_mut_ptr(), self.old_len);
+                let extracted = (self.pred)(&mut v[i]);
+                // Update the index *after* the predicate is called. If the index
+                // is updated prior and the predicate panics, the element at this
+                // index would be leaked.
+                self.idx += 1;
+                if extracted {
+                    self.del += 1;
+                    return Some(ptr::read(&v[i]));
+                } else if self.del > 0 {
+                    let del = self.del;
+                    let src: *const T = &v[i];
+                    let dst: *mut T = &mut v[i - del];
+                    ptr::copy_nonoverlapping(src, dst, 1);
+                }
+            }
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.idx))
+    }
+}
+
+impl<T, F, A: Allocator, G: GrowthPolicy> FusedIterator for ExtractIf<'_, T, F, A, G> where
+    F: FnMut(&mut T) -> bool
+{
+}
+
+impl<T, F, A: Allocator, G: GrowthPolicy> Drop for ExtractIf<'_, T, F, A, G>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn drop(&mut self) {
+        // Whether the iterator was exhausted, dropped early or unwound
+        // through by a panic in the predicate, the elements from `idx` on
+        // are kept and only have to be shifted back over the gap.
+        unsafe {
+            if self.idx < self.old_len && self.del > 0 {
+                let ptr = self.vec.as_mut_ptr();
+                let src = ptr.add(self.idx);
+                let dst = src.sub(self.del);
+                let tail_len = self.old_len - self.idx;
+                src.copy_to(dst, tail_len);
+            }
+            self.vec.len = self.old_len - self.del;
+        }
+    }
+}
+
+/// One insertion or removal for [`Vec::apply_edits`].
+///
+/// The index refers to the vector before any of the edits are applied.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Edit<T> {
+    /// Inserts the value before the element at the index, or at the end if
+    /// the index is the length of the vector.
+    Insert(usize, T),
+    /// Removes the element at the index.
+    Remove(usize),
+}
+
+impl<T> Edit<T> {
+    /// Returns the index the edit applies to.
+    pub fn index(&self) -> usize {
+        match *self {
+            Edit::Insert(index, _) | Edit::Remove(index) => index,
+        }
+    }
+}
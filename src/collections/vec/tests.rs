@@ -0,0 +1,860 @@
+use super::*;
+
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::vec::Vec as StdVec;
+
+use crate::test_util::{clones, Counted, Rng};
+
+////////////////////////////////////////////////////////////////////////////////
+// Model checking against std's Vec
+////////////////////////////////////////////////////////////////////////////////
+
+// Values are drawn from a small range, so `dedup_by` and the filters have
+// something to do.
+fn value(rng: &mut Rng) -> u32 {
+    (rng.next() % 8) as u32
+}
+
+// Random edits for a vector of `len` elements, sorted by index. A removal
+// may come before, between or after the insertions at the same index.
+fn random_edits(rng: &mut Rng, len: usize) -> StdVec<Edit<u32>> {
+    let mut edits = StdVec::new();
+    for i in 0..=len {
+        if rng.below(3) != 0 {
+            continue;
+        }
+        let inserts = rng.below(3);
+        let remove_at = rng.below(inserts + 1);
+        let remove = i < len && rng.below(2) == 0;
+        for j in 0..=inserts {
+            if remove && j == remove_at {
+                edits.push(Edit::Remove(i));
+            }
+            if j < inserts {
+                edits.push(Edit::Insert(i, value(rng)));
+            }
+        }
+    }
+    edits
+}
+
+// Applies one random operation to both vectors and checks that they agree on
+// what it returned.
+fn step(rng: &mut Rng, v: &mut Vec<u32>, m: &mut StdVec<u32>) {
+    let len = m.len();
+    match rng.below(17) {
+        0 | 1 => {
+            let x = value(rng);
+            v.push(x);
+            m.push(x);
+        }
+        2 => {
+            let i = rng.below(len + 1);
+            let x = value(rng);
+            v.insert(i, x);
+            m.insert(i, x);
+        }
+        3 if len > 0 => {
+            let i = rng.below(len);
+            assert_eq!(v.remove(i), m.remove(i));
+        }
+        4 if len > 0 => {
+            let i = rng.below(len);
+            assert_eq!(v.swap_remove(i), m.swap_remove(i));
+        }
+        5 => {
+            let k = value(rng);
+            v.retain(|&x| x != k);
+            m.retain(|&x| x != k);
+        }
+        6 => {
+            v.dedup_by(|a, b| *a / 2 == *b / 2);
+            m.dedup_by(|a, b| *a / 2 == *b / 2);
+        }
+        7 => {
+            // Only part of the drained range may be consumed; the rest must
+            // be removed anyway.
+            let range = rng.range(len);
+            let take = rng.below(range.len() + 1);
+            let got: StdVec<u32> = v.drain(range.clone()).take(take).collect();
+            let expected: StdVec<u32> = m.drain(range).take(take).collect();
+            assert_eq!(got, expected);
+        }
+        8 => {
+            let range = rng.range(len);
+            let with: StdVec<u32> = (0..rng.below(6)).map(|_| value(rng)).collect();
+            let got: StdVec<u32> = v.splice(range.clone(), with.iter().copied()).collect();
+            let expected: StdVec<u32> = m.splice(range, with.iter().copied()).collect();
+            assert_eq!(got, expected);
+        }
+        9 => {
+            // `drain_filter` removes every match even if the iterator is
+            // dropped early, so model it with a partition.
+            let k = value(rng);
+            let take = rng.below(len + 1);
+            let got: StdVec<u32> = v.drain_filter(|x| *x < k).take(take).collect();
+            let (removed, kept): (StdVec<u32>, StdVec<u32>) = m.iter().partition(|&&x| x < k);
+            assert_eq!(got[..], removed[..got.len()]);
+            *m = kept;
+        }
+        10 => {
+            let at = rng.below(len + 1);
+            let got = v.split_off(at);
+            let expected = m.split_off(at);
+            assert_eq!(got.as_slice(), expected.as_slice());
+        }
+        11 => {
+            let n = rng.below(len + 2);
+            v.truncate(n);
+            m.truncate(n);
+        }
+        12 => {
+            let n = rng.below(2 * len + 4);
+            let x = value(rng);
+            v.resize(n, x);
+            m.resize(n, x);
+        }
+        13 => {
+            // `shrink_to` panics when asked to grow.
+            let min = rng.below(2 * len + 4).min(v.capacity());
+            v.shrink_to(min);
+            m.shrink_to(min);
+            assert!(v.capacity() >= v.len());
+        }
+        14 => {
+            // Unlike `drain_filter`, `extract_if` keeps what it has not
+            // looked at when it is dropped early.
+            let range = rng.range(len);
+            let k = value(rng);
+            let take = rng.below(range.len() + 1);
+            let got: StdVec<u32> = v.extract_if(range.clone(), |x| *x < k).take(take).collect();
+            let mut expected = StdVec::new();
+            let (mut i, mut end) = (range.start, range.end);
+            while i < end && expected.len() < take {
+                if m[i] < k {
+                    expected.push(m.remove(i));
+                    end -= 1;
+                } else {
+                    i += 1;
+                }
+            }
+            assert_eq!(got, expected);
+        }
+        15 => {
+            let edits = random_edits(rng, len);
+            let mut expected_removed = StdVec::new();
+            let mut new = StdVec::new();
+            let mut edits_at = edits.iter().peekable();
+            // The element at each index, and `None` for the end.
+            for (i, x) in m.iter().map(Some).chain([None]).enumerate() {
+                let mut removed = false;
+                while let Some(edit) = edits_at.next_if(|edit| edit.index() == i) {
+                    match *edit {
+                        Edit::Insert(_, y) => new.push(y),
+                        Edit::Remove(_) => removed = true,
+                    }
+                }
+                match x {
+                    Some(&x) if removed => expected_removed.push(x),
+                    Some(&x) => new.push(x),
+                    None => {}
+                }
+            }
+            let got = v.apply_edits(edits);
+            assert_eq!(got, expected_removed[..]);
+            *m = new;
+        }
+        _ => {
+            let x = value(rng);
+            v.extend_from_slice(&[x, x]);
+            m.extend_from_slice(&[x, x]);
+        }
+    }
+}
+
+#[test]
+fn random_operations_match_std() {
+    for seed in 0..200 {
+        let mut rng = Rng::new(seed);
+        let mut v: Vec<u32> = Vec::new();
+        let mut m: StdVec<u32> = StdVec::new();
+        for _ in 0..300 {
+            step(&mut rng, &mut v, &mut m);
+            assert_eq!(v.as_slice(), m.as_slice(), "seed {}", seed);
+            assert!(v.capacity() >= v.len());
+        }
+    }
+}
+
+#[test]
+fn random_operations_on_zero_sized_types() {
+    let mut rng = Rng::new(7);
+    let mut v: Vec<()> = Vec::new();
+    let mut m: StdVec<()> = StdVec::new();
+    for _ in 0..1000 {
+        let len = m.len();
+        match rng.below(5) {
+            0 => {
+                v.push(());
+                m.push(());
+            }
+            1 => {
+                let range = rng.range(len);
+                assert_eq!(v.drain(range.clone()).count(), m.drain(range).count());
+            }
+            2 => {
+                let range = rng.range(len);
+                let n = rng.below(4);
+                v.splice(range.clone(), (0..n).map(|_| ()));
+                m.splice(range, (0..n).map(|_| ()));
+            }
+            3 => {
+                let n = rng.below(2 * len + 2);
+                v.resize(n, ());
+                m.resize(n, ());
+            }
+            _ => {
+                let at = rng.below(len + 1);
+                assert_eq!(v.split_off(at).len(), m.split_off(at).len());
+            }
+        }
+        assert_eq!(v.len(), m.len());
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Panic safety
+////////////////////////////////////////////////////////////////////////////////
+
+thread_local! {
+    static DROPS: RefCell<StdVec<usize>> = const { RefCell::new(StdVec::new()) };
+}
+
+// An element that counts its drops, and can be armed to panic when dropped.
+struct Bomb {
+    id: usize,
+    armed: bool,
+}
+
+impl Drop for Bomb {
+    fn drop(&mut self) {
+        DROPS.with(|drops| drops.borrow_mut()[self.id] += 1);
+        if self.armed {
+            panic!("element {} panicked in drop", self.id);
+        }
+    }
+}
+
+// Resets the drop counters for `n` elements.
+fn reset_drops(n: usize) {
+    DROPS.with(|drops| *drops.borrow_mut() = (0..n).map(|_| 0).collect());
+}
+
+// How many times each element has been dropped.
+fn drop_counts() -> StdVec<usize> {
+    DROPS.with(|drops| drops.borrow().clone())
+}
+
+fn assert_dropped_once(n: usize) {
+    assert_eq!(drop_counts(), (0..n).map(|_| 1).collect::<StdVec<_>>());
+}
+
+fn bombs(len: usize) -> Vec<Bomb> {
+    (0..len).map(|id| Bomb { id, armed: false }).collect()
+}
+
+fn ids(v: &Vec<Bomb>) -> StdVec<usize> {
+    v.iter().map(|b| b.id).collect()
+}
+
+#[test]
+fn drain_drops_each_element_once_when_a_drop_panics() {
+    let mut rng = Rng::new(1);
+    for _ in 0..200 {
+        let len = 1 + rng.below(30);
+        reset_drops(len);
+        let mut v = bombs(len);
+        let range = rng.range(len);
+        if range.is_empty() {
+            drop(v);
+            assert_dropped_once(len);
+            continue;
+        }
+        let armed = range.start + rng.below(range.len());
+        v[armed].armed = true;
+        let take = rng.below(armed - range.start + 1);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            // Consume some elements, then drop the drain with the bomb still
+            // inside it.
+            v.drain(range.clone()).take(take).for_each(drop);
+        }));
+        assert!(result.is_err());
+
+        // The guard must have moved the tail back.
+        let expected: StdVec<usize> = (0..range.start).chain(range.end..len).collect();
+        assert_eq!(ids(&v), expected);
+        drop(v);
+        assert_dropped_once(len);
+    }
+}
+
+#[test]
+fn splice_keeps_head_and_tail_when_the_replacement_panics() {
+    let mut rng = Rng::new(2);
+    for _ in 0..200 {
+        let len = rng.below(30);
+        let extra = 1 + rng.below(10);
+        reset_drops(len + extra);
+        let mut v = bombs(len);
+        let range = rng.range(len);
+        let fail_at = len + rng.below(extra);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let with = (len..len + extra).map(|id| {
+                if id == fail_at {
+                    panic!("replacement iterator panicked");
+                }
+                Bomb { id, armed: false }
+            });
+            v.splice(range.clone(), with).for_each(drop);
+        }));
+        assert!(result.is_err());
+
+        // Some replacements may have made it in, between the untouched head
+        // and tail.
+        let got = ids(&v);
+        let head: StdVec<usize> = (0..range.start).collect();
+        let tail: StdVec<usize> = (range.end..len).collect();
+        assert_eq!(got[..head.len()], head[..]);
+        assert_eq!(got[got.len() - tail.len()..], tail[..]);
+        for id in &got[head.len()..got.len() - tail.len()] {
+            assert!((len..fail_at).contains(id));
+        }
+
+        // Every replacement created before the panic is dropped once, like
+        // the original elements.
+        drop(v);
+        let expected: StdVec<usize> = (0..len + extra).map(|id| (id < fail_at) as usize).collect();
+        assert_eq!(drop_counts(), expected);
+    }
+}
+
+#[test]
+fn splice_drops_each_element_once_when_a_drop_panics() {
+    let mut rng = Rng::new(3);
+    for _ in 0..200 {
+        let len = 1 + rng.below(30);
+        let extra = rng.below(10);
+        reset_drops(len + extra);
+        let mut v = bombs(len);
+        let mut range = rng.range(len);
+        if range.is_empty() {
+            range.end += 1;
+            if range.end > len {
+                range.start -= 1;
+                range.end -= 1;
+            }
+        }
+        let armed = range.start + rng.below(range.len());
+        v[armed].armed = true;
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let with = (len..len + extra).map(|id| Bomb { id, armed: false });
+            drop(v.splice(range.clone(), with));
+        }));
+        assert!(result.is_err());
+
+        // The panic comes while the removed elements are dropped, before any
+        // replacement is created, so the gap is simply closed.
+        let expected: StdVec<usize> = (0..range.start).chain(range.end..len).collect();
+        assert_eq!(ids(&v), expected);
+        drop(v);
+        let expected: StdVec<usize> = (0..len + extra).map(|id| (id < len) as usize).collect();
+        assert_eq!(drop_counts(), expected);
+    }
+}
+
+#[test]
+fn drain_filter_backshifts_when_the_predicate_panics() {
+    let mut rng = Rng::new(4);
+    for _ in 0..200 {
+        let len = 1 + rng.below(30);
+        reset_drops(len);
+        let mut v = bombs(len);
+        let fail_at = rng.below(len);
+        let take = rng.below(len + 1);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            v.drain_filter(|b| {
+                if b.id == fail_at {
+                    panic!("predicate panicked");
+                }
+                b.id % 3 == 0
+            })
+            .take(take)
+            .for_each(drop);
+        }));
+
+        // The predicate runs for every element, if not through `take` then
+        // when the drain is dropped.
+        assert!(result.is_err());
+
+        // Elements before the panic that matched are gone; everything from
+        // the panicking element on is kept, in order.
+        let expected: StdVec<usize> =
+            (0..len).filter(|&id| id >= fail_at || id % 3 != 0).collect();
+        assert_eq!(ids(&v), expected);
+        drop(v);
+        assert_dropped_once(len);
+    }
+}
+
+#[test]
+fn drain_filter_backshifts_when_a_drop_panics() {
+    let mut rng = Rng::new(5);
+    for _ in 0..200 {
+        let len = 1 + rng.below(30);
+        reset_drops(len);
+        let mut v = bombs(len);
+        // Arm an element that will be removed, but not consumed.
+        let armed = 3 * rng.below(len.div_ceil(3));
+        v[armed].armed = true;
+        let take = rng.below(armed / 3 + 1);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            v.drain_filter(|b| b.id % 3 == 0).take(take).for_each(drop);
+        }));
+        assert!(result.is_err());
+
+        // Matches up to and including the bomb are gone; the rest is kept.
+        let expected: StdVec<usize> = (0..len).filter(|&id| id > armed || id % 3 != 0).collect();
+        assert_eq!(ids(&v), expected);
+        drop(v);
+        assert_dropped_once(len);
+    }
+}
+
+#[test]
+fn extract_if_backshifts_when_the_predicate_panics() {
+    let mut rng = Rng::new(6);
+    for _ in 0..200 {
+        let len = 1 + rng.below(30);
+        reset_drops(len);
+        let mut v = bombs(len);
+        let mut range = rng.range(len);
+        if range.is_empty() {
+            range = 0..len;
+        }
+        let fail_at = range.start + rng.below(range.len());
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            v.extract_if(range.clone(), |b| {
+                if b.id == fail_at {
+                    panic!("predicate panicked");
+                }
+                b.id % 3 == 0
+            })
+            .for_each(drop);
+        }));
+        assert!(result.is_err());
+
+        // Matches in the range before the panic are gone; everything else is
+        // kept, in order.
+        let expected: StdVec<usize> = (0..len)
+            .filter(|&id| !(range.start..fail_at).contains(&id) || id % 3 != 0)
+            .collect();
+        assert_eq!(ids(&v), expected);
+        drop(v);
+        assert_dropped_once(len);
+    }
+}
+
+#[test]
+fn apply_edits_moves_each_element_once() {
+    let mut rng = Rng::new(7);
+    for _ in 0..200 {
+        let len = rng.below(30);
+        let edits = random_edits(&mut rng, len);
+        let extra = edits.len();
+        let inserted: StdVec<bool> =
+            edits.iter().map(|edit| matches!(edit, Edit::Insert(..))).collect();
+        let removals = extra - inserted.iter().filter(|&&b| b).count();
+        reset_drops(len + extra);
+        let mut v = bombs(len);
+        let edits = edits.into_iter().enumerate().map(|(i, edit)| match edit {
+            Edit::Insert(index, _) => Edit::Insert(index, Bomb { id: len + i, armed: false }),
+            Edit::Remove(index) => Edit::Remove(index),
+        });
+        let removed = v.apply_edits(edits);
+        assert_eq!(removed.len(), removals);
+        assert_eq!(v.len() + removed.len(), len + extra - removals);
+
+        // Every original and every inserted element is dropped exactly once.
+        drop(removed);
+        drop(v);
+        let expected: StdVec<usize> =
+            (0..len).map(|_| 1).chain(inserted.iter().map(|&b| b as usize)).collect();
+        assert_eq!(drop_counts(), expected);
+    }
+}
+
+#[test]
+fn apply_edits_rejects_bad_edits_before_touching_the_vector() {
+    let bad: [StdVec<Edit<u32>>; 4] = [
+        [Edit::Remove(2), Edit::Insert(1, 9)].into(),
+        [Edit::Insert(5, 9)].into(),
+        [Edit::Remove(4)].into(),
+        [Edit::Remove(1), Edit::Insert(1, 9), Edit::Remove(1)].into(),
+    ];
+    for edits in bad {
+        let mut v: Vec<u32> = (0..4).collect();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| v.apply_edits(edits)));
+        assert!(result.is_err());
+        assert_eq!(v, [0, 1, 2, 3]);
+    }
+}
+
+#[test]
+fn drop_after_a_panicking_element_still_drops_the_rest() {
+    for len in 1..20 {
+        for armed in 0..len {
+            reset_drops(len);
+            let mut v = bombs(len);
+            v[armed].armed = true;
+            let result = panic::catch_unwind(AssertUnwindSafe(|| drop(v)));
+            assert!(result.is_err());
+            assert_dropped_once(len);
+        }
+    }
+}
+
+// An iterator whose size hint claims `hint` elements, whatever it yields.
+struct Lying<I> {
+    iter: I,
+    hint: usize,
+}
+
+impl<I: Iterator> Iterator for Lying<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.hint, Some(self.hint))
+    }
+}
+
+#[test]
+fn extend_and_collect_survive_a_wrong_exact_size_hint() {
+    for len in 0..20 {
+        for hint in 0..20 {
+            let expected: StdVec<usize> = (0..len).collect();
+
+            let v: Vec<usize> = Lying { iter: 0..len, hint }.collect();
+            assert_eq!(v, expected[..]);
+
+            let mut v: Vec<usize> = Vec::new();
+            v.extend(Lying { iter: 0..len, hint });
+            assert_eq!(v, expected[..]);
+            assert!(v.capacity() >= v.len());
+        }
+    }
+}
+
+#[test]
+fn from_into_iter_reuses_the_buffer() {
+    let v: Vec<u32> = (0..10).collect();
+    let ptr = v.as_ptr();
+    let mut iter = v.into_iter();
+    iter.next();
+    iter.next_back();
+    let v = Vec::from(iter);
+    assert_eq!(v, [1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(v.as_ptr(), ptr);
+
+    // Too much of the buffer would go unused.
+    let v: Vec<u32> = (0..10).collect();
+    let ptr = v.as_ptr();
+    let mut iter = v.into_iter();
+    iter.nth(7);
+    let v = Vec::from(iter);
+    assert_eq!(v, [8, 9]);
+    assert_ne!(v.as_ptr(), ptr);
+}
+
+#[test]
+fn collecting_into_iter_reuses_the_buffer() {
+    let v: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+    let ptr = v.as_ptr();
+    let v: Vec<String> = v.into_iter().collect();
+    assert_eq!(v.as_ptr(), ptr);
+    assert_eq!(v.len(), 10);
+
+    let mut iter = v.into_iter();
+    iter.nth(2);
+    let v: Vec<String> = iter.collect();
+    assert_eq!(v.as_ptr(), ptr);
+    assert!(v.iter().map(String::as_str).eq(["3", "4", "5", "6", "7", "8", "9"]));
+
+    // Elements that borrow are recognized too.
+    let words: Vec<&str> = v.iter().map(String::as_str).collect();
+    let ptr = words.as_ptr();
+    let words: Vec<&str> = words.into_iter().collect();
+    assert_eq!(words.as_ptr(), ptr);
+
+    // Too much of the buffer would go unused, or the iterator is another
+    // type that only wraps an `IntoIter`.
+    let mut iter = words.into_iter();
+    iter.nth(4);
+    let words: Vec<&str> = iter.collect();
+    assert_ne!(words.as_ptr(), ptr);
+    assert_eq!(words, ["8", "9"]);
+    let ptr = words.as_ptr();
+    let words: Vec<&str> = words.into_iter().take(2).collect();
+    assert_ne!(words.as_ptr(), ptr);
+
+    let units: Vec<()> = std::vec![(); 5].into_iter().collect::<Vec<()>>().into_iter().collect();
+    assert_eq!(units.len(), 5);
+}
+
+// `Zeroable`, so `vec!` may skip cloning it.
+unsafe impl Zeroable for Counted {
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+#[test]
+fn vec_macro_zeroes_zeroable_elements() {
+    let (v, n) = clones(|| crate::vec![Counted(0); 100]);
+    assert_eq!(n, 0);
+    assert!(v.iter().all(|x| *x == Counted(0)));
+
+    let (v, n) = clones(|| crate::vec![Counted(7); 100]);
+    assert_eq!(n, 99);
+    assert!(v.iter().all(|x| *x == Counted(7)));
+
+    // Generic code only knows `T: Clone`, so it clones.
+    fn generic<T: Clone>(elem: T) -> Vec<T> {
+        crate::vec![elem; 100]
+    }
+    let (v, n) = clones(|| generic(Counted(0)));
+    assert_eq!(n, 99);
+    assert_eq!(v.len(), 100);
+
+    // Integer literals still infer their type from later use.
+    let mut v = crate::vec![0; 3];
+    v.push(1u8);
+    assert_eq!(v, [0, 0, 0, 1]);
+    let v = crate::vec![String::new(); 2];
+    assert_eq!(v, ["", ""]);
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// I/O
+////////////////////////////////////////////////////////////////////////////////
+
+// A reader that returns short reads of random length, is interrupted every
+// so often, and fails with `error` once `data` runs out, if given one.
+struct Flaky<'a> {
+    data: &'a [u8],
+    rng: Rng,
+    error: Option<io::ErrorKind>,
+}
+
+impl io::Read for Flaky<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.rng.below(3) == 0 {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        if self.data.is_empty() {
+            return match self.error {
+                Some(kind) => Err(kind.into()),
+                None => Ok(0),
+            };
+        }
+        let n = 1 + self.rng.below(cmp::min(buf.len(), self.data.len()));
+        buf[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn read_to_end_into_handles_short_and_interrupted_reads() {
+    let mut rng = Rng::new(25);
+    for _ in 0..200 {
+        let data: StdVec<u8> = (0..rng.below(40_000)).map(|_| rng.next() as u8).collect();
+        let prefix: StdVec<u8> = (0..rng.below(10)).map(|_| rng.next() as u8).collect();
+
+        let mut v: Vec<u8> = Vec::with_capacity(rng.below(100));
+        v.extend_from_slice(&prefix);
+        let mut reader = Flaky { data: &data, rng: Rng::new(rng.next()), error: None };
+        let n = read_to_end_into(&mut reader, &mut v).unwrap();
+
+        assert_eq!(n, data.len());
+        assert_eq!(v[..prefix.len()], prefix[..]);
+        assert_eq!(v[prefix.len()..], data[..]);
+    }
+}
+
+#[test]
+fn read_to_end_into_keeps_the_bytes_read_before_an_error() {
+    let data: StdVec<u8> = (0..1000).map(|i| i as u8).collect();
+    let mut v: Vec<u8> = Vec::new();
+    let error = Some(io::ErrorKind::BrokenPipe);
+    let mut reader = Flaky { data: &data, rng: Rng::new(1), error };
+    let err = read_to_end_into(&mut reader, &mut v).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    assert_eq!(v, data[..]);
+}
+
+#[test]
+fn read_to_end_into_does_not_grow_a_vector_sized_for_the_input() {
+    let data: StdVec<u8> = (0..100).collect();
+    let mut v: Vec<u8> = Vec::with_capacity(100);
+    assert_eq!(read_to_end_into(&mut &data[..], &mut v).unwrap(), 100);
+    assert_eq!(v, data[..]);
+    assert_eq!(v.capacity(), 100);
+
+    // Nothing left to read.
+    assert_eq!(read_to_end_into(&mut &data[..0], &mut v).unwrap(), 0);
+    assert_eq!(v.capacity(), 100);
+}
+
+#[test]
+#[should_panic(expected = "more bytes than it was offered")]
+fn read_to_end_into_rejects_a_reader_that_overreports() {
+    struct Liar;
+
+    impl io::Read for Liar {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            Ok(buf.len() + 1)
+        }
+    }
+
+    let mut v: Vec<u8> = Vec::with_capacity(8);
+    let _ = read_to_end_into(&mut Liar, &mut v);
+}
+
+#[test]
+fn write_then_read_back() {
+    use std::io::{Read, Write};
+
+    let mut v: Vec<u8> = Vec::new();
+    v.write_all(b"hello, ").unwrap();
+    let bufs = [io::IoSlice::new(b"wor"), io::IoSlice::new(b""), io::IoSlice::new(b"ld")];
+    assert_eq!(v.write_vectored(&bufs).unwrap(), 5);
+    assert_eq!(v.write(b"!").unwrap(), 1);
+    v.flush().unwrap();
+    assert_eq!(v, *b"hello, world!");
+
+    let mut drain = v.drain(7..12);
+    let mut buf = [0; 3];
+    assert_eq!(drain.read(&mut buf).unwrap(), 3);
+    assert_eq!(buf, *b"wor");
+    assert_eq!(drain.next(), Some(b'l'));
+    assert_eq!(drain.read(&mut buf).unwrap(), 1);
+    assert_eq!(buf[..1], *b"d");
+    assert_eq!(drain.read(&mut buf).unwrap(), 0);
+    drop(drain);
+    assert_eq!(v, *b"hello, !");
+
+    let mut iter = v.into_iter();
+    assert_eq!(iter.next_back(), Some(b'!'));
+    let mut out = StdVec::new();
+    assert_eq!(iter.read_to_end(&mut out).unwrap(), 7);
+    assert_eq!(out, b"hello, ");
+    assert_eq!(iter.next(), None);
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// debug-memory
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn enum_padding_is_never_read() {
+    // The payload bytes of `None` are never written, so they still hold the
+    // poison of the fresh buffer.
+    let mut v: Vec<Option<u64>> = Vec::new();
+    for _ in 0..4 {
+        v.push(None);
+    }
+    v.drain(0..1);
+    let w = v.split_off(1);
+    v.extend(w);
+    assert_eq!(v, [None, None, None]);
+    let v: Vec<MaybeUninit<u64>> = Vec::with_capacity(4);
+    let mut v = ManuallyDrop::new(v);
+    let v = unsafe { Vec::from_raw_parts(v.as_mut_ptr(), 4, v.capacity()) };
+    assert_eq!(v.len(), 4);
+}
+
+#[test]
+fn data_that_looks_like_poison_is_not_reported() {
+    let poison = debug_memory::POISON;
+    let mut v: Vec<u64> = Vec::new();
+    v.extend([poison; 3].iter().copied());
+    v.push(poison);
+    v.insert(0, poison);
+    v.extend_from_slice(&[poison; 5]);
+    v.drain(1..3);
+    v.extract_if(.., |&mut x| x == 0).for_each(drop);
+    v.drain_filter(|&mut x| x == 0).for_each(drop);
+    let w = v.split_off(2);
+    let v: Vec<u64> = Vec::from(w.into_iter());
+    assert!(v.iter().all(|&x| x == poison));
+
+    let bytes = poison.to_ne_bytes().repeat(4);
+    let mut v: Vec<u8> = Vec::new();
+    read_to_end_into(&mut &bytes[..], &mut v).unwrap();
+    assert_eq!(v, bytes[..]);
+}
+
+#[test]
+#[cfg(feature = "debug-memory")]
+fn set_len_reports_unwritten_elements() {
+    let mut v: Vec<u64> = Vec::with_capacity(4);
+    v.spare_capacity_mut()[0].write(1);
+    v.spare_capacity_mut()[1].write(2);
+    unsafe { v.set_len(2) };
+    assert_eq!(v, [1, 2]);
+
+    let err = panic::catch_unwind(AssertUnwindSafe(|| unsafe { v.set_len(4) })).unwrap_err();
+    let msg = err.downcast_ref::<String>().unwrap();
+    assert_eq!(msg, "debug-memory: `set_len` exposed uninitialized element 2");
+    unsafe { v.set_len(2) };
+
+    // Small elements are checked by the aligned words they make up.
+    let mut v: Vec<u16> = Vec::with_capacity(16);
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| unsafe { v.set_len(16) })).is_err());
+    unsafe { v.set_len(0) };
+}
+
+#[test]
+#[cfg(feature = "debug-memory")]
+fn from_raw_parts_reports_unwritten_elements() {
+    let mut v = ManuallyDrop::new(Vec::<u32>::with_capacity(4));
+    let (ptr, cap) = (v.as_mut_ptr(), v.capacity());
+    let result = panic::catch_unwind(|| unsafe { Vec::from_raw_parts(ptr, 4, cap) });
+    assert!(result.is_err());
+    drop(unsafe { Vec::from_raw_parts(ptr, 0, cap) });
+}
+
+#[test]
+#[cfg(feature = "debug-memory")]
+fn given_up_and_fresh_slots_are_poisoned() {
+    let mut v: Vec<u64> = (0..4).collect();
+    unsafe { v.set_len(1) };
+    let spare = v.spare_capacity_mut();
+    // SAFETY: Poisoning wrote every byte of the spare capacity.
+    assert!(spare.iter().all(|x| unsafe { x.assume_init() } == debug_memory::POISON));
+
+    v.reserve(100);
+    let spare = v.spare_capacity_mut();
+    assert!(spare.iter().all(|x| unsafe { x.assume_init() } == debug_memory::POISON));
+}
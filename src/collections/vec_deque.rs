@@ -0,0 +1,1419 @@
+//! A double-ended queue implemented with a growable ring buffer.
+//!
+//! [`VecDeque<T>`] keeps its elements in a [`RawVec`] and remembers where
+//! the first one is. Pushing or popping at either end is *O*(1): it only
+//! moves that index, and the elements wrap around the end of the buffer
+//! instead of being shifted. Growing uses `RawVec`'s amortized growth, then
+//! moves whichever side of the wrapped-around contents is shorter so the
+//! elements are in order again in the bigger buffer.
+//!
+//! This is the type to use for a queue. `Vec::remove(0)` shifts every other
+//! element, so a breadth-first walk that pops from the front of a `Vec` is
+//! quadratic; with a `VecDeque` it is linear:
+//!
+//! ```
+//! use rusty_collections::vec_deque::VecDeque;
+//!
+//! // Level-order walk of a binary tree stored in an array.
+//! let tree = [1, 2, 3, 4, 5, 6, 7];
+//! let mut order = Vec::new();
+//! let mut queue = VecDeque::new();
+//! queue.push_back(0);
+//! while let Some(i) = queue.pop_front() {
+//!     order.push(tree[i]);
+//!     for child in [2 * i + 1, 2 * i + 2] {
+//!         if child < tree.len() {
+//!             queue.push_back(child);
+//!         }
+//!     }
+//! }
+//! assert_eq!(order, tree);
+//! ```
+
+use core::cmp::{self, Ordering};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop};
+use core::ops::{Index, IndexMut, Range, RangeBounds};
+use core::ptr::{self, NonNull};
+use core::slice;
+
+use crate::alloc::Global;
+use crate::raw_vec::RawVec;
+use crate::vec::{slice_range, Vec};
+
+#[cfg(test)]
+mod tests;
+
+/// A double-ended queue implemented with a growable ring buffer.
+///
+/// The "default" usage of this type as a queue is to use [`push_back`] to add
+/// to the queue, and [`pop_front`] to remove from the queue. [`extend`] and
+/// [`append`]-like bulk operations push onto the back in this manner, and
+/// iterating over `VecDeque` goes front to back.
+///
+/// Since `VecDeque` is a ring buffer, its elements are not necessarily
+/// contiguous in memory. If you want to access the elements as a single
+/// slice, such as for efficient sorting, you can use [`make_contiguous`].
+///
+/// [`push_back`]: VecDeque::push_back
+/// [`pop_front`]: VecDeque::pop_front
+/// [`extend`]: VecDeque::extend
+/// [`append`]: Vec::append
+/// [`make_contiguous`]: VecDeque::make_contiguous
+pub struct VecDeque<T> {
+    // `self[0]`, if it exists, is `buf[head]`.
+    // `head < buf.capacity()`, unless `buf.capacity() == 0` when `head == 0`.
+    head: usize,
+    // The number of initialized elements, starting from the one at `head`
+    // and potentially wrapping around.
+    // If `len == 0`, the exact value of `head` is unimportant.
+    // If `T` is zero-sized, then `self.len <= usize::MAX`, otherwise
+    // `self.len <= isize::MAX as usize`.
+    len: usize,
+    buf: RawVec<T>,
+}
+
+/// Returns the index in the underlying buffer for a given logical element
+/// index, where `logical_index` is the element's index plus the head and
+/// may have wrapped around the capacity once.
+#[inline]
+fn wrap_index(logical_index: usize, capacity: usize) -> usize {
+    debug_assert!(
+        (logical_index == 0 && capacity == 0)
+            || logical_index < capacity
+            || (logical_index - capacity) < capacity
+    );
+    if logical_index >= capacity { logical_index - capacity } else { logical_index }
+}
+
+impl<T> VecDeque<T> {
+    /// Creates an empty deque.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec_deque::VecDeque;
+    ///
+    /// let deque: VecDeque<u32> = VecDeque::new();
+    /// ```
+    #[inline]
+    pub const fn new() -> VecDeque<T> {
+        VecDeque { head: 0, len: 0, buf: RawVec::NEW }
+    }
+
+    /// Creates an empty deque with space for at least `capacity` elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec_deque::VecDeque;
+    ///
+    /// let deque: VecDeque<u32> = VecDeque::with_capacity(10);
+    /// assert!(deque.capacity() >= 10);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> VecDeque<T> {
+        VecDeque { head: 0, len: 0, buf: RawVec::with_capacity(capacity) }
+    }
+
+    #[inline]
+    fn ptr(&self) -> *mut T {
+        self.buf.ptr()
+    }
+
+    /// Moves an element out of the buffer.
+    #[inline]
+    unsafe fn buffer_read(&mut self, off: usize) -> T {
+        unsafe { ptr::read(self.ptr().add(off)) }
+    }
+
+    /// Writes an element into the buffer, moving it.
+    #[inline]
+    unsafe fn buffer_write(&mut self, off: usize, value: T) {
+        unsafe { ptr::write(self.ptr().add(off), value) }
+    }
+
+    /// Returns a raw slice of the buffer for the physical range `range`.
+    #[inline]
+    unsafe fn buffer_range(&self, range: Range<usize>) -> *mut [T] {
+        unsafe { ptr::slice_from_raw_parts_mut(self.ptr().add(range.start), range.end - range.start) }
+    }
+
+    #[inline]
+    fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Returns the physical index for `idx + addend`.
+    #[inline]
+    fn wrap_add(&self, idx: usize, addend: usize) -> usize {
+        wrap_index(idx.wrapping_add(addend), self.capacity())
+    }
+
+    #[inline]
+    fn to_physical_idx(&self, idx: usize) -> usize {
+        self.wrap_add(self.head, idx)
+    }
+
+    /// Returns the physical index for `idx - subtrahend`.
+    #[inline]
+    fn wrap_sub(&self, idx: usize, subtrahend: usize) -> usize {
+        wrap_index(idx.wrapping_sub(subtrahend).wrapping_add(self.capacity()), self.capacity())
+    }
+
+    /// Copies a contiguous block of memory `len` long from `src` to `dst`.
+    #[inline]
+    unsafe fn copy(&mut self, src: usize, dst: usize, len: usize) {
+        unsafe { ptr::copy(self.ptr().add(src), self.ptr().add(dst), len) }
+    }
+
+    /// Copies a contiguous block of memory `len` long from `src` to `dst`.
+    /// The two blocks must not overlap.
+    #[inline]
+    unsafe fn copy_nonoverlapping(&mut self, src: usize, dst: usize, len: usize) {
+        unsafe { ptr::copy_nonoverlapping(self.ptr().add(src), self.ptr().add(dst), len) }
+    }
+
+    /// Copies a potentially wrapping block of memory `len` long from `src`
+    /// to `dst`, where both are physical indices. The blocks may overlap.
+    ///
+    /// The block is copied in pieces that wrap around neither at the source
+    /// nor at the destination. If the destination starts inside the source,
+    /// the pieces are copied back to front, so nothing is overwritten before
+    /// it has been read.
+    unsafe fn wrap_copy(&mut self, src: usize, dst: usize, len: usize) {
+        debug_assert!(cmp::min(self.wrap_sub(dst, src), self.wrap_sub(src, dst)) + len <= self.capacity());
+        if src == dst || len == 0 {
+            return;
+        }
+        let cap = self.capacity();
+        if self.wrap_sub(dst, src) < len {
+            let mut remaining = len;
+            while remaining > 0 {
+                let src_end = self.wrap_add(src, remaining - 1) + 1;
+                let dst_end = self.wrap_add(dst, remaining - 1) + 1;
+                let n = cmp::min(remaining, cmp::min(src_end, dst_end));
+                unsafe { self.copy(src_end - n, dst_end - n, n) };
+                remaining -= n;
+            }
+        } else {
+            let mut done = 0;
+            while done < len {
+                let s = self.wrap_add(src, done);
+                let d = self.wrap_add(dst, done);
+                let n = cmp::min(len - done, cmp::min(cap - s, cap - d));
+                unsafe { self.copy(s, d, n) };
+                done += n;
+            }
+        }
+    }
+
+    /// Fixes up the contents after the buffer grew from `old_capacity`, so
+    /// that wrapped-around elements are in order again.
+    #[inline]
+    unsafe fn handle_capacity_increase(&mut self, old_capacity: usize) {
+        let new_capacity = self.capacity();
+        debug_assert!(new_capacity >= old_capacity);
+
+        // Move the shortest contiguous section of the ring buffer
+        //
+        //    H := head
+        //    L := last element (`self.to_physical_idx(self.len - 1)`)
+        //
+        //    H           L
+        //   [o o o o o o o o ]
+        //    H           L
+        // A [o o o o o o o o . . . . . . . . ]
+        //        L H
+        //   [o o o o o o o o ]
+        //          H           L
+        // B [. . . o o o o o o o o . . . . . ]
+        //              L H
+        //   [o o o o o o o o ]
+        //              L                 H
+        // C [o o o o o o . . . . . . . . o o ]
+
+        if self.head <= old_capacity - self.len {
+            // A: nothing to do.
+        } else {
+            let head_len = old_capacity - self.head;
+            let tail_len = self.len - head_len;
+            if head_len > tail_len && new_capacity - old_capacity >= tail_len {
+                // B: copy the tail to just after the old end.
+                unsafe { self.copy_nonoverlapping(0, old_capacity, tail_len) };
+            } else {
+                // C: move the head to the end of the new buffer.
+                let new_head = new_capacity - head_len;
+                unsafe { self.copy(self.head, new_head, head_len) };
+                self.head = new_head;
+            }
+        }
+        debug_assert!(self.head < self.capacity() || self.capacity() == 0);
+    }
+
+    // Grows a full buffer through `RawVec::reserve`, which uses
+    // `grow_amortized`, then puts the wrapped elements back in order.
+    #[inline(never)]
+    fn grow(&mut self) {
+        debug_assert!(self.is_full());
+        let old_cap = self.capacity();
+        self.buf.reserve(self.len, 1);
+        unsafe { self.handle_capacity_increase(old_cap) };
+        debug_assert!(!self.is_full());
+    }
+
+    /// Returns the number of elements the deque can hold without
+    /// reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        let new_cap = self.len.checked_add(additional).expect("capacity overflow");
+        let old_cap = self.capacity();
+        if new_cap > old_cap {
+            self.buf.reserve(self.len, additional);
+            unsafe { self.handle_capacity_increase(old_cap) };
+        }
+    }
+
+    /// Reserves the minimum capacity for exactly `additional` more elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let new_cap = self.len.checked_add(additional).expect("capacity overflow");
+        let old_cap = self.capacity();
+        if new_cap > old_cap {
+            self.buf.reserve_exact(self.len, additional);
+            unsafe { self.handle_capacity_increase(old_cap) };
+        }
+    }
+
+    /// Shrinks the capacity of the deque as much as possible.
+    ///
+    /// The elements are moved to the start of the buffer first.
+    pub fn shrink_to_fit(&mut self) {
+        if self.capacity() == self.len || mem::size_of::<T>() == 0 {
+            return;
+        }
+        self.make_contiguous();
+        unsafe { self.copy(self.head, 0, self.len) };
+        self.head = 0;
+        self.buf.shrink_to_fit(self.len);
+    }
+
+    /// Shortens the deque, keeping the first `len` elements and dropping
+    /// the rest.
+    ///
+    /// If `len` is greater than the deque's current length, this has no
+    /// effect.
+    pub fn truncate(&mut self, len: usize) {
+        /// Runs the destructor for all items in the slice when it gets
+        /// dropped (normally or during unwinding).
+        struct Dropper<'a, T>(&'a mut [T]);
+
+        impl<'a, T> Drop for Dropper<'a, T> {
+            fn drop(&mut self) {
+                unsafe {
+                    ptr::drop_in_place(self.0);
+                }
+            }
+        }
+
+        // Safe because:
+        //
+        // * Any slice passed to `drop_in_place` is valid; the second case has
+        //   `len <= front.len()` and returning on `len > self.len()` ensures
+        //   `begin <= back.len()` in the first case
+        // * The head of the deque is moved before calling `drop_in_place`,
+        //   so no value is dropped twice if `drop_in_place` panics
+        unsafe {
+            if len >= self.len {
+                return;
+            }
+
+            let (front, back) = self.as_mut_slices();
+            if len > front.len() {
+                let begin = len - front.len();
+                let drop_back = back.get_unchecked_mut(begin..) as *mut _;
+                self.len = len;
+                ptr::drop_in_place(drop_back);
+            } else {
+                let drop_back = back as *mut _;
+                let drop_front = front.get_unchecked_mut(len..) as *mut _;
+                self.len = len;
+
+                // Make sure the second half is dropped even when a destructor
+                // in the first one panics.
+                let _back_dropper = Dropper(&mut *drop_back);
+                ptr::drop_in_place(drop_front);
+            }
+        }
+    }
+
+    /// Returns the number of elements in the deque.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the deque is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all elements, keeping the allocated memory.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+        // Not strictly necessary, but leaves things in a more consistent state.
+        self.head = 0;
+    }
+
+    /// Returns a reference to the element at `index`, or `None` if it is out
+    /// of bounds. Element 0 is the front of the queue.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            let idx = self.to_physical_idx(index);
+            unsafe { Some(&*self.ptr().add(idx)) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at `index`, or `None` if
+    /// it is out of bounds.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            let idx = self.to_physical_idx(index);
+            unsafe { Some(&mut *self.ptr().add(idx)) }
+        } else {
+            None
+        }
+    }
+
+    /// Swaps the elements at indices `i` and `j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn swap(&mut self, i: usize, j: usize) {
+        assert!(i < self.len());
+        assert!(j < self.len());
+        let ri = self.to_physical_idx(i);
+        let rj = self.to_physical_idx(j);
+        unsafe { ptr::swap(self.ptr().add(ri), self.ptr().add(rj)) }
+    }
+
+    /// Returns a reference to the front element, or `None` if the deque is
+    /// empty.
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns a mutable reference to the front element, or `None` if the
+    /// deque is empty.
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    /// Returns a reference to the back element, or `None` if the deque is
+    /// empty.
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len.wrapping_sub(1))
+    }
+
+    /// Returns a mutable reference to the back element, or `None` if the
+    /// deque is empty.
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.len.wrapping_sub(1))
+    }
+
+    /// Prepends an element to the deque.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec_deque::VecDeque;
+    ///
+    /// let mut d = VecDeque::new();
+    /// d.push_front(1);
+    /// d.push_front(2);
+    /// assert_eq!(d.front(), Some(&2));
+    /// ```
+    pub fn push_front(&mut self, value: T) {
+        if self.is_full() {
+            self.grow();
+        }
+
+        self.head = self.wrap_sub(self.head, 1);
+        self.len += 1;
+
+        unsafe {
+            self.buffer_write(self.head, value);
+        }
+    }
+
+    /// Appends an element to the back of the deque.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec_deque::VecDeque;
+    ///
+    /// let mut buf = VecDeque::new();
+    /// buf.push_back(1);
+    /// buf.push_back(3);
+    /// assert_eq!(3, *buf.back().unwrap());
+    /// ```
+    pub fn push_back(&mut self, value: T) {
+        if self.is_full() {
+            self.grow();
+        }
+
+        unsafe { self.buffer_write(self.to_physical_idx(self.len), value) }
+        self.len += 1;
+    }
+
+    /// Removes the first element and returns it, or `None` if the deque is
+    /// empty.
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            let old_head = self.head;
+            self.head = self.to_physical_idx(1);
+            self.len -= 1;
+            Some(unsafe { self.buffer_read(old_head) })
+        }
+    }
+
+    /// Removes the last element and returns it, or `None` if the deque is
+    /// empty.
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.len -= 1;
+            Some(unsafe { self.buffer_read(self.to_physical_idx(self.len)) })
+        }
+    }
+
+    /// Returns `true` if the deque contains an element equal to `x`.
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq<T>,
+    {
+        let (a, b) = self.as_slices();
+        a.contains(x) || b.contains(x)
+    }
+
+    /// Returns the physical ranges holding the logical range `start..end`.
+    fn slice_ranges(&self, start: usize, end: usize) -> (Range<usize>, Range<usize>) {
+        let len = end - start;
+        if len == 0 {
+            (0..0, 0..0)
+        } else {
+            let wrapped_start = self.to_physical_idx(start);
+            let head_len = self.capacity() - wrapped_start;
+            if head_len >= len {
+                (wrapped_start..wrapped_start + len, 0..0)
+            } else {
+                (wrapped_start..self.capacity(), 0..len - head_len)
+            }
+        }
+    }
+
+    /// Returns a pair of slices which contain, in order, the contents of the
+    /// deque.
+    ///
+    /// If [`make_contiguous`] was previously called, all elements of the
+    /// deque will be in the first slice and the second slice will be empty.
+    ///
+    /// [`make_contiguous`]: VecDeque::make_contiguous
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec_deque::VecDeque;
+    ///
+    /// let mut deque = VecDeque::new();
+    /// deque.push_back(0);
+    /// deque.push_back(1);
+    /// deque.push_back(2);
+    /// deque.push_front(10);
+    /// deque.push_front(9);
+    ///
+    /// assert_eq!(deque.as_slices(), (&[9, 10][..], &[0, 1, 2][..]));
+    /// ```
+    #[inline]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (a_range, b_range) = self.slice_ranges(0, self.len);
+        // SAFETY: `slice_ranges` always returns valid ranges into the
+        // physical buffer.
+        unsafe { (&*self.buffer_range(a_range), &*self.buffer_range(b_range)) }
+    }
+
+    /// Returns a pair of mutable slices which contain, in order, the
+    /// contents of the deque.
+    #[inline]
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (a_range, b_range) = self.slice_ranges(0, self.len);
+        unsafe { (&mut *self.buffer_range(a_range), &mut *self.buffer_range(b_range)) }
+    }
+
+    /// Rearranges the internal storage of this deque so it is one contiguous
+    /// slice, which is then returned.
+    ///
+    /// This does not allocate and does not change the order of the elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec_deque::VecDeque;
+    ///
+    /// let mut buf = VecDeque::with_capacity(15);
+    /// buf.push_back(2);
+    /// buf.push_back(1);
+    /// buf.push_front(3);
+    ///
+    /// buf.make_contiguous().sort();
+    /// assert_eq!(buf.as_slices(), (&[1, 2, 3] as &[_], &[] as &[_]));
+    /// ```
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        if mem::size_of::<T>() == 0 {
+            self.head = 0;
+        }
+
+        if self.head <= self.capacity() - self.len {
+            // Already contiguous.
+            return unsafe { &mut *self.buffer_range(self.head..self.head + self.len) };
+        }
+
+        let cap = self.capacity();
+        let free = cap - self.len;
+        let head_len = cap - self.head;
+        let tail_len = self.len - head_len;
+
+        if free >= head_len {
+            // Enough room to move the tail back by `head_len` and copy the
+            // head in front of it.
+            //
+            // from: DEFGH....ABC
+            // to:   ABCDEFGH....
+            unsafe {
+                self.copy(0, head_len, tail_len);
+                self.copy_nonoverlapping(self.head, 0, head_len);
+            }
+            self.head = 0;
+        } else if free >= tail_len {
+            // Enough room to move the head forward to just after the tail,
+            // then copy the tail after it.
+            //
+            // from: FGH....ABCDE
+            // to:   ...ABCDEFGH.
+            unsafe {
+                self.copy(self.head, tail_len, head_len);
+                self.copy_nonoverlapping(0, tail_len + head_len, tail_len);
+            }
+            self.head = tail_len;
+        } else if head_len > tail_len {
+            // Not enough room for either: put the two parts next to each
+            // other, moving the shorter one, then rotate them into place.
+            //
+            // from: HIJK..ABCDEFG
+            // to:   ..HIJKABCDEFG
+            // to:   ..ABCDEFGHIJK
+            unsafe {
+                if free != 0 {
+                    self.copy(0, free, tail_len);
+                }
+                let slice = &mut *self.buffer_range(free..cap);
+                slice.rotate_left(tail_len);
+            }
+            self.head = free;
+        } else {
+            // from: FGHIJK..ABCDE
+            // to:   FGHIJKABCDE..
+            // to:   ABCDEFGHIJK..
+            unsafe {
+                if free != 0 {
+                    self.copy(self.head, tail_len, head_len);
+                }
+                let slice = &mut *self.buffer_range(0..self.len);
+                slice.rotate_right(head_len);
+            }
+            self.head = 0;
+        }
+
+        unsafe { &mut *self.buffer_range(self.head..self.head + self.len) }
+    }
+
+    /// Rotates the deque `n` places to the left, so that the element at
+    /// index `n` becomes the first element.
+    ///
+    /// This takes *O*(min(n, len - n)) time and no extra space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than `len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec_deque::VecDeque;
+    ///
+    /// let mut buf: VecDeque<_> = (0..10).collect();
+    /// buf.rotate_left(3);
+    /// assert_eq!(buf, [3, 4, 5, 6, 7, 8, 9, 0, 1, 2]);
+    /// ```
+    pub fn rotate_left(&mut self, n: usize) {
+        assert!(n <= self.len());
+        let k = self.len - n;
+        if n <= k {
+            unsafe { self.rotate_left_inner(n) }
+        } else {
+            unsafe { self.rotate_right_inner(k) }
+        }
+    }
+
+    /// Rotates the deque `n` places to the right, so that the element at
+    /// index `len - n` becomes the first element.
+    ///
+    /// This takes *O*(min(n, len - n)) time and no extra space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than `len()`.
+    pub fn rotate_right(&mut self, n: usize) {
+        assert!(n <= self.len());
+        let k = self.len - n;
+        if n <= k {
+            unsafe { self.rotate_right_inner(n) }
+        } else {
+            unsafe { self.rotate_left_inner(k) }
+        }
+    }
+
+    // Moves the first `mid` elements to the back, behind the last one.
+    // The other elements stay where they are.
+    unsafe fn rotate_left_inner(&mut self, mid: usize) {
+        debug_assert!(mid * 2 <= self.len());
+        unsafe {
+            self.wrap_copy(self.head, self.to_physical_idx(self.len), mid);
+        }
+        self.head = self.to_physical_idx(mid);
+    }
+
+    // Moves the last `k` elements to the front, before the first one.
+    unsafe fn rotate_right_inner(&mut self, k: usize) {
+        debug_assert!(k * 2 <= self.len());
+        self.head = self.wrap_sub(self.head, k);
+        unsafe {
+            self.wrap_copy(self.to_physical_idx(self.len), self.head, k);
+        }
+    }
+
+    /// Returns a front-to-back iterator.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (a, b) = self.as_slices();
+        Iter { i1: a.iter(), i2: b.iter() }
+    }
+
+    /// Returns a front-to-back iterator that returns mutable references.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (a, b) = self.as_mut_slices();
+        IterMut { i1: a.iter_mut(), i2: b.iter_mut() }
+    }
+
+    /// Creates an iterator that covers the specified range in the deque.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the deque.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec_deque::VecDeque;
+    ///
+    /// let deque: VecDeque<_> = [1, 2, 3].into_iter().collect();
+    /// let range = deque.range(2..).copied().collect::<VecDeque<_>>();
+    /// assert_eq!(range, [3]);
+    /// ```
+    pub fn range<R>(&self, range: R) -> Iter<'_, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let Range { start, end } = slice_range(range, self.len);
+        let (a_range, b_range) = self.slice_ranges(start, end);
+        unsafe {
+            let a = &*self.buffer_range(a_range);
+            let b = &*self.buffer_range(b_range);
+            Iter { i1: a.iter(), i2: b.iter() }
+        }
+    }
+
+    /// Creates an iterator that covers the specified mutable range in the
+    /// deque.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the deque.
+    pub fn range_mut<R>(&mut self, range: R) -> IterMut<'_, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let Range { start, end } = slice_range(range, self.len);
+        let (a_range, b_range) = self.slice_ranges(start, end);
+        unsafe {
+            let a = &mut *self.buffer_range(a_range);
+            let b = &mut *self.buffer_range(b_range);
+            IterMut { i1: a.iter_mut(), i2: b.iter_mut() }
+        }
+    }
+
+    /// Removes the specified range from the deque in bulk, returning all
+    /// removed elements as an iterator. If the iterator is dropped before
+    /// being fully consumed, it drops the remaining removed elements.
+    ///
+    /// The range may wrap around the end of the buffer. Once the iterator is
+    /// gone, whichever of the elements before and after the range is shorter
+    /// is moved to close the gap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the deque.
+    ///
+    /// # Leaking
+    ///
+    /// If the returned iterator goes out of scope without being dropped (due
+    /// to [`mem::forget`], for example), the deque may have lost and leaked
+    /// elements arbitrarily, including elements outside the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec_deque::VecDeque;
+    ///
+    /// let mut deque: VecDeque<_> = [1, 2, 3].into_iter().collect();
+    /// let drained = deque.drain(2..).collect::<VecDeque<_>>();
+    /// assert_eq!(drained, [3]);
+    /// assert_eq!(deque, [1, 2]);
+    ///
+    /// // A full range clears all contents, like `clear()` does
+    /// deque.drain(..);
+    /// assert!(deque.is_empty());
+    /// ```
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let Range { start, end } = slice_range(range, self.len);
+        let drain_len = end - start;
+        let tail_len = self.len - end;
+
+        // Until the `Drain` is dropped, the deque ends where the range
+        // starts, so leaking the `Drain` leaks the rest instead of exposing
+        // moved-out elements.
+        self.len = start;
+        Drain {
+            deque: NonNull::from(self),
+            drain_len,
+            idx: start,
+            tail_len,
+            remaining: drain_len,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone> VecDeque<T> {
+    /// Modifies the deque in-place so that `len()` is equal to `new_len`,
+    /// either by removing excess elements from the back or by appending
+    /// clones of `value` to the back.
+    pub fn resize(&mut self, new_len: usize, value: T) {
+        if new_len > self.len() {
+            let extra = new_len - self.len();
+            self.reserve(extra);
+            for _ in 0..extra {
+                self.push_back(value.clone());
+            }
+        } else {
+            self.truncate(new_len);
+        }
+    }
+}
+
+impl<T> Drop for VecDeque<T> {
+    fn drop(&mut self) {
+        /// Runs the destructor for all items in the slice when it gets
+        /// dropped (normally or during unwinding).
+        struct Dropper<'a, T>(&'a mut [T]);
+
+        impl<'a, T> Drop for Dropper<'a, T> {
+            fn drop(&mut self) {
+                unsafe {
+                    ptr::drop_in_place(self.0);
+                }
+            }
+        }
+
+        let (front, back) = self.as_mut_slices();
+        unsafe {
+            let _back_dropper = Dropper(back);
+            // use drop for [T]
+            ptr::drop_in_place(front);
+        }
+        // RawVec handles deallocation
+    }
+}
+
+impl<T> Default for VecDeque<T> {
+    /// Creates an empty deque.
+    #[inline]
+    fn default() -> VecDeque<T> {
+        VecDeque::new()
+    }
+}
+
+impl<T: Clone> Clone for VecDeque<T> {
+    fn clone(&self) -> Self {
+        let mut deq = Self::with_capacity(self.len());
+        deq.extend(self.iter().cloned());
+        deq
+    }
+}
+
+impl<T: PartialEq> PartialEq for VecDeque<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for VecDeque<T> {}
+
+impl<T: PartialEq<U>, U> PartialEq<Vec<U>> for VecDeque<T> {
+    fn eq(&self, other: &Vec<U>) -> bool {
+        self.len == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<&[U]> for VecDeque<T> {
+    fn eq(&self, other: &&[U]) -> bool {
+        self.len == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for VecDeque<T> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        self.len == N && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for VecDeque<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for VecDeque<T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for VecDeque<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        // It's not possible to use Hash::hash_slice on slices returned by
+        // as_slices, as it can vary depending on the internal layout of the
+        // buffer while two deques compare equal.
+        self.iter().for_each(|elem| elem.hash(state));
+    }
+}
+
+impl<T> Index<usize> for VecDeque<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("Out of bounds access")
+    }
+}
+
+impl<T> IndexMut<usize> for VecDeque<T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("Out of bounds access")
+    }
+}
+
+impl<T> FromIterator<T> for VecDeque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> VecDeque<T> {
+        let iterator = iter.into_iter();
+        let (lower, _) = iterator.size_hint();
+        let mut deq = VecDeque::with_capacity(lower);
+        deq.extend(iterator);
+        deq
+    }
+}
+
+impl<T> Extend<T> for VecDeque<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push_back(value);
+        }
+    }
+}
+
+impl<'a, T: 'a + Copy> Extend<&'a T> for VecDeque<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for VecDeque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T> From<Vec<T>> for VecDeque<T> {
+    /// Turns a [`Vec<T>`] into a [`VecDeque<T>`].
+    ///
+    /// This reuses the vector's buffer: no elements are copied and nothing
+    /// is allocated.
+    fn from(other: Vec<T>) -> Self {
+        let (ptr, len, cap) = other.into_raw_parts();
+        VecDeque { head: 0, len, buf: unsafe { RawVec::from_raw_parts(ptr, cap) } }
+    }
+}
+
+impl<T> From<VecDeque<T>> for Vec<T> {
+    /// Turns a [`VecDeque<T>`] into a [`Vec<T>`].
+    ///
+    /// This never reallocates, but moves the elements to the start of the
+    /// buffer if they are not there already.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rusty_collections::vec::Vec;
+    /// use rusty_collections::vec_deque::VecDeque;
+    ///
+    /// let mut deque: VecDeque<_> = (1..5).collect();
+    /// deque.push_front(9);
+    /// deque.push_front(8);
+    /// let ptr = deque.as_slices().1.as_ptr();
+    /// let vec = Vec::from(deque);
+    /// assert_eq!(vec, [8, 9, 1, 2, 3, 4]);
+    /// assert_eq!(vec.as_ptr(), ptr);
+    /// ```
+    fn from(mut other: VecDeque<T>) -> Self {
+        other.make_contiguous();
+        unsafe {
+            let other = ManuallyDrop::new(other);
+            let buf = other.buf.ptr();
+            let len = other.len();
+            let cap = other.capacity();
+            if other.head != 0 {
+                ptr::copy(buf.add(other.head), buf, len);
+            }
+            Vec::from_raw_parts_unchecked_in(buf, len, cap, Global)
+        }
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for VecDeque<T> {
+    fn from(arr: [T; N]) -> Self {
+        VecDeque::from(Vec::from(arr))
+    }
+}
+
+impl<T> IntoIterator for VecDeque<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Consumes the deque into a front-to-back iterator yielding elements by
+    /// value.
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { inner: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a VecDeque<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut VecDeque<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// An iterator over the elements of a `VecDeque`.
+///
+/// This `struct` is created by the [`iter`] and [`range`] methods on
+/// [`VecDeque`].
+///
+/// [`iter`]: VecDeque::iter
+/// [`range`]: VecDeque::range
+#[derive(Clone)]
+pub struct Iter<'a, T> {
+    i1: slice::Iter<'a, T>,
+    i2: slice::Iter<'a, T>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Iter").field(&self.i1.as_slice()).field(&self.i2.as_slice()).finish()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        match self.i1.next() {
+            Some(val) => Some(val),
+            None => {
+                // Most of the time the iterator is used up to the end of
+                // the first slice only once, so swap the halves.
+                mem::swap(&mut self.i1, &mut self.i2);
+                self.i1.next()
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+
+    fn fold<Acc, F>(self, accum: Acc, mut f: F) -> Acc
+    where
+        F: FnMut(Acc, Self::Item) -> Acc,
+    {
+        let accum = self.i1.fold(accum, &mut f);
+        self.i2.fold(accum, &mut f)
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        match self.i2.next_back() {
+            Some(val) => Some(val),
+            None => {
+                mem::swap(&mut self.i1, &mut self.i2);
+                self.i2.next_back()
+            }
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {
+    fn len(&self) -> usize {
+        self.i1.len() + self.i2.len()
+    }
+}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// A mutable iterator over the elements of a `VecDeque`.
+///
+/// This `struct` is created by the [`iter_mut`] and [`range_mut`] methods
+/// on [`VecDeque`].
+///
+/// [`iter_mut`]: VecDeque::iter_mut
+/// [`range_mut`]: VecDeque::range_mut
+pub struct IterMut<'a, T> {
+    i1: slice::IterMut<'a, T>,
+    i2: slice::IterMut<'a, T>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for IterMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IterMut").field(&self.i1.as_slice()).field(&self.i2.as_slice()).finish()
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        match self.i1.next() {
+            Some(val) => Some(val),
+            None => {
+                mem::swap(&mut self.i1, &mut self.i2);
+                self.i1.next()
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        match self.i2.next_back() {
+            Some(val) => Some(val),
+            None => {
+                mem::swap(&mut self.i1, &mut self.i2);
+                self.i2.next_back()
+            }
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {
+    fn len(&self) -> usize {
+        self.i1.len() + self.i2.len()
+    }
+}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// An owning iterator over the elements of a `VecDeque`.
+///
+/// This `struct` is created by the [`into_iter`] method on [`VecDeque`]
+/// (provided by the [`IntoIterator`] trait).
+///
+/// [`into_iter`]: VecDeque::into_iter
+#[derive(Clone)]
+pub struct IntoIter<T> {
+    inner: VecDeque<T>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.inner).finish()
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.inner.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.inner.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+/// A draining iterator over the elements of a `VecDeque`.
+///
+/// This `struct` is created by the [`drain`] method on [`VecDeque`].
+///
+/// [`drain`]: VecDeque::drain
+pub struct Drain<'a, T: 'a> {
+    // We can't just use a &mut VecDeque<T>, as that would make Drain
+    // invariant over T and we want it to be covariant instead.
+    deque: NonNull<VecDeque<T>>,
+    // drain_start is stored in deque.len
+    drain_len: usize,
+    // index into the logical array, not the physical one (always lies in
+    // [0..deque.len))
+    idx: usize,
+    // number of elements after the drain range
+    tail_len: usize,
+    remaining: usize,
+    // Needed to make Drain covariant over T
+    _marker: PhantomData<&'a T>,
+}
+
+impl<T> Drain<'_, T> {
+    /// Returns the two slices holding the elements not yielded yet.
+    unsafe fn as_slices(&self) -> (*mut [T], *mut [T]) {
+        unsafe {
+            let deque = self.deque.as_ref();
+            let (a_range, b_range) = deque.slice_ranges(self.idx, self.idx + self.remaining);
+            (deque.buffer_range(a_range), deque.buffer_range(b_range))
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Drain<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain")
+            .field(&self.drain_len)
+            .field(&self.idx)
+            .field(&self.tail_len)
+            .field(&self.remaining)
+            .finish()
+    }
+}
+
+unsafe impl<T: Sync> Sync for Drain<'_, T> {}
+unsafe impl<T: Send> Send for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        struct DropGuard<'r, 'a, T>(&'r mut Drain<'a, T>);
+
+        impl<'r, 'a, T> Drop for DropGuard<'r, 'a, T> {
+            fn drop(&mut self) {
+                if self.0.remaining != 0 {
+                    unsafe {
+                        // SAFETY: We just checked that `self.remaining != 0`.
+                        let (front, back) = self.0.as_slices();
+                        // The front has already panicked, so only the back
+                        // can still need dropping.
+                        self.0.remaining = 0;
+                        ptr::drop_in_place(front);
+                        ptr::drop_in_place(back);
+                    }
+                }
+
+                let source_deque = unsafe { self.0.deque.as_mut() };
+
+                let drain_start = source_deque.len();
+                let drain_len = self.0.drain_len;
+                let drain_end = drain_start + drain_len;
+
+                let orig_len = drain_end + self.0.tail_len;
+
+                if mem::size_of::<T>() == 0 {
+                    // Nothing to move, just fix the length.
+                    source_deque.len = orig_len - drain_len;
+                    return;
+                }
+
+                let head_len = drain_start;
+                let tail_len = self.0.tail_len;
+
+                let new_len = orig_len - drain_len;
+
+                if new_len == 0 {
+                    // Special case: the deque is empty, so reset it.
+                    source_deque.head = 0;
+                } else if head_len < tail_len {
+                    // Fewer elements before the drained range: move them
+                    // forward to close the gap.
+                    unsafe {
+                        source_deque.wrap_copy(
+                            source_deque.head,
+                            source_deque.to_physical_idx(drain_len),
+                            head_len,
+                        );
+                    }
+                    source_deque.head = source_deque.to_physical_idx(drain_len);
+                } else {
+                    // Fewer elements after it: move them back.
+                    unsafe {
+                        source_deque.wrap_copy(
+                            source_deque.to_physical_idx(head_len + drain_len),
+                            source_deque.to_physical_idx(head_len),
+                            tail_len,
+                        );
+                    }
+                }
+                source_deque.len = new_len;
+            }
+        }
+
+        let guard = DropGuard(self);
+        if guard.0.remaining != 0 {
+            unsafe {
+                let (front, back) = guard.0.as_slices();
+                // Account for the front before dropping it, so the guard only
+                // drops the back if a destructor in the front panics.
+                let front_len = (&*front).len();
+                guard.0.idx += front_len;
+                guard.0.remaining -= front_len;
+                ptr::drop_in_place(front);
+                guard.0.remaining = 0;
+                ptr::drop_in_place(back);
+            }
+        }
+
+        // Dropping `guard` handles moving the remaining elements into place.
+    }
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let wrapped_idx = unsafe { self.deque.as_ref().to_physical_idx(self.idx) };
+        self.idx += 1;
+        self.remaining -= 1;
+        Some(unsafe { self.deque.as_mut().buffer_read(wrapped_idx) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let wrapped_idx = unsafe { self.deque.as_ref().to_physical_idx(self.idx + self.remaining) };
+        Some(unsafe { self.deque.as_mut().buffer_read(wrapped_idx) })
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
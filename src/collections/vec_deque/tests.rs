@@ -0,0 +1,192 @@
+use super::*;
+
+use std::collections::VecDeque as StdVecDeque;
+use std::vec::Vec as StdVec;
+
+use crate::test_util::{drops, Counted, Rng};
+
+fn contents<T: Clone>(d: &VecDeque<T>) -> StdVec<T> {
+    let (a, b) = d.as_slices();
+    a.iter().chain(b).cloned().collect()
+}
+
+// Applies one random operation to both deques and checks that they agree
+// on what it returned. Pushing at both ends makes the elements wrap around
+// the end of the buffer most of the time.
+fn step(rng: &mut Rng, d: &mut VecDeque<u32>, m: &mut StdVecDeque<u32>) {
+    let x = rng.next() as u32;
+    match rng.below(14) {
+        0..=2 => {
+            d.push_back(x);
+            m.push_back(x);
+        }
+        3..=5 => {
+            d.push_front(x);
+            m.push_front(x);
+        }
+        6 => assert_eq!(d.pop_front(), m.pop_front()),
+        7 => assert_eq!(d.pop_back(), m.pop_back()),
+        8 => {
+            let start = rng.below(m.len() + 1);
+            let end = start + rng.below(m.len() - start + 1);
+            let drained: StdVec<u32> = d.drain(start..end).collect();
+            assert_eq!(drained, m.drain(start..end).collect::<StdVec<_>>());
+        }
+        9 => {
+            let n = rng.below(m.len() + 1);
+            if rng.below(2) == 0 {
+                d.rotate_left(n);
+                m.rotate_left(n);
+            } else {
+                d.rotate_right(n);
+                m.rotate_right(n);
+            }
+        }
+        10 => {
+            let len = rng.below(m.len() + 4);
+            d.resize(len, x);
+            m.resize(len, x);
+        }
+        11 if !m.is_empty() => {
+            let (i, j) = (rng.below(m.len()), rng.below(m.len()));
+            d.swap(i, j);
+            m.swap(i, j);
+            assert_eq!(d.get(i), m.get(i));
+        }
+        12 => {
+            assert_eq!(d.make_contiguous(), m.make_contiguous());
+            assert!(d.as_slices().1.is_empty());
+        }
+        _ => {
+            d.shrink_to_fit();
+            assert_eq!(d.capacity(), m.len());
+            let extra = rng.below(20);
+            d.reserve(extra);
+            assert!(d.capacity() >= m.len() + extra);
+        }
+    }
+    assert_eq!(d.len(), m.len());
+    assert_eq!(contents(d), m.iter().copied().collect::<StdVec<_>>());
+    assert!(d.iter().eq(m.iter()));
+    assert!(d.iter().rev().eq(m.iter().rev()));
+    assert_eq!(d.front(), m.front());
+    assert_eq!(d.back(), m.back());
+}
+
+#[test]
+fn random_operations_match_std() {
+    for seed in 0..200 {
+        let mut rng = Rng::new(seed);
+        let mut d = VecDeque::new();
+        let mut m = StdVecDeque::new();
+        for _ in 0..200 {
+            step(&mut rng, &mut d, &mut m);
+        }
+    }
+}
+
+// Returns a deque holding `0..len` whose elements start `head` slots into a
+// buffer of `cap` slots, wrapping around its end.
+fn wrapped(len: u32, head: usize, cap: usize) -> VecDeque<u32> {
+    let mut d = VecDeque::with_capacity(cap);
+    let cap = d.capacity();
+    for _ in 0..head {
+        d.push_back(0);
+        d.pop_front();
+    }
+    d.extend(0..len);
+    assert_eq!(d.capacity(), cap, "the deque must not grow");
+    d
+}
+
+#[test]
+fn every_wrap_position_converts_to_a_vec_in_place() {
+    for head in 0..16 {
+        for len in 0..=16 {
+            let d = wrapped(len, head, 16);
+            let cap = d.capacity();
+            let buf = d.buf.ptr() as *const u32;
+            let vec = Vec::from(d);
+            assert!(vec.iter().copied().eq(0..len));
+            assert_eq!(vec.as_ptr(), buf);
+            assert_eq!(vec.capacity(), cap);
+
+            let d = VecDeque::from(vec);
+            assert_eq!(d.buf.ptr() as *const u32, buf);
+            assert!(d.iter().copied().eq(0..len));
+        }
+    }
+}
+
+#[test]
+fn ranges_and_slices_across_the_wrap() {
+    let mut d = wrapped(10, 12, 16);
+    let (a, b) = d.as_slices();
+    assert_eq!((a.len(), b.len()), (4, 6));
+    assert!(d.range(2..7).copied().eq(2..7));
+    assert!(d.range(..).rev().copied().eq((0..10).rev()));
+    for x in d.range_mut(3..=5) {
+        *x *= 10;
+    }
+    assert_eq!(d, [0, 1, 2, 30, 40, 50, 6, 7, 8, 9]);
+    for x in d.iter_mut().rev().take(2) {
+        *x = 0;
+    }
+    assert_eq!(contents(&d), [0, 1, 2, 30, 40, 50, 6, 7, 0, 0]);
+    assert!(d.contains(&50));
+    assert!(!d.contains(&5));
+    d.truncate(5);
+    assert_eq!(d, [0, 1, 2, 30, 40]);
+}
+
+#[test]
+fn drain_dropped_early_or_leaked() {
+    let mut d: VecDeque<String> = (0..10).map(|i| i.to_string()).collect();
+    for _ in 0..3 {
+        let x = d.pop_back().unwrap();
+        d.push_front(x);
+    }
+    let mut drain = d.drain(2..8);
+    assert_eq!(drain.next().as_deref(), Some("9"));
+    assert_eq!(drain.next_back().as_deref(), Some("4"));
+    drop(drain);
+    assert_eq!(contents(&d), ["7", "8", "5", "6"]);
+
+    // A leaked `Drain` may leak elements, but never exposes moved ones.
+    let mut d: VecDeque<String> = (0..10).map(|i| i.to_string()).collect();
+    mem::forget(d.drain(3..6));
+    assert!(d.len() <= 7);
+    assert!(d.iter().all(|s| s.parse::<u32>().is_ok()));
+}
+
+#[test]
+fn zero_sized_types() {
+    let mut d = VecDeque::new();
+    for _ in 0..100 {
+        d.push_front(());
+        d.push_back(());
+    }
+    assert_eq!(d.len(), 200);
+    assert_eq!(d.drain(10..20).count(), 10);
+    d.rotate_left(7);
+    d.shrink_to_fit();
+    assert_eq!(d.pop_back(), Some(()));
+    assert_eq!(Vec::from(d).len(), 189);
+}
+
+#[test]
+fn each_element_is_dropped_once() {
+    for len in 0..12 {
+        let (_, n) = drops(|| {
+            let mut d: VecDeque<Counted> = VecDeque::with_capacity(8);
+            for i in 0..len {
+                if i % 2 == 0 { d.push_back(Counted(0)) } else { d.push_front(Counted(0)) }
+            }
+            d.truncate(len * 3 / 4);
+            drop(d.drain(len / 4..len / 2));
+            let mut iter = d.into_iter();
+            drop(iter.next());
+        });
+        assert_eq!(n, len);
+    }
+}